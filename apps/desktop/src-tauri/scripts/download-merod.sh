@@ -7,6 +7,7 @@ SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
 TAURI_DIR="$(cd "$SCRIPT_DIR/.." && pwd)"
 MEROD_DIR="$TAURI_DIR/merod"
 MEROD_BINARY="$MEROD_DIR/merod"
+MEROD_MANIFEST="$MEROD_DIR/merod.manifest.json"
 
 # Detect architecture
 ARCH=$(uname -m)
@@ -55,7 +56,7 @@ echo "Downloading merod $VERSION from: $URL"
 mkdir -p "$MEROD_DIR"
 
 # Remove old binary so we always get the configured VERSION
-rm -f "$MEROD_BINARY"
+rm -f "$MEROD_BINARY" "$MEROD_MANIFEST"
 
 # Download and extract
 TEMP_TAR="$MEROD_DIR/temp.tar.gz"
@@ -69,4 +70,8 @@ rm "$TEMP_TAR"
 # Make executable
 chmod +x "$MEROD_BINARY"
 
+# Write the integrity manifest checked by the app before it runs merod. Anything that changes
+# the binary afterwards (codesign) must run write-merod-manifest.sh again.
+MEROD_VERSION="$VERSION" "$SCRIPT_DIR/write-merod-manifest.sh"
+
 echo "Successfully downloaded merod to $MEROD_BINARY"
@@ -0,0 +1,74 @@
+#!/bin/bash
+
+# Write the integrity manifest the app checks before it runs the bundled merod binary.
+# Run it after anything that changes the binary (such as codesign) and before `tauri build`.
+# With --check, verify that an existing manifest matches the binary instead.
+set -e
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+TAURI_DIR="$(cd "$SCRIPT_DIR/.." && pwd)"
+MEROD_DIR="$TAURI_DIR/merod"
+MEROD_BINARY="$MEROD_DIR/merod"
+MEROD_MANIFEST="$MEROD_DIR/merod.manifest.json"
+
+if [ ! -f "$MEROD_BINARY" ]; then
+  echo "merod binary not found at $MEROD_BINARY"
+  exit 1
+fi
+
+if command -v sha256sum >/dev/null 2>&1; then
+  SHA256=$(sha256sum "$MEROD_BINARY" | cut -d' ' -f1)
+else
+  SHA256=$(shasum -a 256 "$MEROD_BINARY" | cut -d' ' -f1)
+fi
+SIZE=$(wc -c < "$MEROD_BINARY" | tr -d ' ')
+
+if [ "$1" = "--check" ]; then
+  if [ ! -f "$MEROD_MANIFEST" ]; then
+    echo "merod manifest not found at $MEROD_MANIFEST"
+    exit 1
+  fi
+  EXPECTED_SHA256=$(grep -o '"sha256": *"[^"]*"' "$MEROD_MANIFEST" | cut -d'"' -f4)
+  EXPECTED_SIZE=$(grep -o '"size": *[0-9]*' "$MEROD_MANIFEST" | grep -o '[0-9]*$')
+  if [ "$EXPECTED_SHA256" != "$SHA256" ] || [ "$EXPECTED_SIZE" != "$SIZE" ]; then
+    echo "merod manifest does not match $MEROD_BINARY"
+    echo "  manifest: sha256 $EXPECTED_SHA256, $EXPECTED_SIZE bytes"
+    echo "  binary:   sha256 $SHA256, $SIZE bytes"
+    exit 1
+  fi
+  echo "merod manifest matches $MEROD_BINARY (sha256 $SHA256)"
+  exit 0
+fi
+
+# Keep the version of a manifest being rewritten (e.g. after codesign)
+VERSION="${MEROD_VERSION:-}"
+if [ -z "$VERSION" ] && [ -f "$MEROD_MANIFEST" ]; then
+  VERSION=$(grep -o '"version": *"[^"]*"' "$MEROD_MANIFEST" | cut -d'"' -f4)
+fi
+VERSION_FIELD=""
+if [ -n "$VERSION" ]; then
+  VERSION_FIELD="
+  \"version\": \"$VERSION\","
+fi
+
+# Optionally sign with the updater key (MEROD_SIGNATURE_FILE is the .sig written by
+# `tauri signer sign` for the final binary), so the app can check the binary against its
+# updater public key
+SIGNATURE_FIELD=""
+if [ -n "$MEROD_SIGNATURE_FILE" ]; then
+  if [ ! -f "$MEROD_SIGNATURE_FILE" ]; then
+    echo "Signature file not found: $MEROD_SIGNATURE_FILE"
+    exit 1
+  fi
+  SIGNATURE_FIELD=",
+  \"signature\": \"$(tr -d '\n' < "$MEROD_SIGNATURE_FILE")\""
+fi
+
+cat > "$MEROD_MANIFEST" <<MANIFEST
+{$VERSION_FIELD
+  "sha256": "$SHA256",
+  "size": $SIZE$SIGNATURE_FIELD
+}
+MANIFEST
+
+echo "Wrote integrity manifest to $MEROD_MANIFEST (sha256 $SHA256)"
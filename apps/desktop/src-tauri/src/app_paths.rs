@@ -0,0 +1,96 @@
+//! Where the app keeps its data and bundled resources. Resolved from a running app or straight
+//! from the Tauri config, so headless mode finds the same files without creating a window.
+
+use std::path::{Path, PathBuf};
+
+use log::info;
+
+/// The app's data directory, bundled resources and the key its release artifacts are signed with
+#[derive(Debug, Clone)]
+pub struct AppPaths {
+    app_data_dir: Option<PathBuf>,
+    resource_dir: Option<PathBuf>,
+    updater_pubkey: String,
+}
+
+impl AppPaths {
+    pub fn from_handle(app_handle: &tauri::AppHandle) -> Self {
+        let resolver = app_handle.path_resolver();
+        Self {
+            app_data_dir: resolver.app_data_dir(),
+            resource_dir: resolver.resource_dir(),
+            updater_pubkey: app_handle.config().tauri.updater.pubkey.clone(),
+        }
+    }
+
+    /// Paths for a process that never builds the Tauri app (headless mode)
+    pub fn from_context<A: tauri::Assets>(context: &tauri::Context<A>) -> Self {
+        let config = context.config();
+        Self {
+            app_data_dir: tauri::api::path::app_data_dir(config),
+            resource_dir: tauri::api::path::resource_dir(context.package_info(), &tauri::Env::default()),
+            updater_pubkey: config.tauri.updater.pubkey.clone(),
+        }
+    }
+
+    /// The app data directory, created if it doesn't exist yet
+    pub fn app_data_dir(&self) -> Result<PathBuf, String> {
+        let app_data_dir = self.app_data_dir.clone().ok_or("Failed to get app data directory")?;
+        std::fs::create_dir_all(&app_data_dir)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+        Ok(app_data_dir)
+    }
+
+    pub fn resolve_resource(&self, resource: &str) -> Option<PathBuf> {
+        self.resource_dir.as_ref().map(|dir| dir.join(resource))
+    }
+
+    pub fn updater_pubkey(&self) -> &str {
+        &self.updater_pubkey
+    }
+
+    /// The bundled merod binary
+    pub fn bundled_merod(&self) -> Result<PathBuf, String> {
+        let resource_path = self
+            .resolve_resource("merod/merod")
+            .ok_or("Failed to resolve merod resource")?;
+        if !resource_path.exists() {
+            return Err(format!("Merod resource not found at {:?}", resource_path));
+        }
+        Ok(resource_path)
+    }
+
+    /// SHA-256 of every merod binary the app vouches for: the bundled one, when it passes its
+    /// integrity check, and each installed version.
+    pub fn trusted_merod_sha256s(&self) -> Vec<String> {
+        let mut hashes = Vec::new();
+        if let Ok(binary) = self.bundled_merod() {
+            if crate::verify_bundled_merod_with(self, &binary).is_ok() {
+                hashes.extend(crate::merod_integrity::binary_sha256(&binary).ok());
+            }
+        }
+        if let Ok(app_data_dir) = self.app_data_dir() {
+            hashes.extend(
+                crate::merod_manager::list_installed(&app_data_dir)
+                    .into_iter()
+                    .map(|installed| installed.binary_sha256),
+            );
+        }
+        hashes
+    }
+
+    /// The merod binary for a node: the installed version it is pinned to, if any, otherwise
+    /// the bundled binary. Either is verified before it is returned.
+    pub fn node_merod_binary(&self, home_dir: &Path, node_name: &str) -> Result<PathBuf, String> {
+        if let Ok(app_data_dir) = self.app_data_dir() {
+            if let Some(installed) = crate::merod_manager::pinned_binary(&app_data_dir, home_dir, node_name) {
+                info!("[Merod] Using merod {} for node '{}'", installed.version, node_name);
+                crate::verify_installed_merod(&installed)?;
+                return Ok(installed.path);
+            }
+        }
+        let binary = self.bundled_merod()?;
+        crate::verify_bundled_merod_with(self, &binary)?;
+        Ok(binary)
+    }
+}
@@ -0,0 +1,96 @@
+//! The app's own log, written to the console and to `backend.log` in the app log directory so
+//! it can be attached to support bundles.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const BACKEND_LOG_FILE: &str = "backend.log";
+/// Above this size the log is moved to `backend.log.old` at startup.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+static LOG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Where log records are echoed besides the log file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Console {
+    Stderr,
+    /// Headless mode, where the log is the process's output for service managers and CI.
+    Stdout,
+}
+
+/// Writes every log record to the console and, if it could be opened, the log file.
+struct Tee {
+    console: Console,
+    file: Option<File>,
+}
+
+impl Tee {
+    fn console(&self) -> Box<dyn Write> {
+        match self.console {
+            Console::Stderr => Box::new(std::io::stderr()),
+            Console::Stdout => Box::new(std::io::stdout()),
+        }
+    }
+}
+
+impl Write for Tee {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let _ = self.console().write_all(buf);
+        if let Some(file) = &mut self.file {
+            let _ = file.write_all(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(file) = &mut self.file {
+            file.flush()?;
+        }
+        self.console().flush()
+    }
+}
+
+fn open_log_file(log_dir: &Path) -> std::io::Result<(File, PathBuf)> {
+    std::fs::create_dir_all(log_dir)?;
+    let path = log_dir.join(BACKEND_LOG_FILE);
+    if std::fs::metadata(&path).map(|m| m.len() > MAX_LOG_BYTES).unwrap_or(false) {
+        let _ = std::fs::rename(&path, log_dir.join(format!("{}.old", BACKEND_LOG_FILE)));
+    }
+    let file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+    Ok((file, path))
+}
+
+/// Initializes the logger. Reads RUST_LOG; defaults to debug in debug builds, info otherwise.
+pub fn init(log_dir: Option<PathBuf>, console: Console) {
+    let file = log_dir.as_deref().and_then(|dir| match open_log_file(dir) {
+        Ok((file, path)) => {
+            let _ = LOG_PATH.set(path);
+            Some(file)
+        }
+        Err(e) => {
+            eprintln!("[Calimero] Failed to open the backend log in {:?}: {}", dir, e);
+            None
+        }
+    });
+
+    env_logger::Builder::from_default_env()
+        .filter_level({
+            #[cfg(debug_assertions)]
+            {
+                log::LevelFilter::Debug
+            }
+            #[cfg(not(debug_assertions))]
+            {
+                log::LevelFilter::Info
+            }
+        })
+        .target(env_logger::Target::Pipe(Box::new(Tee { console, file })))
+        .init();
+}
+
+/// Path of this session's backend log, if file logging is active.
+pub fn log_path() -> Option<&'static Path> {
+    LOG_PATH.get().map(PathBuf::as_path)
+}
@@ -0,0 +1,173 @@
+//! Per-window IPC capabilities.
+//!
+//! Every window label is mapped to a role, and every command invocation is checked against
+//! the role of the calling window before it reaches the command handler. The main dashboard
+//! may call everything; third-party app windows only get the node proxy and a few helpers
+//! that cannot touch local processes or files. The node an app window may proxy to is
+//! recorded here when the window is created, not taken from the page.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use log::{debug, warn};
+use serde::Serialize;
+use tauri::{Invoke, Manager, Runtime};
+
+/// Label of the dashboard window declared in tauri.conf.json.
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+/// Event emitted to the main window whenever a command is denied.
+pub const CAPABILITY_DENIED_EVENT: &str = "capability-denied";
+
+/// Commands an app window opened via `create_app_window` may call.
+const APP_WINDOW_COMMANDS: &[&str] = &[
+    "proxy_http_request",
+    "focus_window",
+    "open_external_link",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowRole {
+    /// The dashboard window; may call every registered command.
+    Main,
+    /// A third-party app window; limited to `APP_WINDOW_COMMANDS`.
+    App,
+}
+
+impl WindowRole {
+    pub fn allows(self, command: &str) -> bool {
+        match self {
+            WindowRole::Main => true,
+            WindowRole::App => APP_WINDOW_COMMANDS.contains(&command),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CapabilityDenial {
+    window: String,
+    command: String,
+}
+
+#[derive(Debug, Clone)]
+struct WindowEntry {
+    role: WindowRole,
+    /// The node URL an app window was opened for; its proxy requests may only go there.
+    node_url: Option<String>,
+}
+
+/// Window label -> role. Labels that were never registered (other than `main`) get no access.
+#[derive(Default)]
+pub struct WindowCapabilities(Mutex<HashMap<String, WindowEntry>>);
+
+impl WindowCapabilities {
+    pub fn register(&self, label: &str, role: WindowRole, node_url: Option<&str>) {
+        if let Ok(mut roles) = self.0.lock() {
+            roles.insert(label.to_string(), WindowEntry { role, node_url: node_url.map(str::to_string) });
+        }
+    }
+
+    pub fn forget(&self, label: &str) {
+        if let Ok(mut roles) = self.0.lock() {
+            roles.remove(label);
+        }
+    }
+
+    pub fn role_of(&self, label: &str) -> Option<WindowRole> {
+        if label == MAIN_WINDOW_LABEL {
+            return Some(WindowRole::Main);
+        }
+        self.0.lock().ok().and_then(|roles| roles.get(label).map(|entry| entry.role))
+    }
+
+    /// The node URL recorded for `label` when it was registered.
+    pub fn node_url_of(&self, label: &str) -> Option<String> {
+        self.0.lock().ok().and_then(|roles| roles.get(label).and_then(|entry| entry.node_url.clone()))
+    }
+
+    pub fn is_allowed(&self, label: &str, command: &str) -> bool {
+        self.role_of(label).map_or(false, |role| role.allows(command))
+    }
+}
+
+/// Wraps the handler produced by `tauri::generate_handler!` so that a command is only
+/// dispatched when the calling window's role allows it. Denied calls are rejected, logged,
+/// and reported to the main window.
+pub fn guard_invoke_handler<R, F>(handler: F) -> impl Fn(Invoke<R>) + Send + Sync + 'static
+where
+    R: Runtime,
+    F: Fn(Invoke<R>) + Send + Sync + 'static,
+{
+    move |invoke: Invoke<R>| {
+        let window = invoke.message.window();
+        let label = window.label().to_string();
+        let command = invoke.message.command().to_string();
+
+        let allowed = window
+            .try_state::<WindowCapabilities>()
+            .map_or(false, |caps| caps.is_allowed(&label, &command));
+
+        if allowed {
+            debug!("[Capabilities] Allowed '{}' from window '{}'", command, label);
+            handler(invoke);
+            return;
+        }
+
+        warn!("[Capabilities] Denied '{}' from window '{}'", command, label);
+        let _ = window.emit_to(
+            MAIN_WINDOW_LABEL,
+            CAPABILITY_DENIED_EVENT,
+            CapabilityDenial {
+                window: label.clone(),
+                command: command.clone(),
+            },
+        );
+        invoke.resolver.reject(format!(
+            "Command '{}' is not allowed from window '{}'",
+            command, label
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_window_allows_everything() {
+        let caps = WindowCapabilities::default();
+        assert!(caps.is_allowed("main", "kill_all_merod_processes"));
+        assert!(caps.is_allowed("main", "delete_calimero_data_dir"));
+        assert!(caps.is_allowed("main", "proxy_http_request"));
+    }
+
+    #[test]
+    fn test_app_window_limited_to_proxy_and_helpers() {
+        let caps = WindowCapabilities::default();
+        caps.register("app-example.com-1", WindowRole::App, Some("http://localhost:2528"));
+        assert!(caps.is_allowed("app-example.com-1", "proxy_http_request"));
+        assert!(!caps.is_allowed("app-example.com-1", "check_merod_health"));
+        assert!(!caps.is_allowed("app-example.com-1", "kill_all_merod_processes"));
+        assert!(!caps.is_allowed("app-example.com-1", "delete_calimero_data_dir"));
+        assert!(!caps.is_allowed("app-example.com-1", "create_app_window"));
+    }
+
+    #[test]
+    fn test_unknown_and_forgotten_windows_denied() {
+        let caps = WindowCapabilities::default();
+        assert!(!caps.is_allowed("app-unknown", "proxy_http_request"));
+        caps.register("app-x", WindowRole::App, Some("http://localhost:2528"));
+        caps.forget("app-x");
+        assert!(!caps.is_allowed("app-x", "proxy_http_request"));
+        assert_eq!(caps.node_url_of("app-x"), None);
+    }
+
+    #[test]
+    fn test_app_window_node_url_is_recorded() {
+        let caps = WindowCapabilities::default();
+        caps.register("app-y", WindowRole::App, Some("http://localhost:2529"));
+        assert_eq!(caps.node_url_of("app-y").as_deref(), Some("http://localhost:2529"));
+        assert_eq!(caps.node_url_of("main"), None);
+    }
+}
@@ -0,0 +1,706 @@
+//! Command-line interface. Subcommands manage nodes without opening a window and call the same
+//! functions as the app's Tauri commands; without a subcommand the desktop app starts.
+//!
+//! `--json` prints machine-readable output, with failures as `{"error": "..."}`. Unknown
+//! commands or options print the relevant help and exit with status 2.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use crate::app_paths::AppPaths;
+use crate::headless::{HeadlessOptions, DEFAULT_CONTROL_PORT};
+use crate::{crash_diagnosis, merod_info, node_init, node_limits, node_log_level, node_ops, node_startup, storage, SpawnedMerod};
+
+const USAGE: &str = "\
+Calimero Desktop
+
+Usage:
+  calimero-desktop                                 Start the desktop app
+  calimero-desktop --headless [--control-port <port>]
+                                                   Run nodes without a window (control API port 2628)
+  calimero-desktop <command> [options]
+
+Commands:
+  node   Manage local nodes: list, init, start, stop, logs, status
+  app    Open an app in the desktop app
+  data   Manage the Calimero home directory
+
+Options:
+  -h, --help      Print help
+  -V, --version   Print version
+
+Run `calimero-desktop <command> --help` for the options of a command.
+";
+
+const NODE_USAGE: &str = "\
+Usage:
+  calimero-desktop node list [--home <dir>] [--json]
+  calimero-desktop node init <name> [--home <dir>] [--merod-version <version>]
+                             [--server-port <port>] [--swarm-port <port>] [--json]
+  calimero-desktop node start <name> [--home <dir>] [--server-port <port>] [--swarm-port <port>]
+                              [--debug-logs] [--wait <seconds>] [--json]
+  calimero-desktop node stop <name> [--home <dir>] [--json]
+  calimero-desktop node logs <name> [--home <dir>] [--lines <count>] [--json]
+  calimero-desktop node status [<name>] [--home <dir>] [--json]
+
+Options:
+  --home <dir>   Calimero home directory (default ~/.calimero)
+  --wait <s>     Wait up to <s> seconds for the started node to become healthy
+  --json         Print JSON for scripts
+";
+
+const APP_USAGE: &str = "\
+Usage:
+  calimero-desktop app open <url> [--name <name>]
+
+Starts the desktop app with <url> open in an app window.
+";
+
+const DATA_USAGE: &str = "\
+Usage:
+  calimero-desktop data nuke [--home <dir>] [--dry-run] [--trash] [--yes] [--json]
+
+Stops every merod process and deletes the Calimero home directory (default ~/.calimero).
+
+Options:
+  --dry-run   Only report the nodes and sizes that would be removed
+  --trash     Move the directory to the trash instead of deleting it
+  --yes       Confirm the deletion (required without --dry-run)
+  --json      Print JSON for scripts
+";
+
+/// What the command line asks the binary to do
+#[derive(Debug, PartialEq, Eq)]
+pub enum Invocation {
+    /// Start the desktop app, opening `(url, name)` in an app window if given
+    Gui { open_app: Option<(String, String)> },
+    Headless(HeadlessOptions),
+    Command { command: Command, json: bool },
+    /// Print help or version text and exit
+    Print(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    NodeList {
+        home: Option<String>,
+    },
+    NodeInit {
+        name: String,
+        home: Option<String>,
+        merod_version: Option<String>,
+        server_port: Option<u16>,
+        swarm_port: Option<u16>,
+    },
+    NodeStart {
+        name: String,
+        home: Option<String>,
+        server_port: Option<u16>,
+        swarm_port: Option<u16>,
+        debug_logs: bool,
+        wait_secs: Option<u64>,
+    },
+    NodeStop {
+        name: String,
+        home: Option<String>,
+    },
+    NodeLogs {
+        name: String,
+        home: Option<String>,
+        lines: Option<u32>,
+    },
+    NodeStatus {
+        name: Option<String>,
+        home: Option<String>,
+    },
+    DataNuke {
+        home: Option<String>,
+        dry_run: bool,
+        trash: bool,
+        yes: bool,
+    },
+}
+
+/// A command line that can't be run, with the help text to show for it
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageError {
+    pub message: String,
+    pub usage: &'static str,
+}
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error: {}\n\n{}", self.message, self.usage)
+    }
+}
+
+/// Parses the process arguments, program name included.
+pub fn parse(args: &[String]) -> Result<Invocation, UsageError> {
+    let args = args.get(1..).unwrap_or_default();
+    let with_usage = |usage: &'static str| move |message: String| UsageError { message, usage };
+    match args.first().map(String::as_str) {
+        Some("node") => parse_node(&args[1..]).map_err(with_usage(NODE_USAGE)),
+        Some("app") => parse_app(&args[1..]).map_err(with_usage(APP_USAGE)),
+        Some("data") => parse_data(&args[1..]).map_err(with_usage(DATA_USAGE)),
+        Some("help") => Ok(Invocation::Print(
+            match args.get(1).map(String::as_str) {
+                Some("node") => NODE_USAGE,
+                Some("app") => APP_USAGE,
+                Some("data") => DATA_USAGE,
+                _ => USAGE,
+            }
+            .to_string(),
+        )),
+        _ => parse_top_level(args).map_err(with_usage(USAGE)),
+    }
+}
+
+/// Positionals, `--flag value` (or `--flag=value`) options and switches of one command
+#[derive(Default)]
+struct Args {
+    positionals: Vec<String>,
+    values: HashMap<String, String>,
+    switches: HashSet<String>,
+}
+
+impl Args {
+    /// Splits `args`, rejecting options that aren't in `value_flags` or `switches`.
+    fn split(args: &[String], value_flags: &[&str], switches: &[&str]) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if !arg.starts_with('-') {
+                parsed.positionals.push(arg.clone());
+                continue;
+            }
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            if value_flags.contains(&flag) {
+                let value = match inline_value {
+                    Some(value) => value,
+                    None => iter.next().cloned().ok_or_else(|| format!("{} needs a value", flag))?,
+                };
+                parsed.values.insert(flag.to_string(), value);
+            } else if switches.contains(&flag) && inline_value.is_none() {
+                parsed.switches.insert(flag.to_string());
+            } else {
+                return Err(format!("unknown option '{}'", arg));
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn value(&self, flag: &str) -> Option<String> {
+        self.values.get(flag).cloned()
+    }
+
+    fn number<T: FromStr>(&self, flag: &str) -> Result<Option<T>, String> {
+        self.values
+            .get(flag)
+            .map(|value| value.parse().map_err(|_| format!("invalid value '{}' for {}", value, flag)))
+            .transpose()
+    }
+
+    fn switch(&self, flag: &str) -> bool {
+        self.switches.contains(flag)
+    }
+
+    /// At most `names.len()` positionals, the first `required` of them mandatory.
+    fn positionals(&self, names: &[&str], required: usize) -> Result<Vec<String>, String> {
+        if let Some(extra) = self.positionals.get(names.len()) {
+            return Err(format!("unexpected argument '{}'", extra));
+        }
+        if let Some(missing) = names.get(self.positionals.len()).filter(|_| self.positionals.len() < required) {
+            return Err(format!("missing {}", missing));
+        }
+        Ok(self.positionals.clone())
+    }
+
+    /// Exactly one positional.
+    fn one(&self, name: &str) -> Result<String, String> {
+        self.positionals(&[name], 1).map(|mut positionals| positionals.remove(0))
+    }
+}
+
+fn wants_help(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "-h" || arg == "--help")
+}
+
+/// No subcommand: the desktop app or headless mode.
+fn parse_top_level(args: &[String]) -> Result<Invocation, String> {
+    // Older macOS versions pass a process serial number to apps started from Finder
+    let args: Vec<String> = args.iter().filter(|arg| !arg.starts_with("-psn_")).cloned().collect();
+    // --open-app-url/--open-app-name are what desktop shortcuts created by the app pass
+    let parsed = Args::split(
+        &args,
+        &["--control-port", "--open-app-url", "--open-app-name"],
+        &["--headless", "-h", "--help", "-V", "--version"],
+    )?;
+    if let Some(command) = parsed.positionals.first() {
+        return Err(format!("unknown command '{}'", command));
+    }
+    if parsed.switch("-h") || parsed.switch("--help") {
+        return Ok(Invocation::Print(USAGE.to_string()));
+    }
+    if parsed.switch("-V") || parsed.switch("--version") {
+        return Ok(Invocation::Print(format!("calimero-desktop {}\n", env!("CARGO_PKG_VERSION"))));
+    }
+    if parsed.switch("--headless") {
+        let control_port = parsed.number("--control-port")?.unwrap_or(DEFAULT_CONTROL_PORT);
+        return Ok(Invocation::Headless(HeadlessOptions { control_port }));
+    }
+    if parsed.values.contains_key("--control-port") {
+        return Err("--control-port only applies with --headless".to_string());
+    }
+    let open_app = parsed
+        .value("--open-app-url")
+        .map(|url| (url, parsed.value("--open-app-name").unwrap_or_else(|| "Application".to_string())));
+    Ok(Invocation::Gui { open_app })
+}
+
+fn parse_node(args: &[String]) -> Result<Invocation, String> {
+    if wants_help(args) {
+        return Ok(Invocation::Print(NODE_USAGE.to_string()));
+    }
+    let Some((action, rest)) = args.split_first() else {
+        return Err("missing node command".to_string());
+    };
+    let (command, parsed) = match action.as_str() {
+        "list" => {
+            let parsed = Args::split(rest, &["--home"], &["--json"])?;
+            parsed.positionals(&[], 0)?;
+            (Command::NodeList { home: parsed.value("--home") }, parsed)
+        }
+        "init" => {
+            let parsed = Args::split(rest, &["--home", "--merod-version", "--server-port", "--swarm-port"], &["--json"])?;
+            let name = parsed.one("<name>")?;
+            let command = Command::NodeInit {
+                name,
+                home: parsed.value("--home"),
+                merod_version: parsed.value("--merod-version"),
+                server_port: parsed.number("--server-port")?,
+                swarm_port: parsed.number("--swarm-port")?,
+            };
+            (command, parsed)
+        }
+        "start" => {
+            let parsed =
+                Args::split(rest, &["--home", "--server-port", "--swarm-port", "--wait"], &["--debug-logs", "--json"])?;
+            let name = parsed.one("<name>")?;
+            let command = Command::NodeStart {
+                name,
+                home: parsed.value("--home"),
+                server_port: parsed.number("--server-port")?,
+                swarm_port: parsed.number("--swarm-port")?,
+                debug_logs: parsed.switch("--debug-logs"),
+                wait_secs: parsed.number("--wait")?,
+            };
+            (command, parsed)
+        }
+        "stop" => {
+            let parsed = Args::split(rest, &["--home"], &["--json"])?;
+            let name = parsed.one("<name>")?;
+            (Command::NodeStop { name, home: parsed.value("--home") }, parsed)
+        }
+        "logs" => {
+            let parsed = Args::split(rest, &["--home", "--lines"], &["--json"])?;
+            let name = parsed.one("<name>")?;
+            let command = Command::NodeLogs { name, home: parsed.value("--home"), lines: parsed.number("--lines")? };
+            (command, parsed)
+        }
+        "status" => {
+            let parsed = Args::split(rest, &["--home"], &["--json"])?;
+            let name = parsed.positionals(&["<name>"], 0)?.pop();
+            (Command::NodeStatus { name, home: parsed.value("--home") }, parsed)
+        }
+        other => return Err(format!("unknown node command '{}'", other)),
+    };
+    Ok(Invocation::Command { command, json: parsed.switch("--json") })
+}
+
+fn parse_app(args: &[String]) -> Result<Invocation, String> {
+    if wants_help(args) {
+        return Ok(Invocation::Print(APP_USAGE.to_string()));
+    }
+    match args.split_first() {
+        Some((action, rest)) if action == "open" => {
+            let parsed = Args::split(rest, &["--name"], &[])?;
+            let url = parsed.one("<url>")?;
+            let scheme = url::Url::parse(&url).map_err(|e| format!("invalid URL '{}': {}", url, e))?.scheme().to_string();
+            if scheme != "http" && scheme != "https" {
+                return Err(format!("only http and https URLs can be opened, not '{}'", scheme));
+            }
+            let name = parsed.value("--name").unwrap_or_else(|| "Application".to_string());
+            Ok(Invocation::Gui { open_app: Some((url, name)) })
+        }
+        Some((other, _)) => Err(format!("unknown app command '{}'", other)),
+        None => Err("missing app command".to_string()),
+    }
+}
+
+fn parse_data(args: &[String]) -> Result<Invocation, String> {
+    if wants_help(args) {
+        return Ok(Invocation::Print(DATA_USAGE.to_string()));
+    }
+    match args.split_first() {
+        Some((action, rest)) if action == "nuke" => {
+            let parsed = Args::split(rest, &["--home"], &["--dry-run", "--trash", "--yes", "--json"])?;
+            parsed.positionals(&[], 0)?;
+            let command = Command::DataNuke {
+                home: parsed.value("--home"),
+                dry_run: parsed.switch("--dry-run"),
+                trash: parsed.switch("--trash"),
+                yes: parsed.switch("--yes"),
+            };
+            Ok(Invocation::Command { command, json: parsed.switch("--json") })
+        }
+        Some((other, _)) => Err(format!("unknown data command '{}'", other)),
+        None => Err("missing data command".to_string()),
+    }
+}
+
+/// A command's result: JSON for `--json`, text otherwise
+struct Output {
+    json: serde_json::Value,
+    text: String,
+}
+
+/// Release builds on Windows have no console of their own; print to the one the command was
+/// run from.
+#[cfg(windows)]
+pub fn attach_parent_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// Runs a subcommand and prints its result; returns the process exit code.
+pub fn run(paths: AppPaths, command: Command, json: bool) -> i32 {
+    let result = match tokio::runtime::Builder::new_multi_thread().enable_all().build() {
+        Ok(runtime) => runtime.block_on(execute(&paths, command)),
+        Err(e) => Err(format!("Failed to start the async runtime: {}", e)),
+    };
+    match result {
+        Ok(output) if json => {
+            println!("{}", serde_json::to_string_pretty(&output.json).unwrap_or_default());
+            0
+        }
+        Ok(output) => {
+            if !output.text.is_empty() {
+                println!("{}", output.text.trim_end());
+            }
+            0
+        }
+        Err(e) if json => {
+            println!("{}", json!({ "error": e }));
+            1
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    }
+}
+
+async fn execute(paths: &AppPaths, command: Command) -> Result<Output, String> {
+    match command {
+        Command::NodeList { home } => node_list(home).await,
+        Command::NodeInit { name, home, merod_version, server_port, swarm_port } => {
+            let options = node_init::NodeInitOptions { server_port, swarm_port, ..Default::default() };
+            // merod's own output goes to stderr so stdout stays parseable
+            let progress = |progress: node_init::InitProgress| eprintln!("{}", progress.line);
+            let message = crate::init_node(paths, name.clone(), home, merod_version, options, &progress).await?;
+            Ok(Output { json: json!({ "node_name": name, "message": message }), text: message })
+        }
+        Command::NodeStart { name, home, server_port, swarm_port, debug_logs, wait_secs } => {
+            node_start(paths, name, home, server_port, swarm_port, debug_logs, wait_secs).await
+        }
+        Command::NodeStop { name, home } => {
+            let home_path = crate::resolve_calimero_home(home)?;
+            let pids = node_ops::running_node_pids(&home_path, &name);
+            if pids.is_empty() {
+                return Err(format!("Node '{}' is not running", name));
+            }
+            for pid in &pids {
+                crate::stop_merod_pid(*pid).await?;
+            }
+            let text = format!("Stopped node '{}'", name);
+            Ok(Output { json: json!({ "node_name": name, "pids": pids, "stopped": true }), text })
+        }
+        Command::NodeLogs { name, home, lines } => {
+            let logs = crate::get_merod_logs(name.clone(), home, lines).await?;
+            Ok(Output { json: json!({ "node_name": name, "lines": logs.lines().collect::<Vec<_>>() }), text: logs })
+        }
+        Command::NodeStatus { name, home } => node_status(paths, name, home).await,
+        Command::DataNuke { home, dry_run, trash, yes } => data_nuke(home, dry_run, trash, yes).await,
+    }
+}
+
+async fn node_list(home: Option<String>) -> Result<Output, String> {
+    let home_path = crate::resolve_calimero_home(home.clone())?;
+    let names = crate::list_merod_nodes(home).await?;
+    let nodes: Vec<_> = names
+        .iter()
+        .map(|name| {
+            let pids = node_ops::running_node_pids(&home_path, name);
+            json!({ "name": name, "running": !pids.is_empty(), "pids": pids })
+        })
+        .collect();
+    let text = if nodes.is_empty() {
+        format!("No nodes in {}", home_path.display())
+    } else {
+        nodes
+            .iter()
+            .map(|node| {
+                let state = if node["running"] == true { "running" } else { "stopped" };
+                format!("{:<24} {}", node["name"].as_str().unwrap_or_default(), state)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    Ok(Output { json: json!(nodes), text })
+}
+
+async fn node_start(
+    paths: &AppPaths,
+    name: String,
+    home: Option<String>,
+    server_port: Option<u16>,
+    swarm_port: Option<u16>,
+    debug_logs: bool,
+    wait_secs: Option<u64>,
+) -> Result<Output, String> {
+    let home_path = crate::resolve_calimero_home(home)?;
+    let node_dir = node_ops::existing_node_dir(&home_path, &name)?;
+    node_ops::ensure_not_running(&home_path, &name)?;
+    // Without explicit ports the node keeps the ones it last ran with
+    let (configured_server_port, configured_swarm_port) = node_log_level::configured_ports(&node_dir)?;
+    let server_port = server_port.or(configured_server_port).unwrap_or(2528);
+    let swarm_port = swarm_port.or(configured_swarm_port).unwrap_or(2428);
+    let warning = storage::StorageGuard::load(&paths.app_data_dir()?).check_before_start(&home_path)?;
+
+    let binary = paths.node_merod_binary(&home_path, &name)?;
+    // The node outlives this process, so its cgroup (if any) is left behind when it exits
+    let SpawnedMerod { mut child, pid, spawned_at, log_path, log_offset, limits } =
+        crate::spawn_merod(paths, &binary, &home_path, &name, server_port, swarm_port, debug_logs, true).await?;
+
+    let exited_early = |status: std::process::ExitStatus| {
+        let (exit_code, signal) = crash_diagnosis::exit_details(&status);
+        let log_tail = crash_diagnosis::read_log_tail(&log_path, log_offset);
+        let diagnosis = crash_diagnosis::diagnose(&name, pid, exit_code, signal, &log_path, log_tail);
+        let diagnosis = node_limits::attribute_breach(diagnosis, &limits);
+        node_limits::release(&limits);
+        diagnosis.message()
+    };
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    if let Ok(Some(status)) = child.try_wait() {
+        return Err(format!("Merod process exited immediately. {}", exited_early(status)));
+    }
+
+    let mut time_to_ready_ms = None;
+    if let Some(wait_secs) = wait_secs {
+        let client = reqwest::Client::new();
+        let deadline = Instant::now() + Duration::from_secs(wait_secs);
+        loop {
+            if node_startup::is_healthy(&client, server_port).await {
+                time_to_ready_ms = Some(spawned_at.elapsed().as_millis() as u64);
+                break;
+            }
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(format!("Merod exited while starting. {}", exited_early(status)));
+            }
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "Node did not become healthy within {} s. It is still running as PID {}.",
+                    wait_secs, pid
+                ));
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    let node_url = node_startup::node_url(server_port);
+    let mut text = format!("Started node '{}' (PID {}) on {}\nLogs: {}", name, pid, node_url, log_path.display());
+    if let Some(warning) = &warning {
+        text = format!("{}\nWarning: {}", text, warning);
+    }
+    let json = json!({
+        "pid": pid,
+        "node_name": name,
+        "server_port": server_port,
+        "swarm_port": swarm_port,
+        "node_url": node_url,
+        "log_path": log_path,
+        "time_to_ready_ms": time_to_ready_ms,
+        "warning": warning,
+    });
+    Ok(Output { json, text })
+}
+
+async fn node_status(paths: &AppPaths, name: Option<String>, home: Option<String>) -> Result<Output, String> {
+    let home_path = crate::resolve_calimero_home(home)?;
+    let mut nodes = crate::detect_running_nodes(paths, &merod_info::MerodInfoCache::default()).await?;
+    nodes.retain(|node| {
+        let in_home = node["home_dir"].as_str().is_none_or(|dir| node_ops::same_dir(Path::new(dir), &home_path));
+        in_home && name.as_deref().is_none_or(|name| node["node_name"] == name)
+    });
+    let client = reqwest::Client::new();
+    for node in &mut nodes {
+        let port = node["port"].as_u64().and_then(|port| u16::try_from(port).ok());
+        node["running"] = json!(true);
+        node["healthy"] = match port {
+            Some(port) => json!(node_startup::is_healthy(&client, port).await),
+            None => serde_json::Value::Null,
+        };
+    }
+    if let (Some(name), true) = (&name, nodes.is_empty()) {
+        let text = format!("Node '{}' is not running", name);
+        return Ok(Output { json: json!([{ "node_name": name, "running": false }]), text });
+    }
+    let text = if nodes.is_empty() {
+        format!("No nodes in {} are running", home_path.display())
+    } else {
+        nodes
+            .iter()
+            .map(|node| {
+                format!(
+                    "{:<24} PID {:<8} port {:<6} {}{}",
+                    node["node_name"].as_str().unwrap_or_default(),
+                    node["pid"],
+                    node["port"],
+                    if node["healthy"] == true { "healthy" } else { "not responding" },
+                    node["version"].as_str().map(|v| format!("  merod {}", v)).unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    Ok(Output { json: json!(nodes), text })
+}
+
+async fn data_nuke(home: Option<String>, dry_run: bool, trash: bool, yes: bool) -> Result<Output, String> {
+    let home_path = crate::resolve_calimero_home(home)?;
+    let data_dir = home_path.to_string_lossy().into_owned();
+    if dry_run {
+        let report = crate::delete_calimero_data_dir(data_dir, Some(true), None).await?;
+        return Ok(Output { text: describe_nuke_report(&report), json: report });
+    }
+    if !yes {
+        return Err(format!(
+            "Deleting {} cannot be undone. Pass --yes to confirm, or --dry-run to see what would be removed.",
+            home_path.display()
+        ));
+    }
+    // Same order as the app: nothing may have the directory open while it is removed
+    let stopped = crate::stop_all_merod_processes().await?;
+    let mut result = crate::delete_calimero_data_dir(data_dir, None, Some(trash)).await?;
+    result["stopped_processes"] = json!(stopped);
+    let text = result["message"].as_str().unwrap_or_default().to_string();
+    Ok(Output { json: result, text })
+}
+
+fn describe_nuke_report(report: &serde_json::Value) -> String {
+    if let Some(message) = report["message"].as_str() {
+        return message.to_string();
+    }
+    let mut lines = vec![format!(
+        "Would delete {} ({})",
+        report["path"].as_str().unwrap_or_default(),
+        storage::format_bytes(report["total_size"].as_u64().unwrap_or(0))
+    )];
+    for node in report["nodes"].as_array().into_iter().flatten() {
+        lines.push(format!(
+            "  {:<24} {}",
+            node["name"].as_str().unwrap_or_default(),
+            storage::format_bytes(node["size"].as_u64().unwrap_or(0))
+        ));
+    }
+    if let Some(reason) = report["reason"].as_str() {
+        lines.push(format!("Refusing to delete: {}", reason));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Invocation, UsageError> {
+        let args: Vec<String> = std::iter::once("calimero-desktop").chain(args.iter().copied()).map(String::from).collect();
+        parse(&args)
+    }
+
+    #[test]
+    fn test_top_level_invocations() {
+        assert_eq!(parse_args(&[]), Ok(Invocation::Gui { open_app: None }));
+        assert_eq!(
+            parse_args(&["--open-app-url", "http://localhost:3000", "--open-app-name", "KV Store"]),
+            Ok(Invocation::Gui { open_app: Some(("http://localhost:3000".to_string(), "KV Store".to_string())) })
+        );
+        assert_eq!(
+            parse_args(&["app", "open", "https://example.com"]),
+            Ok(Invocation::Gui { open_app: Some(("https://example.com".to_string(), "Application".to_string())) })
+        );
+        assert_eq!(
+            parse_args(&["--headless"]),
+            Ok(Invocation::Headless(HeadlessOptions { control_port: DEFAULT_CONTROL_PORT }))
+        );
+        assert_eq!(
+            parse_args(&["--headless", "--control-port=9000"]),
+            Ok(Invocation::Headless(HeadlessOptions { control_port: 9000 }))
+        );
+        assert!(matches!(parse_args(&["--help"]), Ok(Invocation::Print(text)) if text == USAGE));
+        assert!(matches!(parse_args(&["node", "start", "--help"]), Ok(Invocation::Print(text)) if text == NODE_USAGE));
+    }
+
+    #[test]
+    fn test_node_commands() {
+        assert_eq!(
+            parse_args(&["node", "start", "node1", "--home", "~/nodes", "--wait", "30", "--json"]),
+            Ok(Invocation::Command {
+                command: Command::NodeStart {
+                    name: "node1".to_string(),
+                    home: Some("~/nodes".to_string()),
+                    server_port: None,
+                    swarm_port: None,
+                    debug_logs: false,
+                    wait_secs: Some(30),
+                },
+                json: true,
+            })
+        );
+        assert_eq!(
+            parse_args(&["node", "status"]),
+            Ok(Invocation::Command { command: Command::NodeStatus { name: None, home: None }, json: false })
+        );
+        assert_eq!(
+            parse_args(&["data", "nuke", "--dry-run"]),
+            Ok(Invocation::Command {
+                command: Command::DataNuke { home: None, dry_run: true, trash: false, yes: false },
+                json: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_usage_errors_carry_the_relevant_help() {
+        let error = |args: &[&str]| parse_args(args).unwrap_err();
+        assert_eq!(error(&["--frobnicate"]), UsageError { message: "unknown option '--frobnicate'".to_string(), usage: USAGE });
+        assert_eq!(error(&["launch"]).message, "unknown command 'launch'");
+        assert_eq!(error(&["--control-port", "9000"]).usage, USAGE);
+        assert_eq!(error(&["node", "start"]), UsageError { message: "missing <name>".to_string(), usage: NODE_USAGE });
+        assert_eq!(error(&["node", "stop", "a", "b"]).message, "unexpected argument 'b'");
+        assert_eq!(error(&["node", "logs", "a", "--lines", "many"]).message, "invalid value 'many' for --lines");
+        assert_eq!(error(&["node", "list", "--verbose"]).message, "unknown option '--verbose'");
+        assert_eq!(error(&["node", "init", "a", "--home"]).message, "--home needs a value");
+        assert_eq!(error(&["app", "open", "file:///etc/passwd"]).usage, APP_USAGE);
+        assert_eq!(error(&["data", "wipe"]).usage, DATA_USAGE);
+    }
+}
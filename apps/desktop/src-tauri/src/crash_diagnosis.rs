@@ -0,0 +1,400 @@
+//! Explaining why a merod process stopped.
+//!
+//! After an abnormal exit the tail of the run's `merod.log` is matched against known failure
+//! signatures (port in use, RocksDB lock held, bad config, ...). The result names the likely
+//! cause and what to do about it. Exits the app caused itself (stopping a node) are not
+//! reported.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use serde::Serialize;
+
+/// Emitted to the main window with a `CrashDiagnosis` when a node exits abnormally.
+pub const NODE_CRASHED_EVENT: &str = "merod-crashed";
+
+/// Lines of log kept in a diagnosis.
+const LOG_TAIL_LINES: usize = 30;
+/// Bytes read from the end of the log at most.
+const LOG_TAIL_BYTES: u64 = 64 * 1024;
+
+/// How long an expected exit is remembered when the stop never reports completion.
+const EXPECTED_EXIT_TTL: Duration = Duration::from_secs(120);
+/// How long an exit stays expected after its stop completed, for the process monitor to see it.
+const STOPPED_EXIT_GRACE: Duration = Duration::from_secs(5);
+
+/// PIDs the app is stopping on purpose, so their exit is not reported as a crash, with the
+/// time they are forgotten. Entries expire so a reused PID isn't mistaken for one of them.
+static EXPECTED_EXITS: Mutex<Option<HashMap<u32, Instant>>> = Mutex::new(None);
+
+fn with_expected_exits<R>(f: impl FnOnce(&mut HashMap<u32, Instant>) -> R) -> Option<R> {
+    let mut expected = EXPECTED_EXITS.lock().ok()?;
+    let expected = expected.get_or_insert_with(HashMap::new);
+    let now = Instant::now();
+    expected.retain(|_, forget_at| *forget_at > now);
+    Some(f(expected))
+}
+
+/// Records that `pid` is about to be stopped by the app.
+pub fn expect_exit(pid: u32) {
+    with_expected_exits(|expected| expected.insert(pid, Instant::now() + EXPECTED_EXIT_TTL));
+}
+
+/// Records that the stop of `pid` finished; its exit stays expected only briefly after this.
+pub fn stop_completed(pid: u32) {
+    with_expected_exits(|expected| {
+        if let Some(forget_at) = expected.get_mut(&pid) {
+            *forget_at = (*forget_at).min(Instant::now() + STOPPED_EXIT_GRACE);
+        }
+    });
+}
+
+/// Whether `pid` was stopped by the app; forgets the PID either way.
+pub fn take_expected_exit(pid: u32) -> bool {
+    with_expected_exits(|expected| expected.remove(&pid).is_some()).unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    AddressInUse,
+    DatabaseLocked,
+    CorruptConfig,
+    MissingBootstrap,
+    PermissionDenied,
+    DiskFull,
+    /// Killed or failed for exceeding the node's memory limit.
+    MemoryLimit,
+    /// Ran out of file descriptors under the node's open files limit.
+    OpenFilesLimit,
+    Panic,
+    /// Terminated by a signal with nothing in the log explaining it.
+    Signal,
+    Unknown,
+}
+
+/// Log substrings (lowercase) for each failure, checked in order.
+const SIGNATURES: &[(FailureKind, &[&str])] = &[
+    (
+        FailureKind::AddressInUse,
+        &["address already in use", "addrinuse", "os error 98)", "os error 48)", "os error 10048)"],
+    ),
+    (
+        FailureKind::DatabaseLocked,
+        &["while lock file", "lock hold by current process", "/lock: resource temporarily unavailable", "database is locked"],
+    ),
+    (FailureKind::DiskFull, &["no space left on device", "os error 28)"]),
+    (
+        FailureKind::PermissionDenied,
+        &["permission denied", "os error 13)", "operation not permitted", "access is denied"],
+    ),
+    (
+        FailureKind::MissingBootstrap,
+        &["no bootstrap", "missing bootstrap", "bootstrap nodes are required", "bootstrap list is empty"],
+    ),
+    (
+        FailureKind::CorruptConfig,
+        &["failed to parse config", "failed to load config", "invalid config", "toml parse error", "missing field", "unknown field"],
+    ),
+    (FailureKind::Panic, &["panicked at"]),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashDiagnosis {
+    pub node_name: String,
+    pub pid: u32,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub signal_name: Option<&'static str>,
+    pub kind: FailureKind,
+    pub summary: String,
+    pub suggestion: String,
+    /// The log line that matched `kind`'s signature.
+    pub matched_line: Option<String>,
+    pub log_path: PathBuf,
+    pub log_tail: Vec<String>,
+    pub occurred_at: String,
+}
+
+impl CrashDiagnosis {
+    /// One-line description for error messages.
+    pub fn message(&self) -> String {
+        format!("{} {}", self.summary, self.suggestion)
+    }
+
+    /// Re-labels the diagnosis as a breach of one of the node's resource limits.
+    pub fn limit_breached(mut self, kind: FailureKind, limit: &str, matched_line: Option<String>) -> Self {
+        let (summary, suggestion) = explain(kind, &self.node_name, self.signal);
+        self.summary = format!("{} (limit: {})", summary.trim_end_matches('.'), limit);
+        self.suggestion = suggestion;
+        self.kind = kind;
+        if matched_line.is_some() {
+            self.matched_line = matched_line;
+        }
+        self
+    }
+}
+
+/// Exit code and terminating signal of `status`.
+pub fn exit_details(status: &ExitStatus) -> (Option<i32>, Option<i32>) {
+    #[cfg(unix)]
+    let signal = std::os::unix::process::ExitStatusExt::signal(status);
+    #[cfg(not(unix))]
+    let signal = None;
+    (status.code(), signal)
+}
+
+pub fn signal_name(signal: i32) -> Option<&'static str> {
+    Some(match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        6 => "SIGABRT",
+        7 => "SIGBUS",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        15 => "SIGTERM",
+        _ => return None,
+    })
+}
+
+/// The last lines written to `log_path` after byte `offset` (the run's start).
+pub fn read_log_tail(log_path: &Path, offset: u64) -> Vec<String> {
+    let Ok(mut file) = std::fs::File::open(log_path) else { return Vec::new() };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = offset.max(len.saturating_sub(LOG_TAIL_BYTES)).min(len);
+    let mut buf = Vec::new();
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_to_end(&mut buf).is_err() {
+        return Vec::new();
+    }
+    static ANSI: OnceLock<Regex> = OnceLock::new();
+    let ansi = ANSI.get_or_init(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap());
+    let lines: Vec<String> = String::from_utf8_lossy(&buf)
+        .lines()
+        .map(|l| ansi.replace_all(l, "").trim_end().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    lines[lines.len().saturating_sub(LOG_TAIL_LINES)..].to_vec()
+}
+
+fn explain(kind: FailureKind, node_name: &str, signal: Option<i32>) -> (String, String) {
+    let (summary, suggestion) = match kind {
+        FailureKind::AddressInUse => (
+            format!("Node '{}' could not bind its server or swarm port: the address is already in use.", node_name),
+            "Stop the other process using the port, or start the node on different ports.".to_string(),
+        ),
+        FailureKind::DatabaseLocked => (
+            format!("Node '{}' could not open its database: the lock is held by another process.", node_name),
+            "Another merod instance is probably running this node. Stop it (see running nodes) and start again."
+                .to_string(),
+        ),
+        FailureKind::CorruptConfig => (
+            format!("Node '{}' has an invalid config.toml.", node_name),
+            "Fix the reported field in config.toml, or re-create the node with init.".to_string(),
+        ),
+        FailureKind::MissingBootstrap => (
+            format!("Node '{}' has no bootstrap peers to join the network.", node_name),
+            "Add bootstrap nodes in config.toml or re-create the node with a boot network.".to_string(),
+        ),
+        FailureKind::PermissionDenied => (
+            format!("Node '{}' was denied access to a file or port.", node_name),
+            "Check that the node directory is owned by your user, and use ports above 1024.".to_string(),
+        ),
+        FailureKind::DiskFull => (
+            format!("Node '{}' ran out of disk space.", node_name),
+            "Free up space or move the Calimero home to a bigger disk, then start the node again.".to_string(),
+        ),
+        FailureKind::MemoryLimit => (
+            format!("Node '{}' exceeded its memory limit.", node_name),
+            "Raise the node's memory limit in its run settings, or report a leak if its usage keeps growing."
+                .to_string(),
+        ),
+        FailureKind::OpenFilesLimit => (
+            format!("Node '{}' hit its open files limit.", node_name),
+            "Raise the node's open files limit in its run settings.".to_string(),
+        ),
+        FailureKind::Panic => (
+            format!("merod crashed (panic) while running node '{}'.", node_name),
+            "This is a merod bug. Please report it with the log lines below.".to_string(),
+        ),
+        FailureKind::Signal => {
+            let name = signal.and_then(signal_name).map(str::to_string);
+            let name = name.unwrap_or_else(|| format!("signal {}", signal.unwrap_or_default()));
+            let suggestion = match signal {
+                Some(9) => "It was killed from outside the app, often by the system running out of memory.",
+                Some(11) | Some(7) | Some(4) | Some(6) => "merod crashed. Please report it with the log lines below.",
+                _ => "It was stopped from outside the app. Start it again if that wasn't intended.",
+            };
+            (format!("Node '{}' was terminated by {}.", node_name, name), suggestion.to_string())
+        }
+        FailureKind::Unknown => (
+            format!("Node '{}' stopped unexpectedly.", node_name),
+            "Check the log lines below for details.".to_string(),
+        ),
+    };
+    (summary, suggestion)
+}
+
+/// Works out why node `node_name` (PID `pid`) exited, from its exit status and log tail.
+pub fn diagnose(
+    node_name: &str,
+    pid: u32,
+    exit_code: Option<i32>,
+    signal: Option<i32>,
+    log_path: &Path,
+    log_tail: Vec<String>,
+) -> CrashDiagnosis {
+    let matched = SIGNATURES.iter().find_map(|(kind, patterns)| {
+        log_tail
+            .iter()
+            .rev()
+            .find(|line| {
+                let line = line.to_lowercase();
+                patterns.iter().any(|p| line.contains(p))
+            })
+            .map(|line| (*kind, line.clone()))
+    });
+    let (kind, matched_line) = match matched {
+        Some((kind, line)) => (kind, Some(line)),
+        None if signal.is_some() => (FailureKind::Signal, None),
+        None => (FailureKind::Unknown, None),
+    };
+    let (mut summary, suggestion) = explain(kind, node_name, signal);
+    if kind != FailureKind::Signal {
+        match (exit_code, signal.and_then(signal_name)) {
+            (Some(code), _) => summary.push_str(&format!(" (exit code {})", code)),
+            (None, Some(name)) => summary.push_str(&format!(" ({})", name)),
+            _ => {}
+        }
+    }
+    CrashDiagnosis {
+        node_name: node_name.to_string(),
+        pid,
+        exit_code,
+        signal,
+        signal_name: signal.and_then(signal_name),
+        kind,
+        summary,
+        suggestion,
+        matched_line,
+        log_path: log_path.to_path_buf(),
+        log_tail,
+        occurred_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    }
+}
+
+/// Last diagnosis per node, keyed by node directory.
+#[derive(Default)]
+pub struct CrashReports(Mutex<HashMap<PathBuf, CrashDiagnosis>>);
+
+impl CrashReports {
+    pub fn record(&self, node_dir: PathBuf, diagnosis: CrashDiagnosis) {
+        if let Ok(mut reports) = self.0.lock() {
+            reports.insert(node_dir, diagnosis);
+        }
+    }
+
+    pub fn get(&self, node_dir: &Path) -> Option<CrashDiagnosis> {
+        self.0.lock().ok().and_then(|r| r.get(node_dir).cloned())
+    }
+
+    pub fn all(&self) -> Vec<CrashDiagnosis> {
+        self.0.lock().map(|r| r.values().cloned().collect()).unwrap_or_default()
+    }
+
+    pub fn for_pid(&self, pid: u32) -> Option<CrashDiagnosis> {
+        self.0.lock().ok().and_then(|r| r.values().find(|d| d.pid == pid).cloned())
+    }
+
+    pub fn clear(&self, node_dir: &Path) {
+        if let Ok(mut reports) = self.0.lock() {
+            reports.remove(node_dir);
+        }
+    }
+}
+
+/// The diagnosis of the node's last abnormal exit since the app started, if any.
+#[tauri::command]
+pub fn get_crash_diagnosis(
+    node_name: String,
+    home_dir: Option<String>,
+    reports: tauri::State<'_, CrashReports>,
+) -> Result<Option<CrashDiagnosis>, String> {
+    let home = crate::resolve_calimero_home(home_dir)?;
+    Ok(reports.get(&home.join(node_name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_matches_known_signatures() {
+        let log = Path::new("merod.log");
+        let cases = [
+            ("Error: Address already in use (os error 98)", FailureKind::AddressInUse),
+            (
+                "Error: IO error: While lock file: /home/u/.calimero/node1/data/LOCK: Resource temporarily unavailable",
+                FailureKind::DatabaseLocked,
+            ),
+            ("Error: failed to load config: missing field `swarm`", FailureKind::CorruptConfig),
+            ("Error: Permission denied (os error 13)", FailureKind::PermissionDenied),
+            ("thread 'main' panicked at src/main.rs:1:1", FailureKind::Panic),
+        ];
+        for (line, kind) in cases {
+            let diagnosis = diagnose("node1", 42, Some(1), None, log, lines(&format!("INFO starting\n{}", line)));
+            assert_eq!(diagnosis.kind, kind, "{}", line);
+            assert_eq!(diagnosis.matched_line.as_deref(), Some(line));
+            assert!(diagnosis.summary.contains("(exit code 1)"));
+        }
+    }
+
+    #[test]
+    fn test_signal_exit_is_reported() {
+        let diagnosis = diagnose("node1", 42, None, Some(9), Path::new("merod.log"), lines("INFO running"));
+        assert_eq!(diagnosis.kind, FailureKind::Signal);
+        assert_eq!(diagnosis.signal_name, Some("SIGKILL"));
+        assert!(diagnosis.summary.contains("SIGKILL"));
+        assert!(diagnosis.suggestion.contains("memory"));
+    }
+
+    #[test]
+    fn test_read_log_tail_starts_at_offset() {
+        let path = std::env::temp_dir().join(format!("calimero-crash-{}.log", std::process::id()));
+        std::fs::write(&path, "previous run: address already in use\n").unwrap();
+        let offset = std::fs::metadata(&path).unwrap().len();
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        std::io::Write::write_all(&mut file, b"\x1b[31mERROR\x1b[0m boom\n").unwrap();
+
+        assert_eq!(read_log_tail(&path, offset), ["ERROR boom"]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_expected_exits() {
+        expect_exit(4242);
+        assert!(take_expected_exit(4242));
+        assert!(!take_expected_exit(4242));
+
+        expect_exit(4243);
+        stop_completed(4243);
+        assert!(take_expected_exit(4243));
+
+        // An exit whose stop finished long ago is no longer expected
+        expect_exit(4244);
+        with_expected_exits(|expected| expected.insert(4244, Instant::now()));
+        assert!(!take_expected_exit(4244));
+    }
+}
@@ -0,0 +1,247 @@
+//! What's inside a Calimero home directory, and whether it is safe to delete.
+//!
+//! A directory counts as a Calimero home if it carries the marker file the app writes into
+//! homes it creates or runs nodes from, or if it holds nothing but node directories
+//! (directories with a `config.toml`). An empty directory may be deleted too. Anything else —
+//! `~`, `~/Documents`, a folder of dotfiles, a project folder that happens to hold a node — is
+//! refused.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+/// Written into every Calimero home the app creates.
+pub const HOME_MARKER: &str = ".calimero-home";
+
+/// Hidden entries that may sit next to the nodes of a Calimero home.
+const ALLOWED_HIDDEN: &[&str] = &[HOME_MARKER, ".DS_Store", ".clusters"];
+
+/// How many unrelated entries to name when refusing a deletion.
+const MAX_LISTED_ENTRIES: usize = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeUsage {
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataDirReport {
+    pub path: PathBuf,
+    pub has_marker: bool,
+    pub nodes: Vec<NodeUsage>,
+    /// Top-level entries that are neither node directories nor in `ALLOWED_HIDDEN`.
+    pub other_entries: Vec<String>,
+    pub total_size: u64,
+    pub deletable: bool,
+    /// Why `deletable` is false.
+    pub reason: Option<String>,
+}
+
+/// Size of everything under `path` in bytes. Symlinks are counted, not followed.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = path.symlink_metadata() else { return 0 };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| dir_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+pub fn is_node_dir(path: &Path) -> bool {
+    path.is_dir() && path.join("config.toml").is_file()
+}
+
+/// Creates `home` if it doesn't exist yet and marks it as a Calimero home. A directory that
+/// already exists is only marked once it holds nodes and nothing else, so pointing the home
+/// at `~/Documents` never makes it deletable.
+pub fn create_calimero_home(home: &Path) -> Result<(), String> {
+    if let Some(parent) = home.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create home directory: {}", e))?;
+    }
+    match std::fs::create_dir(home) {
+        Ok(()) => {
+            mark_calimero_home(home);
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && home.is_dir() => {
+            if inspect(home).is_ok_and(|report| !report.nodes.is_empty()) {
+                mark_calimero_home(home);
+            }
+            Ok(())
+        }
+        Err(e) => Err(format!("Failed to create home directory: {}", e)),
+    }
+}
+
+/// Records that `home`, a directory the app created or runs nodes from, is a Calimero home.
+/// Skipped when it holds anything but nodes.
+pub fn mark_calimero_home(home: &Path) {
+    let marker = home.join(HOME_MARKER);
+    if marker.exists() {
+        return;
+    }
+    match inspect(home) {
+        Ok(report) if report.other_entries.is_empty() => {
+            if let Err(e) = std::fs::write(&marker, "Created by Calimero Desktop. Do not remove.\n") {
+                log::warn!("[Calimero] Failed to write {:?}: {}", marker, e);
+            }
+        }
+        Ok(report) => log::warn!(
+            "[Calimero] Not marking {:?} as a Calimero home: it contains {}",
+            home,
+            report.other_entries.join(", ")
+        ),
+        Err(e) => log::warn!("[Calimero] Not marking {:?} as a Calimero home: {}", home, e),
+    }
+}
+
+/// Lists the nodes in `path` with their sizes and decides whether it may be deleted.
+pub fn inspect(path: &Path) -> Result<DataDirReport, String> {
+    let has_marker = path.join(HOME_MARKER).is_file();
+    let mut nodes = Vec::new();
+    let mut other_entries = Vec::new();
+    let mut total_size = 0;
+
+    let mut entries: Vec<_> = std::fs::read_dir(path)
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?
+        .filter_map(|e| e.ok())
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let entry_path = entry.path();
+        let size = dir_size(&entry_path);
+        total_size += size;
+        let is_symlink = entry.file_type().map(|t| t.is_symlink()).unwrap_or(false);
+        if !is_symlink && is_node_dir(&entry_path) {
+            nodes.push(NodeUsage { name, size });
+        } else if !ALLOWED_HIDDEN.contains(&name.as_str()) {
+            other_entries.push(name);
+        }
+    }
+
+    let (deletable, reason) = if has_marker || other_entries.is_empty() {
+        (true, None)
+    } else {
+        let listed: Vec<&str> = other_entries.iter().take(MAX_LISTED_ENTRIES).map(String::as_str).collect();
+        let more = other_entries.len().saturating_sub(MAX_LISTED_ENTRIES);
+        (
+            false,
+            Some(format!(
+                "{:?} doesn't look like a Calimero data directory: it contains {}{} which {} not node directories",
+                path,
+                listed.join(", "),
+                if more > 0 { format!(" and {} more", more) } else { String::new() },
+                if other_entries.len() == 1 { "is" } else { "are" }
+            )),
+        )
+    };
+
+    Ok(DataDirReport {
+        path: path.to_path_buf(),
+        has_marker,
+        nodes,
+        other_entries,
+        total_size,
+        deletable,
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("calimero-data-dir-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn add_node(root: &Path, name: &str) {
+        std::fs::create_dir_all(root.join(name).join("data")).unwrap();
+        std::fs::write(root.join(name).join("config.toml"), "x = 1").unwrap();
+        std::fs::write(root.join(name).join("data/db"), "0123456789").unwrap();
+    }
+
+    #[test]
+    fn test_home_with_only_nodes_is_deletable() {
+        let root = temp_root("nodes");
+        add_node(&root, "node1");
+        add_node(&root, "node2");
+        std::fs::write(root.join(".DS_Store"), "").unwrap();
+
+        let report = inspect(&root).unwrap();
+        assert!(report.deletable);
+        assert_eq!(report.nodes.len(), 2);
+        assert_eq!(report.nodes[0].size, 15);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_unrelated_content_is_refused() {
+        let root = temp_root("documents");
+        add_node(&root, "node1");
+        std::fs::write(root.join("taxes.pdf"), "private").unwrap();
+        std::fs::create_dir_all(root.join("photos")).unwrap();
+
+        let report = inspect(&root).unwrap();
+        assert!(!report.deletable);
+        assert_eq!(report.other_entries, ["photos", "taxes.pdf"]);
+        assert!(report.reason.unwrap().contains("taxes.pdf"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_dotfiles_only_is_refused() {
+        let root = temp_root("dotfiles");
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join(".ssh")).unwrap();
+        std::fs::write(root.join(".env"), "SECRET=1").unwrap();
+
+        let report = inspect(&root).unwrap();
+        assert!(!report.deletable);
+        assert_eq!(report.other_entries, [".env", ".git", ".ssh"]);
+        let _ = std::fs::remove_dir_all(&root);
+
+        std::fs::create_dir_all(&root).unwrap();
+        assert!(inspect(&root).unwrap().deletable, "an empty directory may be deleted");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_marker_allows_deletion() {
+        let root = temp_root("marker");
+        std::fs::write(root.join("notes.txt"), "").unwrap();
+        assert!(!inspect(&root).unwrap().deletable);
+        std::fs::write(root.join(HOME_MARKER), "").unwrap();
+        assert!(inspect(&root).unwrap().deletable);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_only_created_homes_are_marked() {
+        let root = temp_root("create");
+        std::fs::write(root.join("notes.txt"), "").unwrap();
+        create_calimero_home(&root).unwrap();
+        mark_calimero_home(&root);
+        assert!(!root.join(HOME_MARKER).exists());
+
+        let home = root.join("nested/.calimero");
+        create_calimero_home(&home).unwrap();
+        assert!(home.join(HOME_MARKER).is_file());
+
+        // A home from an older version is marked once it is used and holds only nodes
+        let existing = root.join("existing");
+        std::fs::create_dir_all(&existing).unwrap();
+        create_calimero_home(&existing).unwrap();
+        assert!(!existing.join(HOME_MARKER).exists());
+        add_node(&existing, "node1");
+        create_calimero_home(&existing).unwrap();
+        assert!(existing.join(HOME_MARKER).is_file());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,621 @@
+//! Headless mode (`--headless`, see `cli`): runs nodes without a window, e.g. on a build server.
+//!
+//! Nodes marked `autostart` in the node registry are started through the same launch path as
+//! the desktop app (`spawn_merod`, so pinned versions, run settings, limits and the sandbox
+//! all apply), restarted with backoff when they crash and health-checked periodically.
+//!
+//! A control API on 127.0.0.1 reports their state and starts, stops or restarts them:
+//!
+//! - `GET /health`: liveness of the headless process itself (no token needed)
+//! - `GET /nodes`, `GET /nodes/<name>`
+//! - `POST /nodes/<name>/start`, `/stop`, `/restart`
+//!
+//! Requests other than `/health` need `Authorization: Bearer <token>`, with the token read
+//! from `headless-token` in the app data directory. SIGTERM or Ctrl-C stops every node
+//! before the process exits.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{error, info, warn};
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+
+use crate::app_paths::AppPaths;
+use crate::crash_diagnosis::{self, CrashDiagnosis};
+use crate::{node_limits, node_log_level, node_registry, node_startup, storage, SpawnedMerod};
+
+pub const DEFAULT_CONTROL_PORT: u16 = 2628;
+const TOKEN_FILE: &str = "headless-token";
+
+const HEALTH_INTERVAL: Duration = Duration::from_secs(15);
+const MIN_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+/// A node that ran this long before crashing starts over at the shortest backoff.
+const STABLE_RUN: Duration = Duration::from_secs(60);
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadlessOptions {
+    pub control_port: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum NodeState {
+    Starting,
+    Running,
+    /// Waiting to restart after a crash or failed start.
+    Backoff,
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct NodeStatus {
+    node_name: String,
+    home_dir: PathBuf,
+    state: NodeState,
+    pid: Option<u32>,
+    server_port: Option<u16>,
+    /// Result of the last health check while running; None until the first one.
+    healthy: Option<bool>,
+    restarts: u32,
+    last_error: Option<String>,
+    last_crash: Option<CrashDiagnosis>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Start,
+    Stop,
+    Restart,
+}
+
+struct NodeHandle {
+    status: Arc<Mutex<NodeStatus>>,
+    commands: mpsc::Sender<Control>,
+}
+
+impl NodeHandle {
+    fn status(&self) -> NodeStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+/// Runs headless mode until SIGTERM or Ctrl-C; returns the process exit code.
+pub fn run(paths: AppPaths, options: HeadlessOptions) -> i32 {
+    match tokio::runtime::Builder::new_multi_thread().enable_all().build() {
+        Ok(runtime) => runtime.block_on(serve(paths, options)),
+        Err(e) => {
+            error!("[Headless] Failed to start the async runtime: {}", e);
+            1
+        }
+    }
+}
+
+async fn serve(paths: AppPaths, options: HeadlessOptions) -> i32 {
+    info!("[Headless] Starting Calimero {} without a window", env!("CARGO_PKG_VERSION"));
+    let app_data_dir = match paths.app_data_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            error!("[Headless] {}", e);
+            return 1;
+        }
+    };
+    let token = match load_or_create_token(&app_data_dir) {
+        Ok(token) => token,
+        Err(e) => {
+            error!("[Headless] {}", e);
+            return 1;
+        }
+    };
+    let listener = match TcpListener::bind(("127.0.0.1", options.control_port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("[Headless] Cannot listen on 127.0.0.1:{}: {}", options.control_port, e);
+            return 1;
+        }
+    };
+    info!(
+        "[Headless] Control API on http://127.0.0.1:{} (token in {:?})",
+        options.control_port,
+        app_data_dir.join(TOKEN_FILE)
+    );
+
+    let node_dirs = match node_registry::autostart_nodes(&app_data_dir) {
+        Ok(node_dirs) => node_dirs,
+        Err(e) => {
+            error!("[Headless] {}", e);
+            return 1;
+        }
+    };
+    if node_dirs.is_empty() {
+        warn!("[Headless] No nodes are set to start in headless mode; enable it in a node's run settings");
+    }
+    let storage_guard = Arc::new(storage::StorageGuard::load(&app_data_dir));
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let mut nodes = Vec::new();
+    let mut supervisors = Vec::new();
+    for node_dir in node_dirs {
+        let (Some(home_dir), Some(node_name)) = (node_dir.parent(), node_dir.file_name()) else { continue };
+        let status = Arc::new(Mutex::new(NodeStatus {
+            node_name: node_name.to_string_lossy().into_owned(),
+            home_dir: home_dir.to_path_buf(),
+            state: NodeState::Starting,
+            pid: None,
+            server_port: None,
+            healthy: None,
+            restarts: 0,
+            last_error: None,
+            last_crash: None,
+        }));
+        let (commands_tx, commands_rx) = mpsc::channel(8);
+        supervisors.push(tokio::spawn(supervise(
+            status.clone(),
+            paths.clone(),
+            storage_guard.clone(),
+            commands_rx,
+            shutdown_rx.clone(),
+        )));
+        nodes.push(NodeHandle { status, commands: commands_tx });
+    }
+    let nodes = Arc::new(nodes);
+    tokio::spawn(monitor_health(nodes.clone(), shutdown_rx.clone()));
+    tokio::spawn(serve_control_api(listener, token, nodes.clone()));
+
+    shutdown_signal().await;
+    info!("[Headless] Shutting down, stopping {} node(s)", nodes.len());
+    let _ = shutdown_tx.send(true);
+    for supervisor in supervisors {
+        let _ = supervisor.await;
+    }
+    info!("[Headless] Stopped");
+    0
+}
+
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = terminate.recv() => info!("[Headless] Received SIGTERM"),
+                    _ = tokio::signal::ctrl_c() => info!("[Headless] Received Ctrl-C"),
+                }
+                return;
+            }
+            Err(e) => warn!("[Headless] Cannot listen for SIGTERM: {}", e),
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+    info!("[Headless] Received Ctrl-C");
+}
+
+/// Delay before the `failures`-th consecutive restart: doubling from `MIN_BACKOFF`.
+fn backoff(failures: u32) -> Duration {
+    MIN_BACKOFF
+        .checked_mul(1 << failures.saturating_sub(1).min(16))
+        .map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF))
+}
+
+/// How a run of the node ended.
+enum Exit {
+    /// Stopped on request; `None` when headless mode is shutting down.
+    Requested(Option<Control>),
+    /// Exited on its own with success.
+    Finished,
+    Crashed(Box<CrashDiagnosis>, Duration),
+    FailedToStart(String),
+}
+
+/// Keeps one node running: starts it, restarts it after crashes and follows control requests
+/// until headless mode shuts down.
+async fn supervise(
+    status: Arc<Mutex<NodeStatus>>,
+    paths: AppPaths,
+    storage_guard: Arc<storage::StorageGuard>,
+    mut commands: mpsc::Receiver<Control>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let update = |f: &dyn Fn(&mut NodeStatus)| f(&mut status.lock().unwrap());
+    let (home_dir, node_name) = {
+        let status = status.lock().unwrap();
+        (status.home_dir.clone(), status.node_name.clone())
+    };
+    let mut failures = 0;
+    let mut wanted = true;
+    loop {
+        if !wanted {
+            update(&|s| {
+                s.state = NodeState::Stopped;
+                s.pid = None;
+                s.healthy = None;
+            });
+            tokio::select! {
+                control = commands.recv() => match control {
+                    Some(Control::Start | Control::Restart) => {
+                        wanted = true;
+                        failures = 0;
+                    }
+                    Some(Control::Stop) => {}
+                    None => return,
+                },
+                _ = shutdown.changed() => return,
+            }
+            continue;
+        }
+
+        update(&|s| {
+            s.state = NodeState::Starting;
+            s.healthy = None;
+        });
+        let exit = match start_node(&paths, &storage_guard, &home_dir, &node_name).await {
+            Ok((spawned, server_port)) => {
+                info!("[Headless] Node '{}' running as PID {} on port {}", node_name, spawned.pid, server_port);
+                update(&|s| {
+                    s.state = NodeState::Running;
+                    s.pid = Some(spawned.pid);
+                    s.server_port = Some(server_port);
+                    s.last_error = None;
+                });
+                watch_node(spawned, &node_name, &mut commands, &mut shutdown).await
+            }
+            Err(e) => Exit::FailedToStart(e),
+        };
+
+        let error = match exit {
+            Exit::Requested(Some(Control::Restart)) => {
+                failures = 0;
+                continue;
+            }
+            Exit::Requested(Some(_)) | Exit::Finished => {
+                wanted = false;
+                continue;
+            }
+            Exit::Requested(None) => return,
+            Exit::Crashed(diagnosis, ran) => {
+                warn!("[Headless] Node '{}' exited: {}", node_name, diagnosis.message());
+                if ran >= STABLE_RUN {
+                    failures = 0;
+                }
+                let message = diagnosis.summary.clone();
+                update(&|s| s.last_crash = Some((*diagnosis).clone()));
+                message
+            }
+            Exit::FailedToStart(e) => {
+                warn!("[Headless] Node '{}' failed to start: {}", node_name, e);
+                e
+            }
+        };
+
+        failures += 1;
+        let delay = backoff(failures);
+        info!("[Headless] Restarting node '{}' in {} s", node_name, delay.as_secs());
+        update(&|s| {
+            s.state = NodeState::Backoff;
+            s.pid = None;
+            s.healthy = None;
+            s.last_error = Some(error.clone());
+            s.restarts += 1;
+        });
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            control = commands.recv() => match control {
+                Some(Control::Start | Control::Restart) => failures = 0,
+                Some(Control::Stop) => wanted = false,
+                None => return,
+            },
+            _ = shutdown.changed() => return,
+        }
+    }
+}
+
+async fn start_node(
+    paths: &AppPaths,
+    storage_guard: &storage::StorageGuard,
+    home_dir: &Path,
+    node_name: &str,
+) -> Result<(SpawnedMerod, u16), String> {
+    let (server_port, swarm_port) = node_log_level::configured_ports(&home_dir.join(node_name))?;
+    let (server_port, swarm_port) = (server_port.unwrap_or(2528), swarm_port.unwrap_or(2428));
+    if let Some(warning) = storage_guard.check_before_start(home_dir)? {
+        warn!("[Headless] {}", warning);
+    }
+    let binary = paths.node_merod_binary(home_dir, node_name)?;
+    let spawned = crate::spawn_merod(paths, &binary, home_dir, node_name, server_port, swarm_port, false, false).await?;
+    Ok((spawned, server_port))
+}
+
+/// Waits until the node exits or is asked to stop.
+async fn watch_node(
+    spawned: SpawnedMerod,
+    node_name: &str,
+    commands: &mut mpsc::Receiver<Control>,
+    shutdown: &mut watch::Receiver<bool>,
+) -> Exit {
+    let SpawnedMerod { mut child, pid, spawned_at, log_path, log_offset, limits } = spawned;
+    let requested = loop {
+        tokio::select! {
+            status = child.wait() => {
+                node_limits::release(&limits);
+                let Ok(status) = status else { return Exit::Finished };
+                let (exit_code, signal) = crash_diagnosis::exit_details(&status);
+                if crash_diagnosis::take_expected_exit(pid) || status.success() {
+                    info!("[Headless] Node '{}' stopped (code: {:?}, signal: {:?})", node_name, exit_code, signal);
+                    return Exit::Finished;
+                }
+                let log_tail = crash_diagnosis::read_log_tail(&log_path, log_offset);
+                let diagnosis = crash_diagnosis::diagnose(node_name, pid, exit_code, signal, &log_path, log_tail);
+                let diagnosis = node_limits::attribute_breach(diagnosis, &limits);
+                return Exit::Crashed(Box::new(diagnosis), spawned_at.elapsed());
+            }
+            control = commands.recv() => match control {
+                Some(Control::Start) => continue,
+                control => break control,
+            },
+            _ = shutdown.changed() => break None,
+        }
+    };
+
+    info!("[Headless] Stopping node '{}' (PID {})", node_name, pid);
+    if let Err(e) = crate::stop_merod_pid(pid).await {
+        warn!("[Headless] {}", e);
+    }
+    // The child is bubblewrap rather than merod for sandboxed nodes
+    let _ = child.start_kill();
+    let _ = child.wait().await;
+    crash_diagnosis::take_expected_exit(pid);
+    node_limits::release(&limits);
+    Exit::Requested(requested)
+}
+
+/// Polls each running node's health endpoint, logging when it stops or starts answering.
+async fn monitor_health(nodes: Arc<Vec<NodeHandle>>, mut shutdown: watch::Receiver<bool>) {
+    let client = reqwest::Client::new();
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(HEALTH_INTERVAL) => {}
+            _ = shutdown.changed() => return,
+        }
+        for node in nodes.iter() {
+            let status = node.status();
+            let (NodeState::Running, Some(port)) = (status.state, status.server_port) else { continue };
+            let healthy = node_startup::is_healthy(&client, port).await;
+            let previous = {
+                let mut current = node.status.lock().unwrap();
+                if current.pid != status.pid {
+                    continue;
+                }
+                current.healthy.replace(healthy)
+            };
+            match (previous, healthy) {
+                (None, true) => info!("[Headless] Node '{}' is healthy", status.node_name),
+                (Some(false), true) => info!("[Headless] Node '{}' is healthy again", status.node_name),
+                (None | Some(true), false) => {
+                    warn!("[Headless] Node '{}' is not responding on port {}", status.node_name, port)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The control API token, created on first use and kept so scripts can reuse it.
+fn load_or_create_token(app_data_dir: &Path) -> Result<String, String> {
+    let path = app_data_dir.join(TOKEN_FILE);
+    if let Ok(token) = std::fs::read_to_string(&path) {
+        let token = token.trim();
+        if token.len() == 64 && token.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(token.to_string());
+        }
+    }
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("Failed to generate a control API token: {}", e))?;
+    let token = hex::encode(bytes);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(&path)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, token.as_bytes()))
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(token)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Route {
+    Health,
+    ListNodes,
+    Node(String),
+    Control(String, Control),
+}
+
+fn route(method: &str, path: &str) -> Option<Route> {
+    let path = path.split('?').next().unwrap_or(path);
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        ("GET", ["health"]) => Some(Route::Health),
+        ("GET", ["nodes"]) => Some(Route::ListNodes),
+        ("GET", ["nodes", name]) => Some(Route::Node(name.to_string())),
+        ("POST", ["nodes", name, action]) => {
+            let control = match *action {
+                "start" => Control::Start,
+                "stop" => Control::Stop,
+                "restart" => Control::Restart,
+                _ => return None,
+            };
+            Some(Route::Control(name.to_string(), control))
+        }
+        _ => None,
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    bearer_token: Option<String>,
+}
+
+/// Parses the request line and headers; bodies are not used by any endpoint.
+fn parse_request(head: &str) -> Option<Request> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let (method, path) = (request_line.next()?.to_string(), request_line.next()?.to_string());
+    let bearer_token = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| value.trim().strip_prefix("Bearer ").map(|token| token.trim().to_string()));
+    Some(Request { method, path, bearer_token })
+}
+
+/// Compares without returning early, so response timing doesn't reveal the token.
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len() && given.bytes().zip(expected.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn serve_control_api(listener: TcpListener, token: String, nodes: Arc<Vec<NodeHandle>>) {
+    let token = Arc::new(token);
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let (token, nodes) = (token.clone(), nodes.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &token, &nodes).await {
+                        warn!("[Headless] Control API request failed: {}", e);
+                    }
+                });
+            }
+            Err(e) => warn!("[Headless] Control API accept failed: {}", e),
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, token: &str, nodes: &[NodeHandle]) -> Result<(), String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_BYTES {
+            return respond(&mut stream, 431, serde_json::json!({ "error": "Request headers too large" })).await;
+        }
+        let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .map_err(|_| "timed out reading the request".to_string())?
+            .map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..read]);
+    }
+    let Some(request) = parse_request(&String::from_utf8_lossy(&head)) else {
+        return respond(&mut stream, 400, serde_json::json!({ "error": "Malformed request" })).await;
+    };
+    let Some(route) = route(&request.method, &request.path) else {
+        return respond(&mut stream, 404, serde_json::json!({ "error": "Not found" })).await;
+    };
+    if route != Route::Health && !request.bearer_token.is_some_and(|given| token_matches(&given, token)) {
+        return respond(&mut stream, 401, serde_json::json!({ "error": "Missing or invalid bearer token" })).await;
+    }
+
+    let find = |name: &str| -> Result<&NodeHandle, (u16, serde_json::Value)> {
+        let mut matches = nodes.iter().filter(|node| node.status.lock().unwrap().node_name == name);
+        match (matches.next(), matches.next()) {
+            (Some(node), None) => Ok(node),
+            (None, _) => Err((404, serde_json::json!({ "error": format!("No headless node named '{}'", name) }))),
+            (Some(_), Some(_)) => {
+                Err((409, serde_json::json!({ "error": format!("More than one headless node is named '{}'", name) })))
+            }
+        }
+    };
+    let (status, body) = match route {
+        Route::Health => (200, serde_json::json!({ "status": "ok", "nodes": nodes.len() })),
+        Route::ListNodes => (200, serde_json::json!(nodes.iter().map(NodeHandle::status).collect::<Vec<_>>())),
+        Route::Node(name) => match find(&name) {
+            Ok(node) => (200, serde_json::json!(node.status())),
+            Err(error) => error,
+        },
+        Route::Control(name, control) => match find(&name) {
+            Ok(node) => {
+                info!("[Headless] Control API: {:?} '{}'", control, name);
+                match node.commands.send(control).await {
+                    Ok(()) => (202, serde_json::json!({ "node_name": name, "accepted": true })),
+                    Err(_) => (503, serde_json::json!({ "error": "Headless mode is shutting down" })),
+                }
+            }
+            Err(error) => error,
+        },
+    };
+    respond(&mut stream, status, body).await
+}
+
+async fn respond(stream: &mut TcpStream, status: u16, body: serde_json::Value) -> Result<(), String> {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        409 => "Conflict",
+        431 => "Request Header Fields Too Large",
+        _ => "Service Unavailable",
+    };
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.map_err(|e| e.to_string())?;
+    stream.shutdown().await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff(1), MIN_BACKOFF);
+        assert_eq!(backoff(2), MIN_BACKOFF * 2);
+        assert_eq!(backoff(4), MIN_BACKOFF * 8);
+        assert_eq!(backoff(20), MAX_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_routes_and_request_parsing() {
+        assert_eq!(route("GET", "/health"), Some(Route::Health));
+        assert_eq!(route("GET", "/nodes/"), Some(Route::ListNodes));
+        assert_eq!(route("GET", "/nodes/node1?verbose=1"), Some(Route::Node("node1".to_string())));
+        assert_eq!(route("POST", "/nodes/node1/restart"), Some(Route::Control("node1".to_string(), Control::Restart)));
+        assert_eq!(route("GET", "/nodes/node1/stop"), None);
+        assert_eq!(route("POST", "/nodes/node1/delete"), None);
+
+        let request = parse_request("POST /nodes/n/stop HTTP/1.1\r\nHost: x\r\nauthorization: Bearer abc \r\n\r\n").unwrap();
+        assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/nodes/n/stop"));
+        assert_eq!(request.bearer_token.as_deref(), Some("abc"));
+        assert!(parse_request("GET /nodes HTTP/1.1\r\n\r\n").unwrap().bearer_token.is_none());
+        assert!(token_matches("abc", "abc"));
+        assert!(!token_matches("abd", "abc"));
+        assert!(!token_matches("ab", "abc"));
+    }
+
+    #[test]
+    fn test_token_is_created_once() {
+        let dir = std::env::temp_dir().join(format!("calimero-headless-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let token = load_or_create_token(&dir).unwrap();
+        assert_eq!(token.len(), 64);
+        assert_eq!(load_or_create_token(&dir).unwrap(), token);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
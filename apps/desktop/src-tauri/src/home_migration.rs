@@ -0,0 +1,376 @@
+//! Moving a Calimero home to another location, e.g. a bigger disk.
+//!
+//! The home is copied into a staging directory next to the destination, every file is
+//! checked against its source by SHA-256, and only then is the staging directory renamed
+//! into place and the new location saved as the home setting. The source is left alone;
+//! removing it is a separate step the user confirms once the switch has been saved.
+
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{data_dir, merod_manager, node_ops, node_registry, node_service, storage};
+
+/// Emitted to the calling window with `MigrationProgress` while files are copied.
+pub const MIGRATION_PROGRESS_EVENT: &str = "home-migration-progress";
+
+/// The home chosen by the last migration, in the app data directory.
+const SAVED_HOME_FILE: &str = "calimero-home.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedHome {
+    home_dir: PathBuf,
+}
+
+/// The home saved by the last completed migration, if any.
+pub fn saved_home(app_data_dir: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(app_data_dir.join(SAVED_HOME_FILE)).ok()?;
+    serde_json::from_str::<SavedHome>(&content).ok().map(|saved| saved.home_dir)
+}
+
+/// Saves `home_dir` as the home setting through a temporary file and a rename, so the
+/// setting is either the old or the new home, never a partial write.
+fn save_home(app_data_dir: &Path, home_dir: &Path) -> Result<(), String> {
+    let path = app_data_dir.join(SAVED_HOME_FILE);
+    let tmp_path = app_data_dir.join(format!("{}.tmp", SAVED_HOME_FILE));
+    let content = serde_json::to_string_pretty(&SavedHome { home_dir: home_dir.to_path_buf() })
+        .map_err(|e| format!("Failed to serialize the home setting: {}", e))?;
+    std::fs::write(&tmp_path, content).map_err(|e| format!("Failed to write {:?}: {}", tmp_path, e))?;
+    std::fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to save {:?}: {}", path, e))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationProgress {
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub files_done: usize,
+    pub total_files: usize,
+    pub current: String,
+}
+
+/// `path` made absolute through its nearest existing ancestor, so paths that don't exist
+/// yet can still be compared.
+fn normalize(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    while !existing.exists() {
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => break,
+        }
+    }
+    let mut normalized = existing.canonicalize().unwrap_or_else(|_| existing.to_path_buf());
+    for name in missing.into_iter().rev() {
+        normalized.push(name);
+    }
+    normalized
+}
+
+/// Checks that `from` can be moved to `to`: `to` must be new or empty, and neither may
+/// contain the other.
+pub fn validate_paths(from: &Path, to: &Path) -> Result<(), String> {
+    if !from.is_dir() {
+        return Err(format!("{:?} does not exist or is not a directory", from));
+    }
+    let (from_norm, to_norm) = (normalize(from), normalize(to));
+    if from_norm == to_norm {
+        return Err("The new location is the same as the current one".to_string());
+    }
+    if to_norm.starts_with(&from_norm) || from_norm.starts_with(&to_norm) {
+        return Err("The new location must not be inside the current home (or the other way round)".to_string());
+    }
+    if to.exists() {
+        let is_empty_dir = to.is_dir()
+            && std::fs::read_dir(to).map(|mut entries| entries.next().is_none()).unwrap_or(false);
+        if !is_empty_dir {
+            return Err(format!("{:?} already exists and is not empty", to));
+        }
+    }
+    Ok(())
+}
+
+/// Copies every file of `from` into `staging` and verifies each copy by SHA-256.
+pub fn copy_verified(
+    from: &Path,
+    staging: &Path,
+    mut on_progress: impl FnMut(MigrationProgress),
+) -> Result<(usize, u64), String> {
+    let files = crate::node_archive::collect_files(from, &[])?;
+    let sizes: Vec<u64> = files
+        .iter()
+        .map(|(_, path)| std::fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+        .collect();
+    let total_bytes: u64 = sizes.iter().sum();
+    let mut copied_bytes = 0;
+
+    std::fs::create_dir_all(staging).map_err(|e| format!("Failed to create {:?}: {}", staging, e))?;
+    for (i, ((relative, source), size)) in files.iter().zip(&sizes).enumerate() {
+        let target = staging.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        std::fs::copy(source, &target).map_err(|e| format!("Failed to copy {:?}: {}", source, e))?;
+        if merod_manager::sha256_file(source)? != merod_manager::sha256_file(&target)? {
+            return Err(format!("Verification failed for {}: the copy differs from the original", relative));
+        }
+        copied_bytes += size;
+        on_progress(MigrationProgress {
+            copied_bytes,
+            total_bytes,
+            files_done: i + 1,
+            total_files: files.len(),
+            current: relative.clone(),
+        });
+    }
+    Ok((files.len(), copied_bytes))
+}
+
+/// Moves the Calimero home `from` (default `~/.calimero`) to `to`. Nodes running from the old
+/// home are stopped first. Config paths and merod pins are updated for the new location, and
+/// `to` is saved as the home setting before this returns. The old home is kept; remove it
+/// with `remove_migrated_home` once the user confirms.
+#[tauri::command]
+pub async fn migrate_home(
+    from: Option<String>,
+    to: String,
+    window: tauri::Window,
+    app_handle: tauri::AppHandle,
+    merod_state: tauri::State<'_, crate::MerodState>,
+) -> Result<serde_json::Value, String> {
+    let from = crate::resolve_calimero_home(from)?;
+    let to = crate::expand_tilde(to);
+    validate_paths(&from, &to)?;
+
+    let report = data_dir::inspect(&from)?;
+    if let Some(reason) = &report.reason {
+        return Err(reason.clone());
+    }
+    node_service::ensure_no_units(&from, None)?;
+    if let Some(space) = storage::filesystem_space(&to) {
+        if space.available < report.total_size {
+            return Err(format!(
+                "Not enough free space at {:?}: {} needed, {} available",
+                to,
+                storage::format_bytes(report.total_size),
+                storage::format_bytes(space.available)
+            ));
+        }
+    }
+
+    // Stop every node running from the old home
+    let mut stopped = Vec::new();
+    for node in &report.nodes {
+        for pid in node_ops::running_node_pids(&from, &node.name) {
+            info!("[Home Migration] Stopping node '{}' (PID {})", node.name, pid);
+            crate::stop_merod_pid(pid).await?;
+            stopped.push(pid);
+        }
+    }
+    if let Ok(mut state) = merod_state.lock() {
+        state.retain(|p| !stopped.contains(&p.pid));
+    }
+
+    let staging = to.with_file_name(format!(
+        ".{}.migrating",
+        to.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+    ));
+    let _ = std::fs::remove_dir_all(&staging);
+    info!("[Home Migration] Copying {:?} to {:?} ({} bytes)", from, to, report.total_size);
+
+    let (from_clone, staging_clone, window_clone) = (from.clone(), staging.clone(), window.clone());
+    let copied = tokio::task::spawn_blocking(move || {
+        copy_verified(&from_clone, &staging_clone, |progress| {
+            let _ = window_clone.emit(MIGRATION_PROGRESS_EVENT, progress);
+        })
+    })
+    .await
+    .map_err(|e| format!("Migration task failed: {}", e))?;
+    let (files, bytes) = match copied {
+        Ok(copied) => copied,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            warn!("[Home Migration] Failed, {:?} is unchanged: {}", from, e);
+            return Err(e);
+        }
+    };
+
+    // Switch over: the new home appears in one rename, fully copied and verified
+    if to.is_dir() {
+        let _ = std::fs::remove_dir(&to);
+    }
+    if let Err(e) = std::fs::rename(&staging, &to) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(format!("Failed to move the copied home into place: {}", e));
+    }
+    // `to` was just created by the rename, so it may carry the marker
+    data_dir::mark_calimero_home(&to);
+
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    for node in &report.nodes {
+        if let Err(e) = node_ops::update_config_paths(&to.join(&node.name), &from.join(&node.name)) {
+            warn!("[Home Migration] Could not update the config of '{}': {}", node.name, e);
+        }
+        let (old_dir, new_dir) = (from.join(&node.name), to.join(&node.name));
+        if let Err(e) = node_registry::transfer(&app_data_dir, &old_dir, Some(&new_dir), true) {
+            warn!("[Home Migration] Could not copy the run settings of '{}': {}", node.name, e);
+        }
+        if let Some(version) = merod_manager::pinned_version(&app_data_dir, &from, &node.name) {
+            if let Err(e) = merod_manager::set_pin(&app_data_dir, &to, &node.name, Some(&version)) {
+                warn!("[Home Migration] Could not copy the merod pin of '{}': {}", node.name, e);
+            }
+        }
+    }
+
+    // The switch: once this is saved the app uses the new home, whatever happens to the UI
+    save_home(&app_data_dir, &to).map_err(|e| {
+        format!("The home was copied to {:?}, but the new location could not be saved ({}). {:?} is unchanged and still in use.", to, e, from)
+    })?;
+
+    info!(
+        "[Home Migration] Moved {:?} to {:?} ({} files, {} bytes); the old home is kept until removal is confirmed",
+        from, to, files, bytes
+    );
+    Ok(serde_json::json!({
+        "from": from,
+        "to": to,
+        "nodes": report.nodes.iter().map(|n| n.name.clone()).collect::<Vec<_>>(),
+        "files": files,
+        "bytes": bytes,
+        "stopped_pids": stopped,
+    }))
+}
+
+/// Checks that `from` may be removed after migrating to `to`: `to` must be the saved home
+/// and hold every node of `from`.
+pub fn check_removable_source(app_data_dir: &Path, from: &Path, to: &Path) -> Result<data_dir::DataDirReport, String> {
+    let saved = saved_home(app_data_dir).ok_or("No completed home migration was found")?;
+    if !node_ops::same_dir(&saved, to) {
+        return Err(format!("{:?} is not the current home ({:?}); the old home is kept", to, saved));
+    }
+    if node_ops::same_dir(from, to) {
+        return Err("The old and new home are the same directory".to_string());
+    }
+    let report = data_dir::inspect(from)?;
+    if let Some(reason) = &report.reason {
+        return Err(reason.clone());
+    }
+    let missing: Vec<&str> = report
+        .nodes
+        .iter()
+        .filter(|node| !data_dir::is_node_dir(&to.join(&node.name)))
+        .map(|node| node.name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("{:?} is missing nodes from the old home: {}", to, missing.join(", ")));
+    }
+    Ok(report)
+}
+
+/// Removes the old home after a completed migration to `to`, once the user confirms. Its run
+/// settings and merod pins are forgotten with it.
+#[tauri::command]
+pub async fn remove_migrated_home(from: String, to: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    let (from, to) = (crate::expand_tilde(from), crate::expand_tilde(to));
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    let report = check_removable_source(&app_data_dir, &from, &to)?;
+    for node in &report.nodes {
+        node_ops::ensure_not_running(&from, &node.name)?;
+    }
+
+    let (from_clone, app_data_dir_clone) = (from.clone(), app_data_dir.clone());
+    tokio::task::spawn_blocking(move || {
+        std::fs::remove_dir_all(&from_clone)
+            .map_err(|e| format!("Failed to remove the old home {:?}: {}", from_clone, e))?;
+        for node in &report.nodes {
+            let _ = node_registry::set(&app_data_dir_clone, &from_clone.join(&node.name), None);
+            let _ = merod_manager::set_pin(&app_data_dir_clone, &from_clone, &node.name, None);
+        }
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Removing the old home failed: {}", e))??;
+    info!("[Home Migration] Removed the old home {:?}", from);
+    Ok(())
+}
+
+/// The home saved by the last completed migration, so the UI can pick it up.
+#[tauri::command]
+pub fn get_saved_home(app_handle: tauri::AppHandle) -> Result<Option<PathBuf>, String> {
+    Ok(saved_home(&crate::get_app_data_dir(&app_handle)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("calimero-migrate-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_validate_paths() {
+        let root = temp_root("validate");
+        let home = root.join("home");
+        std::fs::create_dir_all(&home).unwrap();
+
+        assert!(validate_paths(&home, &root.join("new-home")).is_ok());
+        assert!(validate_paths(&home, &home.join("nested")).is_err());
+        assert!(validate_paths(&home, &root).is_err());
+        assert!(validate_paths(&root.join("missing"), &root.join("new-home")).is_err());
+
+        std::fs::create_dir_all(root.join("occupied")).unwrap();
+        std::fs::write(root.join("occupied/file"), "x").unwrap();
+        assert!(validate_paths(&home, &root.join("occupied")).is_err());
+        std::fs::create_dir_all(root.join("empty")).unwrap();
+        assert!(validate_paths(&home, &root.join("empty")).is_ok());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_copy_verified_reports_progress() {
+        let root = temp_root("copy");
+        let home = root.join("home");
+        std::fs::create_dir_all(home.join("node1/data")).unwrap();
+        std::fs::write(home.join("node1/config.toml"), "x = 1").unwrap();
+        std::fs::write(home.join("node1/data/db"), vec![7u8; 1000]).unwrap();
+
+        let mut events = Vec::new();
+        let (files, bytes) = copy_verified(&home, &root.join("staging"), |p| events.push(p)).unwrap();
+        assert_eq!((files, bytes), (2, 1005));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events.last().unwrap().copied_bytes, 1005);
+        assert_eq!(std::fs::read(root.join("staging/node1/data/db")).unwrap(), vec![7u8; 1000]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_source_removal_requires_saved_switch() {
+        let root = temp_root("remove");
+        let (app_data, from, to) = (root.join("app"), root.join("old"), root.join("new"));
+        std::fs::create_dir_all(&app_data).unwrap();
+        for home in [&from, &to] {
+            std::fs::create_dir_all(home.join("node1")).unwrap();
+            std::fs::write(home.join("node1/config.toml"), "x = 1").unwrap();
+        }
+
+        // Nothing saved yet: the UI may not have switched, so the source stays
+        assert!(check_removable_source(&app_data, &from, &to).is_err());
+        save_home(&app_data, &root.join("elsewhere")).unwrap();
+        assert!(check_removable_source(&app_data, &from, &to).is_err());
+
+        save_home(&app_data, &to).unwrap();
+        assert_eq!(saved_home(&app_data), Some(to.clone()));
+        assert!(check_removable_source(&app_data, &from, &to).is_ok());
+
+        std::fs::remove_dir_all(to.join("node1")).unwrap();
+        assert!(check_removable_source(&app_data, &from, &to).unwrap_err().contains("node1"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
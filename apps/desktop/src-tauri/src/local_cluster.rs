@@ -0,0 +1,447 @@
+//! Local multi-node clusters for development.
+//!
+//! `create_local_cluster` initializes `count` nodes named `<prefix>-1` … `<prefix>-<count>`
+//! with ports that are free and not configured for any other node in the home, points every node's bootstrap list at the others over
+//! localhost, starts them and waits for them to report healthy. The cluster is recorded in
+//! `<home>/.clusters/<prefix>.json` so `teardown_local_cluster` knows what to stop and remove.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::{data_dir, merod_manager, node_init, node_log_level, node_ops};
+
+pub const MAX_CLUSTER_SIZE: usize = 10;
+const DEFAULT_NAME_PREFIX: &str = "node";
+const DEFAULT_BASE_SERVER_PORT: u16 = 2528;
+const DEFAULT_BASE_SWARM_PORT: u16 = 2428;
+const CLUSTERS_DIR: &str = ".clusters";
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(30);
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterNode {
+    pub name: String,
+    pub server_port: u16,
+    pub swarm_port: u16,
+    pub peer_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterManifest {
+    pub name_prefix: String,
+    pub created_at: String,
+    pub nodes: Vec<ClusterNode>,
+}
+
+/// `<home>/.clusters/<name_prefix>.json`; the prefix must be a valid node name so the path
+/// can't leave `.clusters`.
+fn manifest_path(home: &Path, name_prefix: &str) -> Result<PathBuf, String> {
+    node_init::validate_node_name(name_prefix)?;
+    Ok(home.join(CLUSTERS_DIR).join(format!("{}.json", name_prefix)))
+}
+
+/// Loads a cluster manifest. Node names in it are validated before anything acts on them,
+/// since teardown deletes `<home>/<name>` for each.
+pub fn load_manifest(home: &Path, name_prefix: &str) -> Result<Option<ClusterManifest>, String> {
+    let path = manifest_path(home, name_prefix)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let manifest: ClusterManifest =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse {:?}: {}", path, e))?;
+    for node in &manifest.nodes {
+        node_init::validate_node_name(&node.name).map_err(|e| format!("Invalid cluster manifest {:?}: {}", path, e))?;
+    }
+    Ok(Some(manifest))
+}
+
+fn save_manifest(home: &Path, manifest: &ClusterManifest) -> Result<(), String> {
+    let path = manifest_path(home, &manifest.name_prefix)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    let content = serde_json::to_string_pretty(manifest).map_err(|e| format!("Failed to serialize cluster: {}", e))?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+pub fn node_names(name_prefix: &str, count: usize) -> Result<Vec<String>, String> {
+    if !(2..=MAX_CLUSTER_SIZE).contains(&count) {
+        return Err(format!("A local cluster needs between 2 and {} nodes", MAX_CLUSTER_SIZE));
+    }
+    let names: Vec<String> = (1..=count).map(|i| format!("{}-{}", name_prefix, i)).collect();
+    for name in &names {
+        node_init::validate_node_name(name)?;
+    }
+    Ok(names)
+}
+
+/// `count` (server, swarm) port pairs counting up from the two bases, skipping ports for which
+/// `is_free` is false and never handing out the same port twice.
+pub fn assign_ports(
+    count: usize,
+    base_server_port: u16,
+    base_swarm_port: u16,
+    is_free: impl Fn(u16) -> bool,
+) -> Result<Vec<(u16, u16)>, String> {
+    let mut taken: Vec<u16> = Vec::new();
+    let next_free = |from: &mut u32, taken: &mut Vec<u16>| -> Result<u16, String> {
+        while let Ok(port) = u16::try_from(*from) {
+            *from += 1;
+            if port != 0 && !taken.contains(&port) && is_free(port) {
+                taken.push(port);
+                return Ok(port);
+            }
+        }
+        Err("Ran out of ports to assign to the cluster".to_string())
+    };
+    let (mut server, mut swarm) = (u32::from(base_server_port), u32::from(base_swarm_port));
+    (0..count)
+        .map(|_| {
+            let server_port = next_free(&mut server, &mut taken)?;
+            let swarm_port = next_free(&mut swarm, &mut taken)?;
+            Ok((server_port, swarm_port))
+        })
+        .collect()
+}
+
+/// Server and swarm ports configured for the nodes already in `home`, whether or not they
+/// are running.
+pub fn home_ports(home: &Path) -> Vec<u16> {
+    let Ok(entries) = std::fs::read_dir(home) else { return Vec::new() };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| data_dir::is_node_dir(path))
+        .filter_map(|path| node_log_level::configured_ports(&path).ok())
+        .flat_map(|(server, swarm)| [server, swarm])
+        .flatten()
+        .collect()
+}
+
+pub fn local_multiaddr(swarm_port: u16, peer_id: &str) -> String {
+    format!("/ip4/127.0.0.1/tcp/{}/p2p/{}", swarm_port, peer_id)
+}
+
+/// The peer id merod wrote to the node's `[identity]` section.
+fn peer_id(node_dir: &Path) -> Result<String, String> {
+    node_ops::read_config(node_dir)?
+        .as_ref()
+        .and_then(|c| c.get("identity"))
+        .and_then(|i| i.get("peer_id"))
+        .and_then(|p| p.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| format!("{:?} has no identity.peer_id in its config", node_dir))
+}
+
+/// Replaces the node's bootstrap list with `boot_nodes`.
+pub fn set_bootstrap_nodes(node_dir: &Path, boot_nodes: &[String]) -> Result<(), String> {
+    let mut config = node_ops::read_config(node_dir)?
+        .ok_or_else(|| format!("{:?} has no config.toml", node_dir))?;
+    let table = config.as_table_mut().ok_or("config.toml is not a table")?;
+    let bootstrap = table
+        .entry("bootstrap")
+        .or_insert_with(|| toml::Value::Table(toml::map::Map::new()))
+        .as_table_mut()
+        .ok_or("[bootstrap] in config.toml is not a table")?;
+    bootstrap.insert(
+        "nodes".to_string(),
+        toml::Value::Array(boot_nodes.iter().cloned().map(toml::Value::String).collect()),
+    );
+    node_ops::write_config(node_dir, &config)
+}
+
+async fn wait_until_healthy(app_handle: &tauri::AppHandle, server_port: u16) -> bool {
+    let url = crate::node_startup::node_url(server_port);
+    let deadline = Instant::now() + HEALTH_TIMEOUT;
+    while Instant::now() < deadline {
+        if let Ok(health) = crate::check_merod_health(url.clone(), app_handle.state()).await {
+            if health["healthy"].as_bool() == Some(true) {
+                return true;
+            }
+        }
+        tokio::time::sleep(HEALTH_POLL_INTERVAL).await;
+    }
+    false
+}
+
+fn remove_nodes(app_handle: &tauri::AppHandle, home: &Path, names: &[String]) {
+    let app_data_dir = crate::get_app_data_dir(app_handle).ok();
+    for name in names {
+        let node_dir = home.join(name);
+        if node_dir.exists() {
+            if let Err(e) = std::fs::remove_dir_all(&node_dir) {
+                warn!("[Local Cluster] Failed to remove {:?}: {}", node_dir, e);
+            }
+        }
+        if let Some(app_data_dir) = &app_data_dir {
+            let _ = merod_manager::set_pin(app_data_dir, home, name, None);
+            let _ = crate::node_registry::set(app_data_dir, &node_dir, None);
+        }
+    }
+}
+
+/// Creates, wires up and starts `count` local nodes, then reports each node's health.
+#[tauri::command]
+pub async fn create_local_cluster(
+    count: usize,
+    base_server_port: Option<u16>,
+    base_swarm_port: Option<u16>,
+    name_prefix: Option<String>,
+    home_dir: Option<String>,
+    merod_version: Option<String>,
+    app_handle: tauri::AppHandle,
+    window: tauri::Window,
+) -> Result<serde_json::Value, String> {
+    let name_prefix = name_prefix.unwrap_or_else(|| DEFAULT_NAME_PREFIX.to_string());
+    let names = node_names(&name_prefix, count)?;
+    let home = crate::resolve_calimero_home(home_dir)?;
+    if load_manifest(&home, &name_prefix)?.is_some() {
+        return Err(format!(
+            "A cluster named '{}' already exists in {:?}. Tear it down first.",
+            name_prefix, home
+        ));
+    }
+    if let Some(existing) = names.iter().find(|name| home.join(name).exists()) {
+        return Err(format!("Node '{}' already exists in {:?}", existing, home));
+    }
+    // Stopped nodes don't hold their ports, but would clash with the cluster once started
+    let reserved = home_ports(&home);
+    let ports = assign_ports(
+        count,
+        base_server_port.unwrap_or(DEFAULT_BASE_SERVER_PORT),
+        base_swarm_port.unwrap_or(DEFAULT_BASE_SWARM_PORT),
+        |port| !reserved.contains(&port) && node_init::port_is_free(port),
+    )?;
+
+    // Initialize every node; peer ids are only known once merod has generated them
+    let mut nodes = Vec::new();
+    for (name, (server_port, swarm_port)) in names.iter().zip(&ports) {
+        info!("[Local Cluster] Initializing '{}' (server {}, swarm {})", name, server_port, swarm_port);
+        let options = node_init::NodeInitOptions {
+            server_port: Some(*server_port),
+            swarm_port: Some(*swarm_port),
+            ..Default::default()
+        };
+        let result = crate::init_merod_node(
+            name.clone(),
+            Some(home.to_string_lossy().into_owned()),
+            merod_version.clone(),
+            Some(options),
+            app_handle.clone(),
+            window.clone(),
+        )
+        .await
+        .and_then(|_| peer_id(&home.join(name)));
+        match result {
+            Ok(peer_id) => nodes.push(ClusterNode {
+                name: name.clone(),
+                server_port: *server_port,
+                swarm_port: *swarm_port,
+                peer_id,
+            }),
+            Err(e) => {
+                remove_nodes(&app_handle, &home, &names);
+                return Err(format!("Failed to initialize '{}': {}", name, e));
+            }
+        }
+    }
+
+    for node in &nodes {
+        let peers: Vec<String> = nodes
+            .iter()
+            .filter(|other| other.name != node.name)
+            .map(|other| local_multiaddr(other.swarm_port, &other.peer_id))
+            .collect();
+        if let Err(e) = set_bootstrap_nodes(&home.join(&node.name), &peers) {
+            remove_nodes(&app_handle, &home, &names);
+            return Err(format!("Failed to configure '{}': {}", node.name, e));
+        }
+    }
+
+    let manifest = ClusterManifest {
+        name_prefix: name_prefix.clone(),
+        created_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        nodes,
+    };
+    save_manifest(&home, &manifest)?;
+
+    let mut statuses = Vec::new();
+    for node in &manifest.nodes {
+        let started = crate::start_merod(
+            Some(node.server_port),
+            Some(node.swarm_port),
+            Some(home.to_string_lossy().into_owned()),
+            Some(node.name.clone()),
+            None,
+            None,
+            app_handle.clone(),
+            app_handle.state(),
+            app_handle.state(),
+            app_handle.state(),
+        )
+        .await;
+        statuses.push(started);
+    }
+
+    let mut report = Vec::new();
+    for (node, started) in manifest.nodes.iter().zip(statuses) {
+        let (pid, healthy, error) = match started {
+            Ok(started) => (Some(started.pid), wait_until_healthy(&app_handle, node.server_port).await, None),
+            Err(e) => {
+                warn!("[Local Cluster] Failed to start '{}': {}", node.name, e);
+                (None, false, Some(e))
+            }
+        };
+        report.push(serde_json::json!({
+            "name": node.name,
+            "pid": pid,
+            "server_port": node.server_port,
+            "swarm_port": node.swarm_port,
+            "peer_id": node.peer_id,
+            "url": crate::node_startup::node_url(node.server_port),
+            "healthy": healthy,
+            "error": error,
+        }));
+    }
+    let healthy = report.iter().all(|n| n["healthy"] == true);
+    info!(
+        "[Local Cluster] Cluster '{}' with {} nodes is {}",
+        name_prefix,
+        count,
+        if healthy { "healthy" } else { "not fully healthy" }
+    );
+    Ok(serde_json::json!({
+        "name_prefix": name_prefix,
+        "home": home,
+        "healthy": healthy,
+        "nodes": report,
+    }))
+}
+
+/// Stops every node of the cluster and, unless `keep_nodes` is true, deletes their directories.
+#[tauri::command]
+pub async fn teardown_local_cluster(
+    name_prefix: String,
+    home_dir: Option<String>,
+    keep_nodes: Option<bool>,
+    app_handle: tauri::AppHandle,
+    merod_state: tauri::State<'_, crate::MerodState>,
+) -> Result<serde_json::Value, String> {
+    let home = crate::resolve_calimero_home(home_dir)?;
+    let manifest = load_manifest(&home, &name_prefix)?
+        .ok_or_else(|| format!("No cluster named '{}' in {:?}", name_prefix, home))?;
+    let names: Vec<String> = manifest.nodes.iter().map(|n| n.name.clone()).collect();
+
+    let mut stopped = Vec::new();
+    for name in &names {
+        for pid in node_ops::running_node_pids(&home, name) {
+            info!("[Local Cluster] Stopping '{}' (PID {})", name, pid);
+            crate::stop_merod_pid(pid).await?;
+            stopped.push(pid);
+        }
+    }
+    if let Ok(mut state) = merod_state.lock() {
+        state.retain(|p| !stopped.contains(&p.pid));
+    }
+
+    let removed = !keep_nodes.unwrap_or(false);
+    if removed {
+        remove_nodes(&app_handle, &home, &names);
+    }
+    let path = manifest_path(&home, &name_prefix)?;
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to remove {:?}: {}", path, e))?;
+
+    info!("[Local Cluster] Tore down cluster '{}'", name_prefix);
+    Ok(serde_json::json!({
+        "name_prefix": name_prefix,
+        "nodes": names,
+        "stopped_pids": stopped,
+        "removed": removed,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assign_ports_skips_busy_and_duplicate_ports() {
+        let ports = assign_ports(3, 2528, 2428, |p| p != 2529).unwrap();
+        assert_eq!(ports, vec![(2528, 2428), (2530, 2429), (2531, 2430)]);
+
+        // Overlapping ranges must not hand out a port twice
+        let ports = assign_ports(2, 3000, 3001, |_| true).unwrap();
+        assert_eq!(ports, vec![(3000, 3001), (3002, 3003)]);
+
+        assert!(assign_ports(2, 65535, 65534, |_| true).is_err());
+    }
+
+    #[test]
+    fn test_ports_of_existing_nodes_are_skipped() {
+        let home = std::env::temp_dir().join(format!("calimero-cluster-ports-{}", std::process::id()));
+        let node = home.join("node1");
+        std::fs::create_dir_all(&node).unwrap();
+        std::fs::write(
+            node.join("config.toml"),
+            "[server]\nlisten = [\"/ip4/127.0.0.1/tcp/2528\"]\n\n[swarm]\nlisten = [\"/ip4/0.0.0.0/tcp/2428\"]\n",
+        )
+        .unwrap();
+
+        let mut reserved = home_ports(&home);
+        reserved.sort_unstable();
+        assert_eq!(reserved, vec![2428, 2528]);
+        let ports = assign_ports(2, 2528, 2428, |port| !reserved.contains(&port)).unwrap();
+        assert_eq!(ports, vec![(2529, 2429), (2530, 2430)]);
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn test_node_names() {
+        assert_eq!(node_names("dev", 3).unwrap(), ["dev-1", "dev-2", "dev-3"]);
+        assert!(node_names("dev", 1).is_err());
+        assert!(node_names("dev", MAX_CLUSTER_SIZE + 1).is_err());
+        assert!(node_names("../x", 2).is_err());
+    }
+
+    #[test]
+    fn test_set_bootstrap_nodes() {
+        let dir = std::env::temp_dir().join(format!("calimero-cluster-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            "[identity]\npeer_id = \"12D3KooA\"\n\n[bootstrap]\nnodes = [\"/dns/boot.example/tcp/4001/p2p/X\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(peer_id(&dir).unwrap(), "12D3KooA");
+        let peers = vec![local_multiaddr(2429, "12D3KooB")];
+        set_bootstrap_nodes(&dir, &peers).unwrap();
+        let config = node_ops::read_config(&dir).unwrap().unwrap();
+        assert_eq!(
+            config["bootstrap"]["nodes"].as_array().unwrap()[0].as_str(),
+            Some("/ip4/127.0.0.1/tcp/2429/p2p/12D3KooB")
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_manifest_names_are_validated() {
+        let home = std::env::temp_dir().join(format!("calimero-cluster-manifest-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(CLUSTERS_DIR)).unwrap();
+        assert!(load_manifest(&home, "../../x").is_err());
+        assert!(load_manifest(&home, "dev").unwrap().is_none());
+
+        std::fs::write(
+            home.join(CLUSTERS_DIR).join("dev.json"),
+            r#"{"name_prefix":"dev","created_at":"","nodes":[{"name":"../../Documents","server_port":1,"swarm_port":2,"peer_id":""}]}"#,
+        )
+        .unwrap();
+        assert!(load_manifest(&home, "dev").is_err());
+        let _ = std::fs::remove_dir_all(&home);
+    }
+}
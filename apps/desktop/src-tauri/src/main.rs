@@ -6,7 +6,42 @@ use tauri::{CustomMenuItem, SystemTray, SystemTrayEvent, SystemTrayMenu};
 use serde::{Deserialize, Serialize};
 use log::{debug, info, warn};
 
-#[derive(Debug, Serialize, Deserialize)]
+mod app_paths;
+mod backend_log;
+mod capabilities;
+mod cli;
+mod crash_diagnosis;
+mod data_dir;
+mod headless;
+mod home_migration;
+mod local_cluster;
+mod merod_info;
+mod merod_integrity;
+mod merod_manager;
+mod merod_sandbox;
+mod navigation;
+mod node_archive;
+mod node_init;
+mod node_limits;
+mod node_log_level;
+mod node_ops;
+mod node_profiles;
+mod node_registry;
+mod node_service;
+mod node_startup;
+mod proxy_fixtures;
+mod proxy_inspector;
+mod storage;
+mod support_bundle;
+
+use app_paths::AppPaths;
+use capabilities::{WindowCapabilities, WindowRole};
+use navigation::{NavigationDecision, NavigationPolicies, NavigationPolicy};
+use node_profiles::NodeProfiles;
+use proxy_fixtures::{ProxyMockState, ProxyMode};
+use proxy_inspector::ProxyInspector;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct HttpRequest {
     url: String,
     method: String,
@@ -14,44 +49,24 @@ struct HttpRequest {
     body: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct HttpResponse {
     status: u16,
     headers: std::collections::HashMap<String, String>,
     body: String,
 }
 
-/// Parses --open-app-url and --open-app-name from CLI args (used when launched from a desktop shortcut).
-fn parse_open_app_args() -> Option<(String, String)> {
-    let args: Vec<String> = std::env::args().collect();
-    let mut url = None;
-    let mut name = None;
-    let mut i = 0;
-    while i < args.len() {
-        if args[i] == "--open-app-url" && i + 1 < args.len() {
-            url = Some(args[i + 1].clone());
-            i += 2;
-            continue;
-        }
-        if args[i] == "--open-app-name" && i + 1 < args.len() {
-            name = Some(args[i + 1].clone());
-            i += 2;
-            continue;
-        }
-        i += 1;
-    }
-    url.map(|u| (u, name.unwrap_or_else(|| "Application".to_string())))
-}
-
 /// State for app to open when launched from a desktop shortcut (read by frontend on load).
 pub struct PendingOpenApp(pub std::sync::Mutex<Option<(String, String)>>);
 
 /// Validates that a URL is allowed for proxying
 /// 
 /// Allowed URLs:
-/// - Configured node URL (from settings, typically http://localhost:2528 or custom HTTP localhost)
+/// - Configured node URL (from settings, typically http://localhost:2528, or an https:// remote
+///   node whose TLS settings come from its node profile)
 /// 
-/// Only HTTP localhost URLs are proxied. HTTPS registries don't need proxying (no mixed content issues).
+/// Without a configured node URL only HTTP localhost URLs are proxied. HTTPS registries don't need
+/// proxying (no mixed content issues).
 /// 
 /// This function prevents hostname spoofing attacks like:
 /// - http://localhost:2528.evil.com (invalid hostname)
@@ -141,11 +156,79 @@ pub(crate) fn validate_allowed_url(url: &str, configured_node_url: Option<&str>)
 }
 
 #[tauri::command]
-async fn proxy_http_request(request: HttpRequest, configured_node_url: Option<String>) -> Result<HttpResponse, String> {
-    use reqwest;
-    
-    // Validate URL before processing (pass configured node URL if available)
+async fn proxy_http_request(
+    request: HttpRequest,
+    configured_node_url: Option<String>,
+    window: tauri::Window,
+    inspector: tauri::State<'_, ProxyInspector>,
+    mock_state: tauri::State<'_, ProxyMockState>,
+    profiles: tauri::State<'_, NodeProfiles>,
+    capabilities: tauri::State<'_, WindowCapabilities>,
+) -> Result<HttpResponse, String> {
+    // App windows may only reach the node they were opened for; the URL the page passes is
+    // ignored for them. Only the dashboard chooses its own node URL.
+    let configured_node_url = match capabilities.role_of(window.label()) {
+        Some(WindowRole::Main) => configured_node_url,
+        _ => Some(capabilities.node_url_of(window.label()).ok_or_else(|| {
+            format!("Window '{}' has no node URL to proxy to", window.label())
+        })?),
+    };
     validate_allowed_url(&request.url, configured_node_url.as_deref())?;
+
+    // Client configured from the node's profile (custom CA, pinned certificate, mTLS)
+    let client = profiles.client_for(&request.url)?;
+
+    // Capture the request half up front; the inspector only keeps it when enabled
+    let pending = inspector.settings().enabled.then(|| proxy_inspector::PendingEntry {
+        window: window.label().to_string(),
+        started_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        started: std::time::Instant::now(),
+        method: request.method.clone(),
+        url: request.url.clone(),
+        request_headers: request.headers.clone().unwrap_or_default(),
+        request_body: request.body.clone(),
+    });
+
+    let mock = mock_state.config();
+    let result = match (mock.mode, mock.fixture_dir) {
+        // Replay never touches the network
+        (ProxyMode::Replay, Some(dir)) => {
+            let replay_request = request.clone();
+            tokio::task::spawn_blocking(move || proxy_fixtures::replay(&dir, &replay_request))
+                .await
+                .map_err(|e| format!("Fixture replay failed: {}", e))?
+        }
+        (ProxyMode::Record, Some(dir)) => {
+            let recorded_request = request.clone();
+            let result = forward_http_request(&client, request).await;
+            if let Ok(response) = &result {
+                let response = response.clone();
+                let recorded = tokio::task::spawn_blocking(move || proxy_fixtures::record(&dir, &recorded_request, &response)).await;
+                match recorded {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => warn!("[Tauri Proxy] {}", e),
+                    Err(e) => warn!("[Tauri Proxy] Fixture recording failed: {}", e),
+                }
+            }
+            result
+        }
+        (ProxyMode::Live, _) => forward_http_request(&client, request).await,
+        (mode, None) => Err(format!("Proxy is in {:?} mode but no fixture directory is configured", mode)),
+    };
+
+    if let Some(pending) = pending {
+        match &result {
+            Ok(response) => inspector.record(pending, Ok((response.status, &response.headers, response.body.as_str()))),
+            Err(error) => inspector.record(pending, Err(error.as_str())),
+        }
+    }
+
+    result
+}
+
+/// Sends an already validated proxy request to the node and collects the response.
+async fn forward_http_request(client: &reqwest::Client, request: HttpRequest) -> Result<HttpResponse, String> {
+    use reqwest;
     
     // Parse URL to determine what Host header to use
     let parsed_original = url::Url::parse(&request.url)
@@ -166,31 +249,21 @@ async fn proxy_http_request(request: HttpRequest, configured_node_url: Option<St
     // The issue might be that normalizing breaks something
     let normalized_url = request.url.clone();
     
-    info!("[Tauri Proxy] Proxying request: {} {}", request.method, request.url);
+    info!("[Tauri Proxy] Proxying request: {} {}", request.method, proxy_inspector::redact_url(&request.url));
     if let Some(ref headers) = request.headers {
         debug!("[Tauri Proxy] Request headers count: {}", headers.len());
-        // Log Authorization header if present (but don't log the full token)
-        if let Some(auth_header) = headers.get("Authorization").or_else(|| headers.get("authorization")) {
-            // Safely truncate to first 20 characters (UTF-8 safe)
-            let preview: String = auth_header.chars().take(20).collect();
-            debug!("[Tauri Proxy] Authorization header present: {}...", preview);
+        // Never log credential values, only whether they are present
+        if headers.keys().any(|k| k.eq_ignore_ascii_case("authorization")) {
+            debug!("[Tauri Proxy] Authorization header present");
         } else {
             warn!("[Tauri Proxy] No Authorization header found!");
         }
         // Log all header keys for debugging
         debug!("[Tauri Proxy] Header keys: {:?}", headers.keys().collect::<Vec<_>>());
-        debug!("[Tauri Proxy] Request headers: {:?}", headers);
     } else {
         warn!("[Tauri Proxy] No headers in request!");
     }
     
-    // Create HTTP client with proper configuration
-    let client = reqwest::Client::builder()
-        .danger_accept_invalid_certs(false) // Use proper cert validation
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Failed to initialize HTTP client: {}. This may indicate a system configuration issue. Please try again.", e))?;
-    
     // Build request (use normalized URL)
     let mut req_builder = match request.method.as_str() {
         "GET" => client.get(&normalized_url),
@@ -218,14 +291,12 @@ async fn proxy_http_request(request: HttpRequest, configured_node_url: Option<St
             if key_lower == "host" {
                 has_host = true;
             }
-            // Log header being added (truncate value for security, UTF-8 safe)
-            let value_preview = if value.len() > 50 {
-                let preview: String = value.chars().take(50).collect();
-                format!("{}...", preview)
+            // Log header being added (credential values are redacted)
+            if proxy_inspector::is_sensitive_header(key) {
+                debug!("[Tauri Proxy] Adding header: '{}' = [REDACTED]", key);
             } else {
-                value.clone()
-            };
-            debug!("[Tauri Proxy] Adding header: '{}' = '{}'", key, value_preview);
+                debug!("[Tauri Proxy] Adding header: '{}' = '{}'", key, value);
+            }
             // Add header directly - reqwest will handle validation
             req_builder = req_builder.header(key, value);
         }
@@ -252,6 +323,7 @@ async fn proxy_http_request(request: HttpRequest, configured_node_url: Option<St
     if let Some(body) = request.body {
         req_builder = req_builder.body(body);
     }
+    req_builder = req_builder.timeout(std::time::Duration::from_secs(30));
     
     // Send request
     let response = req_builder.send()
@@ -461,6 +533,10 @@ async fn create_app_window(
     node_url: Option<String>,
 ) -> Result<(), String> {
     use tauri::{WindowBuilder, Manager};
+
+    if window_label == capabilities::MAIN_WINDOW_LABEL {
+        return Err("The window label 'main' is reserved for the dashboard window.".to_string());
+    }
     
     // Parse URL to get domain for IPC scope configuration
     let parsed_url = url.parse::<url::Url>()
@@ -479,7 +555,22 @@ async fn create_app_window(
     let node_url_to_use = node_url.as_deref().unwrap_or("http://localhost:2528");
     // Replace placeholder in script with actual node URL
     proxy_script = proxy_script.replace("__CONFIGURED_NODE_URL__", node_url_to_use);
-    
+    // Intercept target=_blank links and window.open so they go through the navigation policy
+    proxy_script.push('\n');
+    proxy_script.push_str(include_str!("navigation_script.js"));
+
+    // Same-origin (and node) navigation stays in the window; other origins open in the system browser
+    let navigation_policy = NavigationPolicy::for_app(&parsed_url, Some(node_url_to_use));
+    app_handle.state::<NavigationPolicies>().register(&window_label, navigation_policy.clone());
+    let navigation_handle = app_handle.clone();
+    let navigation_label = window_label.clone();
+    
+    // Register the window as an app window before it loads, so the proxy script's first
+    // calls are checked against the app capability set rather than denied outright
+    app_handle
+        .state::<WindowCapabilities>()
+        .register(&window_label, WindowRole::App, Some(node_url_to_use));
+
     // Create window with proxy script injected BEFORE page loads
     let window = WindowBuilder::new(
         &app_handle,
@@ -492,17 +583,29 @@ async fn create_app_window(
     .resizable(true)
     .center()
     .initialization_script(&proxy_script) // Inject script with configured node URL
+    .on_navigation(move |target| match navigation_policy.decide(&target) {
+        NavigationDecision::Allow => true,
+        decision => {
+            let _ = navigation::redirect_navigation(&navigation_handle, &navigation_label, &target, decision);
+            false
+        }
+    })
     .build()
-    .map_err(|e| format!("Failed to create window '{}' for URL '{}': {}. Please check that the window label is unique and try again.", title, url, e))?;
+    .map_err(|e| {
+        app_handle.state::<WindowCapabilities>().forget(&window_label);
+        app_handle.state::<NavigationPolicies>().forget(&window_label);
+        format!("Failed to create window '{}' for URL '{}': {}. Please check that the window label is unique and try again.", title, url, e)
+    })?;
     
     // Configure IPC scope BEFORE showing window
-    // This allows windows with unique labels (domain + timestamp) to access Tauri IPC
+    // This allows windows with unique labels (domain + timestamp) to reach our commands.
+    // The Tauri core API is NOT enabled; which commands the app may call is decided by
+    // the capability check in `capabilities::guard_invoke_handler`.
     let remote_access = tauri::ipc::RemoteDomainAccessScope::new(domain)
-        .add_window(&window_label)
-        .enable_tauri_api();
+        .add_window(&window_label);
     app_handle.ipc_scope().configure_remote_access(remote_access);
     
-    info!("[Tauri] Configured IPC scope for domain: {} on window: {}", domain, window_label);
+    info!("[Tauri] Configured IPC scope for domain: {} on window: {} (app capabilities)", domain, window_label);
     
     // Show the window AFTER IPC scope is configured
     window.show().map_err(|e| format!("Failed to display window '{}': {}. The window may have been closed or there may be a system issue.", title, e))?;
@@ -563,205 +666,226 @@ use tokio::process::Command;
 struct MerodProcess {
     pid: u32,
     port: u16,
+    /// merod version reported by `merod --version`, if it could be determined
+    version: Option<String>,
+    home_dir: std::path::PathBuf,
+    node_name: String,
+    /// Resource limits the process was started with
+    limits: node_limits::AppliedLimits,
 }
 
 type MerodState = Arc<Mutex<Vec<MerodProcess>>>;
 
 /// Get the path to the bundled merod binary
 fn get_merod_binary_path(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    // Access the bundled resource
-    let resource_path = app_handle
-        .path_resolver()
-        .resolve_resource("merod/merod")
-        .ok_or("Failed to resolve merod resource")?;
-    
-    if !resource_path.exists() {
-        return Err(format!("Merod resource not found at {:?}", resource_path));
-    }
-    
-    Ok(resource_path)
+    AppPaths::from_handle(app_handle).bundled_merod()
 }
 
-/// Get the app data directory for storing merod data
-fn get_app_data_dir(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    let app_data_dir = app_handle
-        .path_resolver()
-        .app_data_dir()
-        .ok_or("Failed to get app data directory")?;
-    
-    std::fs::create_dir_all(&app_data_dir)
-        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-    
-    Ok(app_data_dir)
+/// Get the merod binary for a node: the installed version it is pinned to, if any,
+/// otherwise the bundled binary
+fn get_node_merod_binary(
+    app_handle: &tauri::AppHandle,
+    home_dir: &std::path::Path,
+    node_name: &str,
+) -> Result<std::path::PathBuf, String> {
+    AppPaths::from_handle(app_handle).node_merod_binary(home_dir, node_name)
 }
 
-#[tauri::command]
-async fn start_merod(
-    server_port: Option<u16>,
-    swarm_port: Option<u16>,
-    data_dir: Option<String>,
-    node_name: Option<String>,
-    debug_logs: Option<bool>,
-    app_handle: tauri::AppHandle,
-    merod_state: tauri::State<'_, MerodState>,
-) -> Result<String, String> {
-    let server_port = server_port.unwrap_or(2528);
-    let swarm_port = swarm_port.unwrap_or(2428);
-    
-    // Only stop a process that uses the same server_port (port conflict)
-    let existing_on_port: Option<u32> = {
-        let state = merod_state.lock().unwrap();
-        state.iter().find(|p| p.port == server_port).map(|p| p.pid)
+/// Check an installed merod version against the hash recorded when it was installed
+fn verify_installed_merod(installed: &merod_manager::InstalledMerod) -> Result<(), String> {
+    let manifest = merod_integrity::BinaryManifest {
+        version: Some(installed.version.clone()),
+        sha256: installed.binary_sha256.clone(),
+        size: None,
+        signature: None,
     };
+    merod_integrity::verify_binary(&installed.path, &manifest, None)?;
+    debug!("[Merod] Verified merod {} ({})", installed.version, installed.binary_sha256);
+    Ok(())
+}
 
-    if let Some(pid) = existing_on_port {
-        info!("[Merod] Stopping existing process on port {} (PID: {}) before starting new one", server_port, pid);
-        #[cfg(unix)]
-        {
-            use std::process::Command;
-            let _ = Command::new("kill")
-                .arg("-TERM")
-                .arg(pid.to_string())
-                .output();
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-            let _ = Command::new("kill")
-                .arg("-9")
-                .arg(pid.to_string())
-                .output();
-        }
-        #[cfg(windows)]
-        {
-            use std::process::Command;
-            let _ = Command::new("taskkill")
-                .arg("/PID")
-                .arg(pid.to_string())
-                .arg("/F")
-                .output();
+/// Check the bundled merod binary against the manifest shipped next to it
+fn verify_bundled_merod(app_handle: &tauri::AppHandle, binary: &std::path::Path) -> Result<(), String> {
+    verify_bundled_merod_with(&AppPaths::from_handle(app_handle), binary)
+}
+
+/// Check the bundled merod binary against the manifest shipped next to it. Release builds
+/// refuse to run a bundled binary without a manifest; debug builds only warn so a locally
+/// downloaded merod still works.
+fn verify_bundled_merod_with(paths: &AppPaths, binary: &std::path::Path) -> Result<(), String> {
+    let manifest_path = paths
+        .resolve_resource(merod_integrity::BUNDLED_MANIFEST_RESOURCE)
+        .filter(|path| path.exists());
+    let Some(manifest_path) = manifest_path else {
+        if cfg!(debug_assertions) {
+            warn!("[Merod] No manifest for the bundled merod binary; skipping integrity check");
+            return Ok(());
         }
-        let mut state = merod_state.lock().unwrap();
-        state.retain(|p| p.pid != pid);
-    }
-    
-    // Get bundled merod binary
-    let merod_binary = get_merod_binary_path(&app_handle)?;
-    
-    // Prepare home directory (where .calimero folder is, e.g., ~/.calimero)
-    let home_dir_path = if let Some(dir) = data_dir {
-        // Expand ~ if present
-        let expanded = if dir.starts_with("~") {
-            if let Some(home) = dirs::home_dir() {
-                dir.replacen("~", &home.to_string_lossy(), 1)
-            } else {
-                dir
-            }
-        } else {
-            dir
-        };
-        std::path::PathBuf::from(expanded)
+        return Err("The bundled merod binary has no integrity manifest. Reinstall the app.".to_string());
+    };
+
+    let manifest = merod_integrity::read_manifest(&manifest_path)?;
+    merod_integrity::verify_binary(binary, &manifest, Some(paths.updater_pubkey()))?;
+    debug!(
+        "[Merod] Verified bundled merod {} ({}{})",
+        manifest.version.as_deref().unwrap_or("unknown version"),
+        manifest.sha256,
+        if manifest.signature.is_some() { ", signed" } else { "" }
+    );
+    Ok(())
+}
+
+/// Resolve the Calimero home directory (where node directories live), expanding `~`.
+/// Defaults to `~/.calimero`.
+fn resolve_calimero_home(home_dir: Option<String>) -> Result<std::path::PathBuf, String> {
+    let path = if let Some(dir) = home_dir {
+        expand_tilde(dir)
     } else {
         dirs::home_dir()
             .ok_or("Failed to get home directory")?
             .join(".calimero")
     };
-    
-    std::fs::create_dir_all(&home_dir_path)
-        .map_err(|e| format!("Failed to create home directory: {}", e))?;
-    
-    // Update config.toml with the specified ports if node_name is provided
-    if let Some(name) = &node_name {
-        let node_dir = home_dir_path.join(name);
-        let config_path = node_dir.join("config.toml");
+    Ok(path)
+}
 
-        // Verify the node is initialized (config.toml exists)
-        if !config_path.exists() {
-            return Err(format!(
-                "Node '{}' is not initialized. config.toml not found at {:?}. Please run init first.",
-                name, config_path
-            ));
+/// Expand a leading `~` to the user's home directory
+fn expand_tilde(path: String) -> std::path::PathBuf {
+    let expanded = if path.starts_with("~") {
+        if let Some(home) = dirs::home_dir() {
+            path.replacen("~", &home.to_string_lossy(), 1)
+        } else {
+            path
         }
+    } else {
+        path
+    };
+    std::path::PathBuf::from(expanded)
+}
 
-        // Config exists, proceed with port updates
-        {
-            // Read existing config
-            let config_content = std::fs::read_to_string(&config_path)
-                .map_err(|e| format!("Failed to read config.toml: {}", e))?;
-            
-            let mut config: toml::Value = config_content.parse()
-                .map_err(|e| format!("Failed to parse config.toml: {}", e))?;
-            
-            // Update server.listen ports (auth_mode comes from merod init --auth-mode embedded)
-            if let Some(server) = config.get_mut("server") {
-                if let Some(listen) = server.get_mut("listen") {
-                    if let Some(listen_array) = listen.as_array_mut() {
-                        for listen_str in listen_array.iter_mut() {
-                            if let Some(addr) = listen_str.as_str() {
-                                // Replace port in IPv4 server addresses (e.g., /ip4/127.0.0.1/tcp/2528)
-                                if addr.contains("/ip4/127.0.0.1/tcp/") {
-                                    let new_addr = regex::Regex::new(r"/tcp/\d+")
-                                        .unwrap()
-                                        .replace(addr, &format!("/tcp/{}", server_port))
-                                        .to_string();
-                                    *listen_str = toml::Value::String(new_addr);
-                                } else if addr.contains("/ip6/::1/tcp/") {
-                                    // Replace port in IPv6 server addresses
-                                    let new_addr = regex::Regex::new(r"/tcp/\d+")
-                                        .unwrap()
-                                        .replace(addr, &format!("/tcp/{}", server_port))
-                                        .to_string();
-                                    *listen_str = toml::Value::String(new_addr);
-                                }
-                            }
+/// Get the app data directory for storing merod data
+fn get_app_data_dir(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    AppPaths::from_handle(app_handle).app_data_dir()
+}
+
+/// A merod process just started by `spawn_merod`, not yet watched by anything
+struct SpawnedMerod {
+    child: tokio::process::Child,
+    pid: u32,
+    spawned_at: std::time::Instant,
+    log_path: std::path::PathBuf,
+    /// Size of the log before this run, so only its own lines are read back
+    log_offset: u64,
+    limits: node_limits::AppliedLimits,
+}
+
+/// Write the server and swarm ports into a node's config.toml
+fn write_node_ports(config_path: &std::path::Path, server_port: u16, swarm_port: u16) -> Result<(), String> {
+    // Read existing config
+    let config_content = std::fs::read_to_string(config_path)
+        .map_err(|e| format!("Failed to read config.toml: {}", e))?;
+    
+    let mut config: toml::Value = config_content.parse()
+        .map_err(|e| format!("Failed to parse config.toml: {}", e))?;
+    
+    // Update server.listen ports (auth_mode comes from merod init --auth-mode embedded)
+    if let Some(server) = config.get_mut("server") {
+        if let Some(listen) = server.get_mut("listen") {
+            if let Some(listen_array) = listen.as_array_mut() {
+                for listen_str in listen_array.iter_mut() {
+                    if let Some(addr) = listen_str.as_str() {
+                        // Replace port in IPv4 server addresses (e.g., /ip4/127.0.0.1/tcp/2528)
+                        if addr.contains("/ip4/127.0.0.1/tcp/") {
+                            let new_addr = regex::Regex::new(r"/tcp/\d+")
+                                .unwrap()
+                                .replace(addr, &format!("/tcp/{}", server_port))
+                                .to_string();
+                            *listen_str = toml::Value::String(new_addr);
+                        } else if addr.contains("/ip6/::1/tcp/") {
+                            // Replace port in IPv6 server addresses
+                            let new_addr = regex::Regex::new(r"/tcp/\d+")
+                                .unwrap()
+                                .replace(addr, &format!("/tcp/{}", server_port))
+                                .to_string();
+                            *listen_str = toml::Value::String(new_addr);
                         }
                     }
                 }
             }
-            
-            // Update swarm.listen ports - use regex-like replacement for any port number
-            if let Some(swarm) = config.get_mut("swarm") {
-                if let Some(listen) = swarm.get_mut("listen") {
-                    if let Some(listen_array) = listen.as_array_mut() {
-                        for listen_str in listen_array.iter_mut() {
-                            if let Some(addr) = listen_str.as_str() {
-                                // Replace port in swarm addresses - handle both TCP and UDP
-                                if addr.contains("/tcp/") && !addr.contains("/udp/") {
-                                    // Replace TCP port (e.g., /ip4/0.0.0.0/tcp/2428)
-                                    let new_addr = regex::Regex::new(r"/tcp/\d+")
-                                        .unwrap()
-                                        .replace(addr, &format!("/tcp/{}", swarm_port))
-                                        .to_string();
-                                    *listen_str = toml::Value::String(new_addr);
-                                } else if addr.contains("/udp/") {
-                                    // Replace UDP port (e.g., /ip4/0.0.0.0/udp/2428/quic-v1)
-                                    let new_addr = regex::Regex::new(r"/udp/\d+")
-                                        .unwrap()
-                                        .replace(addr, &format!("/udp/{}", swarm_port))
-                                        .to_string();
-                                    *listen_str = toml::Value::String(new_addr);
-                                }
-                            }
+        }
+    }
+    
+    // Update swarm.listen ports - use regex-like replacement for any port number
+    if let Some(swarm) = config.get_mut("swarm") {
+        if let Some(listen) = swarm.get_mut("listen") {
+            if let Some(listen_array) = listen.as_array_mut() {
+                for listen_str in listen_array.iter_mut() {
+                    if let Some(addr) = listen_str.as_str() {
+                        // Replace port in swarm addresses - handle both TCP and UDP
+                        if addr.contains("/tcp/") && !addr.contains("/udp/") {
+                            // Replace TCP port (e.g., /ip4/0.0.0.0/tcp/2428)
+                            let new_addr = regex::Regex::new(r"/tcp/\d+")
+                                .unwrap()
+                                .replace(addr, &format!("/tcp/{}", swarm_port))
+                                .to_string();
+                            *listen_str = toml::Value::String(new_addr);
+                        } else if addr.contains("/udp/") {
+                            // Replace UDP port (e.g., /ip4/0.0.0.0/udp/2428/quic-v1)
+                            let new_addr = regex::Regex::new(r"/udp/\d+")
+                                .unwrap()
+                                .replace(addr, &format!("/udp/{}", swarm_port))
+                                .to_string();
+                            *listen_str = toml::Value::String(new_addr);
                         }
                     }
                 }
             }
-            
-            // Write updated config back
-            let updated_config = toml::to_string_pretty(&config)
-                .map_err(|e| format!("Failed to serialize config.toml: {}", e))?;
-            std::fs::write(&config_path, updated_config)
-                .map_err(|e| format!("Failed to write config.toml: {}", e))?;
-            
-            info!("[Merod] Updated config.toml with server_port={} and swarm_port={}", server_port, swarm_port);
         }
     }
     
-    // Node name required
-    let node_name_str = node_name.as_ref().ok_or("Node name is required")?.clone();
+    // Write updated config back
+    let updated_config = toml::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize config.toml: {}", e))?;
+    std::fs::write(config_path, updated_config)
+        .map_err(|e| format!("Failed to write config.toml: {}", e))?;
+    
+    info!("[Merod] Updated config.toml with server_port={} and swarm_port={}", server_port, swarm_port);
+    Ok(())
+}
+
+/// Start merod for an initialized node with its ports, run settings, resource limits and
+/// sandbox applied, and its output appended to the node's `logs/merod.log`. Shared by the
+/// `start_merod` command, headless mode and the CLI; the caller watches the returned child.
+/// A `detach`ed node gets its own process group so it outlives the terminal that started it.
+#[allow(clippy::too_many_arguments)]
+async fn spawn_merod(
+    paths: &AppPaths,
+    merod_binary: &std::path::Path,
+    home_dir_path: &std::path::Path,
+    node_name: &str,
+    server_port: u16,
+    swarm_port: u16,
+    debug_logs: bool,
+    detach: bool,
+) -> Result<SpawnedMerod, String> {
+    let node_dir = home_dir_path.join(node_name);
+    let config_path = node_dir.join("config.toml");
+
+    // Verify the node is initialized (config.toml exists)
+    if !config_path.exists() {
+        return Err(format!(
+            "Node '{}' is not initialized. config.toml not found at {:?}. Please run init first.",
+            node_name, config_path
+        ));
+    }
+    write_node_ports(&config_path, server_port, swarm_port)?;
 
     // Create logs directory and open log file - redirect merod stdout/stderr here
-    let log_dir = home_dir_path.join(&node_name_str).join("logs");
+    let log_dir = home_dir_path.join(node_name).join("logs");
     std::fs::create_dir_all(&log_dir)
         .map_err(|e| format!("Failed to create logs directory: {}", e))?;
     let log_path = log_dir.join("merod.log");
+    // Only lines written by this run count towards its start progress
+    let log_offset = std::fs::metadata(&log_path).map(|m| m.len()).unwrap_or(0);
 
     // Open log file for append - use separate handles for stdout and stderr
     let log_file_stdout = std::fs::OpenOptions::new()
@@ -779,78 +903,277 @@ async fn start_merod(
         })
         .map_err(|e| format!("Failed to open log file for stderr: {}", e))?;
 
+    // Per-node run settings from the node registry (log filter, env vars, extra args, sandbox)
+    let run_settings = match paths.app_data_dir() {
+        Ok(dir) => node_registry::get(&dir, &node_dir)?,
+        Err(_) => node_registry::NodeRunSettings::default(),
+    };
+    run_settings
+        .validate()
+        .map_err(|e| format!("Invalid run settings for node '{}': {}", node_name, e))?;
+
     // Build command - global options come BEFORE subcommand
     // Merod expects: merod --home ~/.calimero --node node1 run
-    let mut cmd = Command::new(&merod_binary);
+    // A sandboxed node fails to start rather than running unconfined
+    let (mut cmd, sandbox) = if run_settings.sandbox {
+        let (cmd, sandbox) = merod_sandbox::command(merod_binary, &node_dir)
+            .map_err(|e| format!("Cannot start node '{}' sandboxed: {}", node_name, e))?;
+        (cmd, Some(sandbox))
+    } else {
+        (Command::new(merod_binary), None)
+    };
     // Force ANSI colors in output so the log viewer can display them
     cmd.env("CLICOLOR_FORCE", "1");
     cmd.env("FORCE_COLOR", "1");
-    // Set log level based on debug_logs setting
-    if debug_logs.unwrap_or(false) {
+    // Set log level: the node's log filter, otherwise based on debug_logs setting
+    if let Some(filter) = &run_settings.log_filter {
+        cmd.env("RUST_LOG", filter);
+        info!("[Merod] Log filter: {}", filter);
+    } else if debug_logs {
         cmd.env("RUST_LOG", "debug");
         info!("[Merod] Debug logging enabled");
     } else {
         cmd.env("RUST_LOG", "info");
     }
+    cmd.envs(&run_settings.env);
     
     // Set home directory (global option, before subcommand)
-    cmd.arg("--home").arg(&home_dir_path);
+    cmd.arg("--home").arg(home_dir_path);
     
     // Set node name (global option, before subcommand)
-    cmd.arg("--node").arg(&node_name_str);
+    cmd.arg("--node").arg(node_name);
     
-    // Add 'run' subcommand last
+    // Add 'run' subcommand last, followed by the node's extra args
     cmd.arg("run");
+    cmd.args(&run_settings.extra_args);
     
     // Redirect stdout/stderr to log file - merod output goes directly to disk
     cmd.stdout(Stdio::from(log_file_stdout));
     cmd.stderr(Stdio::from(log_file_stderr));
     cmd.stdin(Stdio::null());
+    if detach {
+        #[cfg(unix)]
+        cmd.process_group(0);
+        #[cfg(windows)]
+        cmd.creation_flags(0x0000_0200); // CREATE_NEW_PROCESS_GROUP
+    }
+
+    // Resource limits, then the sandbox last so it doesn't block joining the node's cgroup
+    let limits = node_limits::apply(&mut cmd, node_name, &run_settings.limits)?;
+    if let Some(sandbox) = &sandbox {
+        sandbox.confine(&mut cmd);
+    }
     
     // Log the command being run
     let cmd_str = format!("{:?}", cmd);
     info!("[Merod] Running command: {}, logs at {:?}", cmd_str, log_path);
     
     // Start the process
-    let mut child = cmd.spawn()
-        .map_err(|e| format!("Failed to start merod: {}", e))?;
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            node_limits::release(&limits);
+            return Err(format!("Failed to start merod: {}", e));
+        }
+    };
     
-    let pid = child.id().unwrap();
+    let spawned_at = std::time::Instant::now();
+    let mut pid = child.id().unwrap();
+    if let Some(sandbox) = sandbox {
+        info!("[Merod] Node '{}' is sandboxed with {:?}", node_name, sandbox.backend);
+        pid = match sandbox.merod_pid(pid).await {
+            Ok(merod_pid) => merod_pid,
+            Err(e) => {
+                let _ = child.start_kill();
+                let _ = child.wait().await;
+                node_limits::release(&limits);
+                return Err(e);
+            }
+        };
+    }
     info!("[Merod] Started with PID: {}", pid);
+    Ok(SpawnedMerod { child, pid, spawned_at, log_path, log_offset, limits })
+}
+
+#[tauri::command]
+async fn start_merod(
+    server_port: Option<u16>,
+    swarm_port: Option<u16>,
+    data_dir: Option<String>,
+    node_name: Option<String>,
+    debug_logs: Option<bool>,
+    wait_until_healthy: Option<u64>,
+    app_handle: tauri::AppHandle,
+    merod_state: tauri::State<'_, MerodState>,
+    info_cache: tauri::State<'_, merod_info::MerodInfoCache>,
+    storage_guard: tauri::State<'_, storage::StorageGuard>,
+) -> Result<node_startup::MerodStartResult, String> {
+    let server_port = server_port.unwrap_or(2528);
+    let swarm_port = swarm_port.unwrap_or(2428);
+    
+    // Only stop a process that uses the same server_port (port conflict)
+    let existing_on_port: Option<u32> = {
+        let state = merod_state.lock().unwrap();
+        state.iter().find(|p| p.port == server_port).map(|p| p.pid)
+    };
+
+    if let Some(pid) = existing_on_port {
+        info!("[Merod] Stopping existing process on port {} (PID: {}) before starting new one", server_port, pid);
+        crash_diagnosis::expect_exit(pid);
+        #[cfg(unix)]
+        {
+            use std::process::Command;
+            let _ = Command::new("kill")
+                .arg("-TERM")
+                .arg(pid.to_string())
+                .output();
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            let _ = Command::new("kill")
+                .arg("-9")
+                .arg(pid.to_string())
+                .output();
+        }
+        #[cfg(windows)]
+        {
+            use std::process::Command;
+            let _ = Command::new("taskkill")
+                .arg("/PID")
+                .arg(pid.to_string())
+                .arg("/F")
+                .output();
+        }
+        crash_diagnosis::stop_completed(pid);
+        let mut state = merod_state.lock().unwrap();
+        state.retain(|p| p.pid != pid);
+    }
+    
+    // Prepare home directory (where .calimero folder is, e.g., ~/.calimero)
+    let home_dir_path = resolve_calimero_home(data_dir)?;
+    
+    data_dir::create_calimero_home(&home_dir_path)?;
+
+    // A full disk corrupts RocksDB: refuse (or warn) below the free-space threshold
+    let storage_warning = storage_guard.check_before_start(&home_dir_path)?;
+    
+    // Node name required
+    let node_name_str = node_name.as_ref().ok_or("Node name is required")?.clone();
+
+    // Pinned merod version for this node, or the bundled binary
+    let paths = AppPaths::from_handle(&app_handle);
+    let merod_binary = paths.node_merod_binary(&home_dir_path, &node_name_str)?;
+    let merod_version = info_cache.version_of(&merod_binary).await;
+
+    let SpawnedMerod { mut child, pid, spawned_at, log_path, log_offset, limits } = spawn_merod(
+        &paths,
+        &merod_binary,
+        &home_dir_path,
+        &node_name_str,
+        server_port,
+        swarm_port,
+        debug_logs.unwrap_or(false),
+        false,
+    )
+    .await?;
+    let node_dir = home_dir_path.join(&node_name_str);
+    let mut tracker = node_startup::StartTracker::spawned(&app_handle, &node_name_str, pid, spawned_at);
     
     // Wait a brief moment to check if process is still alive
     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     
-    // Check if process already exited
+    // Check if process already exited (with an exit code or killed by a signal)
+    let crash_reports = app_handle.state::<crash_diagnosis::CrashReports>();
+    crash_reports.clear(&node_dir);
     if let Ok(Some(status)) = child.try_wait() {
-        if let Some(code) = status.code() {
-            let error_msg = format!("Merod process exited immediately with code: {}. Check merod logs for details.", code);
-            warn!("[Merod] {}", error_msg);
-            return Err(error_msg);
-        }
+        let (exit_code, signal) = crash_diagnosis::exit_details(&status);
+        let log_tail = crash_diagnosis::read_log_tail(&log_path, log_offset);
+        let diagnosis = crash_diagnosis::diagnose(&node_name_str, pid, exit_code, signal, &log_path, log_tail);
+        let diagnosis = node_limits::attribute_breach(diagnosis, &limits);
+        node_limits::release(&limits);
+        let error_msg = format!("Merod process exited immediately. {}", diagnosis.message());
+        warn!("[Merod] {}", error_msg);
+        crash_reports.record(node_dir, diagnosis);
+        return Err(error_msg);
     }
     
     // Store process state
     {
         let mut state = merod_state.lock().unwrap();
-        state.push(MerodProcess { pid, port: server_port });
+        state.push(MerodProcess {
+            pid,
+            port: server_port,
+            version: merod_version.clone(),
+            home_dir: home_dir_path.clone(),
+            node_name: node_name_str.clone(),
+            limits: limits.clone(),
+        });
     }
     
     // Spawn a task to monitor the process
     let merod_state_clone = merod_state.inner().clone();
     let monitored_pid = pid; // Capture PID for verification
+    let monitor_app_handle = app_handle.clone();
+    let monitored_node = node_name_str.clone();
+    let monitored_log = log_path.clone();
     tokio::spawn(async move {
         let status = child.wait().await;
-        let mut state = merod_state_clone.lock().unwrap();
         if let Ok(exit_status) = status {
-            if let Some(code) = exit_status.code() {
-                warn!("[Merod] Process {} exited with code: {}", monitored_pid, code);
+            let (exit_code, signal) = crash_diagnosis::exit_details(&exit_status);
+            if crash_diagnosis::take_expected_exit(monitored_pid) || exit_status.success() {
+                info!("[Merod] Process {} stopped (code: {:?}, signal: {:?})", monitored_pid, exit_code, signal);
+            } else {
+                // Diagnose before dropping the process from state, so a waiting start sees it
+                let log_tail = crash_diagnosis::read_log_tail(&monitored_log, log_offset);
+                let diagnosis =
+                    crash_diagnosis::diagnose(&monitored_node, monitored_pid, exit_code, signal, &monitored_log, log_tail);
+                let diagnosis = node_limits::attribute_breach(diagnosis, &limits);
+                warn!("[Merod] Process {} exited: {}", monitored_pid, diagnosis.summary);
+                monitor_app_handle
+                    .state::<crash_diagnosis::CrashReports>()
+                    .record(node_dir, diagnosis.clone());
+                let _ = monitor_app_handle.emit_to(
+                    capabilities::MAIN_WINDOW_LABEL,
+                    crash_diagnosis::NODE_CRASHED_EVENT,
+                    diagnosis,
+                );
             }
         }
+        node_limits::release(&limits);
+        let mut state = merod_state_clone.lock().unwrap();
         state.retain(|p| p.pid != monitored_pid);
     });
     
-    Ok(format!("Merod started successfully with PID: {}", pid))
+    let time_to_ready_ms = match wait_until_healthy {
+        Some(timeout_secs) => Some(
+            node_startup::wait_until_ready(
+                merod_state.inner(),
+                &mut tracker,
+                &log_path,
+                node_startup::LogFollower::new(log_offset),
+                server_port,
+                timeout_secs,
+            )
+            .await
+            .map_err(|e| match app_handle.state::<crash_diagnosis::CrashReports>().for_pid(pid) {
+                Some(diagnosis) => format!("{} {}", e, diagnosis.message()),
+                None => e,
+            })?,
+        ),
+        None => None,
+    };
+
+    let node_url = node_startup::node_url(server_port);
+    Ok(node_startup::MerodStartResult {
+        pid,
+        node_name: node_name_str,
+        server_port,
+        swarm_port,
+        admin_api_url: format!("{}/admin-api", node_url),
+        node_url,
+        version: merod_version,
+        stage: tracker.stage(),
+        time_to_ready_ms,
+        warning: storage_warning,
+    })
 }
 
 #[tauri::command]
@@ -865,6 +1188,7 @@ async fn stop_merod(merod_state: tauri::State<'_, MerodState>) -> Result<String,
     }
 
     for pid in &pids {
+        crash_diagnosis::expect_exit(*pid);
         #[cfg(unix)]
         {
             use std::process::Command;
@@ -938,6 +1262,7 @@ async fn stop_merod(merod_state: tauri::State<'_, MerodState>) -> Result<String,
             }
         }
 
+        crash_diagnosis::stop_completed(*pid);
         info!("[Merod] Stopped process with PID: {}", pid);
     }
 
@@ -951,6 +1276,27 @@ async fn stop_merod(merod_state: tauri::State<'_, MerodState>) -> Result<String,
 
 #[tauri::command]
 async fn stop_merod_by_pid_command(pid: u32, merod_state: tauri::State<'_, MerodState>) -> Result<String, String> {
+    stop_merod_pid(pid).await?;
+    
+    // Remove this process from state
+    {
+        let mut state = merod_state.lock().unwrap();
+        state.retain(|p| p.pid != pid);
+    }
+    
+    info!("[Merod] Stopped process with PID: {}", pid);
+    Ok(format!("Merod stopped successfully (PID: {})", pid))
+}
+
+/// Stop a merod process: SIGTERM, then SIGKILL if it is still running after 2 seconds
+async fn stop_merod_pid(pid: u32) -> Result<(), String> {
+    crash_diagnosis::expect_exit(pid);
+    let result = kill_merod_pid(pid).await;
+    crash_diagnosis::stop_completed(pid);
+    result
+}
+
+async fn kill_merod_pid(pid: u32) -> Result<(), String> {
     #[cfg(unix)]
     {
         use std::process::Command;
@@ -1032,14 +1378,7 @@ async fn stop_merod_by_pid_command(pid: u32, merod_state: tauri::State<'_, Merod
         }
     }
     
-    // Remove this process from state
-    {
-        let mut state = merod_state.lock().unwrap();
-        state.retain(|p| p.pid != pid);
-    }
-    
-    info!("[Merod] Stopped process with PID: {}", pid);
-    Ok(format!("Merod stopped successfully (PID: {})", pid))
+    Ok(())
 }
 
 fn is_process_running(pid: u32) -> bool {
@@ -1077,37 +1416,22 @@ async fn get_merod_status(merod_state: tauri::State<'_, MerodState>) -> Result<s
         return Ok(serde_json::json!({ "running": false, "nodes": [] }));
     }
     let nodes: Vec<_> = state.iter()
-        .map(|p| serde_json::json!({ "pid": p.pid, "port": p.port }))
+        .map(|p| serde_json::json!({ "pid": p.pid, "port": p.port, "version": p.version, "limits": p.limits }))
         .collect();
     let first = &state[0];
     Ok(serde_json::json!({
         "running": true,
         "nodes": nodes,
         "pid": first.pid,
-        "port": first.port
+        "port": first.port,
+        "version": first.version
     }))
 }
 
 #[tauri::command]
 async fn list_merod_nodes(home_dir: Option<String>) -> Result<Vec<String>, String> {
     // Merod stores nodes in ~/.calimero/ as directories (node1, node2, etc.)
-    let calimero_home = if let Some(dir) = home_dir {
-        // Expand ~ if present
-        let expanded = if dir.starts_with("~") {
-            if let Some(home) = dirs::home_dir() {
-                dir.replacen("~", &home.to_string_lossy(), 1)
-            } else {
-                dir
-            }
-        } else {
-            dir
-        };
-        std::path::PathBuf::from(expanded)
-    } else {
-        dirs::home_dir()
-            .ok_or("Failed to get home directory")?
-            .join(".calimero")
-    };
+    let calimero_home = resolve_calimero_home(home_dir)?;
     
     if !calimero_home.exists() {
         return Ok(vec![]);
@@ -1156,17 +1480,21 @@ async fn list_merod_nodes(home_dir: Option<String>) -> Result<Vec<String>, Strin
 }
 
 #[tauri::command]
-async fn check_merod_health(node_url: String) -> Result<serde_json::Value, String> {
+async fn check_merod_health(
+    node_url: String,
+    profiles: tauri::State<'_, NodeProfiles>,
+) -> Result<serde_json::Value, String> {
     let health_url = format!("{}/health", node_url.trim_end_matches('/'));
     
     info!("[Merod] Checking health at: {}", health_url);
     
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let client = profiles.client_for(&health_url)?;
     
-    let response = client.get(&health_url).send().await;
+    let response = client
+        .get(&health_url)
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await;
     
     match response {
         Ok(resp) => {
@@ -1194,249 +1522,186 @@ async fn check_merod_health(node_url: String) -> Result<serde_json::Value, Strin
 async fn init_merod_node(
     node_name: String,
     home_dir: Option<String>,
+    merod_version: Option<String>,
+    options: Option<node_init::NodeInitOptions>,
     app_handle: tauri::AppHandle,
+    window: tauri::Window,
 ) -> Result<String, String> {
-    // Get bundled merod binary
-    let merod_binary = get_merod_binary_path(&app_handle)?;
-    
+    let progress = |progress: node_init::InitProgress| {
+        let _ = window.emit(node_init::INIT_PROGRESS_EVENT, progress);
+    };
+    init_node(
+        &AppPaths::from_handle(&app_handle),
+        node_name,
+        home_dir,
+        merod_version,
+        options.unwrap_or_default(),
+        &progress,
+    )
+    .await
+}
+
+/// Initialize a node with `merod init`, reporting each line of its output to `progress`
+async fn init_node(
+    paths: &AppPaths,
+    node_name: String,
+    home_dir: Option<String>,
+    merod_version: Option<String>,
+    options: node_init::NodeInitOptions,
+    progress: &(dyn Fn(node_init::InitProgress) + Sync),
+) -> Result<String, String> {
+    node_init::validate_node_name(&node_name)?;
+    options.validate(true)?;
+
     // Prepare home directory (where .calimero folder will be)
-    let home_dir_path = if let Some(dir) = home_dir {
-        // Expand ~ if present
-        let expanded = if dir.starts_with("~") {
-            if let Some(home) = dirs::home_dir() {
-                dir.replacen("~", &home.to_string_lossy(), 1)
-            } else {
-                dir
-            }
-        } else {
-            dir
-        };
-        std::path::PathBuf::from(expanded)
-    } else {
-        dirs::home_dir()
-            .ok_or("Failed to get home directory")?
-            .join(".calimero")
+    let home_dir_path = resolve_calimero_home(home_dir)?;
+    let node_dir = home_dir_path.join(&node_name);
+    if node_dir.exists() {
+        return Err(format!("Node '{}' already exists in {:?}", node_name, home_dir_path));
+    }
+
+    // Use the requested merod version (pinned for the node once init succeeds), otherwise
+    // whatever the node is already pinned to, otherwise the bundled binary
+    let merod_binary = match &merod_version {
+        Some(version) => {
+            let app_data_dir = paths.app_data_dir()?;
+            let installed = merod_manager::find_installed(&app_data_dir, version)
+                .ok_or_else(|| format!("merod {} is not installed. Download it first.", version))?;
+            verify_installed_merod(&installed)?;
+            installed.path
+        }
+        None => paths.node_merod_binary(&home_dir_path, &node_name)?,
     };
     
-    std::fs::create_dir_all(&home_dir_path)
-        .map_err(|e| format!("Failed to create home directory: {}", e))?;
+    data_dir::create_calimero_home(&home_dir_path)?;
     
-    // Run merod init command - global options come BEFORE subcommand
-    // Use --auth-mode embedded so merod creates the full embedded_auth config
-    let mut cmd = Command::new(&merod_binary);
-    cmd.arg("--home").arg(&home_dir_path);
-    cmd.arg("--node").arg(&node_name);
-    cmd.arg("init").arg("--auth-mode").arg("embedded");
+    let result = run_merod_init(&merod_binary, &home_dir_path, &node_name, &options, progress).await;
+    if let Err(e) = result {
+        // Don't leave a half-created node behind for list_merod_nodes to pick up
+        if node_dir.exists() {
+            match std::fs::remove_dir_all(&node_dir) {
+                Ok(()) => info!("[Merod] Removed partially initialized node directory {:?}", node_dir),
+                Err(remove_err) => warn!("[Merod] Failed to clean up {:?}: {}", node_dir, remove_err),
+            }
+        }
+        return Err(e);
+    }
+    // Homes that existed before the app wrote markers are marked once they hold a node
+    data_dir::create_calimero_home(&home_dir_path)?;
     
+    if let Some(version) = &merod_version {
+        let app_data_dir = paths.app_data_dir()?;
+        merod_manager::set_pin(&app_data_dir, &home_dir_path, &node_name, Some(version))?;
+        info!("[Merod] Pinned node '{}' to merod {}", node_name, version);
+    }
+
+    info!("[Merod] Initialized node '{}' in {:?}", node_name, home_dir_path);
+    Ok(format!("Node '{}' initialized successfully", node_name))
+}
+
+/// Run `merod init`, passing each line of its output to `progress`
+async fn run_merod_init(
+    merod_binary: &std::path::Path,
+    home_dir_path: &std::path::Path,
+    node_name: &str,
+    options: &node_init::NodeInitOptions,
+    progress: &(dyn Fn(node_init::InitProgress) + Sync),
+) -> Result<(), String> {
+    // Global options come BEFORE the subcommand: merod --home ~/.calimero --node node1 init ...
+    let args = options.to_args(home_dir_path, node_name);
+    info!("[Merod] Running merod init for node '{}': {:?}", node_name, &args[4..]);
+    let mut cmd = Command::new(merod_binary);
+    cmd.args(&args);
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
     cmd.stdin(Stdio::null());
-    
-    // Add timeout to prevent hanging (30 seconds should be enough for init)
-    let output = tokio::time::timeout(
-        tokio::time::Duration::from_secs(30),
-        cmd.output()
-    )
+    cmd.kill_on_drop(true);
+
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to execute merod init: {}", e))?;
+    let stdout = child.stdout.take().ok_or("Failed to capture merod init output")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture merod init output")?;
+
+    let timeout_secs = options.timeout_secs();
+    let run = async {
+        let (_, stderr_lines, status) = tokio::join!(
+            stream_init_output(stdout, "stdout", node_name, progress),
+            stream_init_output(stderr, "stderr", node_name, progress),
+            child.wait()
+        );
+        (stderr_lines, status)
+    };
+    let (stderr_lines, status) = tokio::time::timeout(tokio::time::Duration::from_secs(timeout_secs), run)
         .await
-    .map_err(|_| "Merod init command timed out after 30 seconds. Please check if the merod binary is working correctly.")?
-        .map_err(|e| format!("Failed to execute merod init: {}", e))?;
-    
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Merod init failed: {}", stderr));
+        .map_err(|_| format!(
+            "Merod init command timed out after {} seconds. Please check if the merod binary is working correctly.",
+            timeout_secs
+        ))?;
+    let status = status.map_err(|e| format!("Failed to execute merod init: {}", e))?;
+
+    if !status.success() {
+        return Err(format!("Merod init failed: {}", stderr_lines.join("\n")));
     }
-    
-    info!("[Merod] Initialized node '{}' in {:?}", node_name, home_dir_path);
-    Ok(format!("Node '{}' initialized successfully", node_name))
+    Ok(())
+}
+
+/// Forward each line of `reader` to `progress` and return the lines
+async fn stream_init_output<R: tokio::io::AsyncRead + Unpin>(
+    reader: R,
+    stream: &'static str,
+    node_name: &str,
+    progress: &(dyn Fn(node_init::InitProgress) + Sync),
+) -> Vec<String> {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    let mut lines = BufReader::new(reader).lines();
+    let mut collected = Vec::new();
+    while let Ok(Some(line)) = lines.next_line().await {
+        debug!("[Merod] init {}: {}", stream, line);
+        progress(node_init::InitProgress { node_name: node_name.to_string(), stream, line: line.clone() });
+        collected.push(line);
+    }
+    collected
 }
 
 #[tauri::command]
-async fn detect_running_merod_nodes() -> Result<Vec<serde_json::Value>, String> {
-    #[cfg(unix)]
-    {
-        use std::process::Command;
-        
-        // Use ps to find merod processes
-        let output = Command::new("ps")
-            .arg("ax")
-            .arg("-o")
-            .arg("pid,command")
-            .output()
-            .map_err(|e| format!("Failed to run ps: {}", e))?;
-        
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut running_nodes = Vec::new();
-        
-        for line in stdout.lines() {
-            if line.contains("merod") && line.contains("run") {
-                // Parse PID and extract node name and home directory from command
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if let Some(pid_str) = parts.get(0) {
-                    if let Ok(pid) = pid_str.parse::<u32>() {
-                        // Try to extract node name and home directory from arguments
-                        let mut node_name = None;
-                        let mut home_dir = None;
-                        
-                        for (i, part) in parts.iter().enumerate() {
-                            if (part == &"--node" || part == &"-n") && i + 1 < parts.len() {
-                                node_name = Some(parts[i + 1].to_string());
-                            }
-                            if part == &"--home" && i + 1 < parts.len() {
-                                home_dir = Some(parts[i + 1].to_string());
-                            }
-                        }
-                        
-                        // Try to read ports from config.toml
-                        let mut server_port = 2528; // Default
-                        let mut swarm_port = 2428; // Default
-                        if let (Some(name), Some(home)) = (&node_name, &home_dir) {
-                            let config_path = std::path::PathBuf::from(home).join(name).join("config.toml");
-                            if config_path.exists() {
-                                if let Ok(config_content) = std::fs::read_to_string(&config_path) {
-                                    if let Ok(config) = config_content.parse::<toml::Value>() {
-                                        // Try to extract server port from server.listen
-                                        if let Some(server) = config.get("server") {
-                                            if let Some(listen) = server.get("listen") {
-                                                if let Some(listen_array) = listen.as_array() {
-                                                    for listen_str in listen_array {
-                                                        if let Some(addr) = listen_str.as_str() {
-                                                            // Extract port from /ip4/127.0.0.1/tcp/2528
-                                                            if let Some(tcp_pos) = addr.find("/tcp/") {
-                                                                let port_str = &addr[tcp_pos + 5..];
-                                                                if let Some(slash_pos) = port_str.find('/') {
-                                                                    if let Ok(p) = port_str[..slash_pos].parse::<u16>() {
-                                                                        server_port = p;
-                                                                        break;
-                                                                    }
-                                                                } else if let Ok(p) = port_str.parse::<u16>() {
-                                                                    server_port = p;
-                                                                    break;
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        
-                                        // Try to extract swarm port from swarm.listen
-                                        if let Some(swarm) = config.get("swarm") {
-                                            if let Some(listen) = swarm.get("listen") {
-                                                if let Some(listen_array) = listen.as_array() {
-                                                    for listen_str in listen_array {
-                                                        if let Some(addr) = listen_str.as_str() {
-                                                            // Extract port from /ip4/0.0.0.0/tcp/2428 or /ip4/0.0.0.0/udp/2428/quic-v1
-                                                            if let Some(tcp_pos) = addr.find("/tcp/") {
-                                                                let port_str = &addr[tcp_pos + 5..];
-                                                                if let Some(slash_pos) = port_str.find('/') {
-                                                                    if let Ok(p) = port_str[..slash_pos].parse::<u16>() {
-                                                                        swarm_port = p;
-                                                                        break;
-                                                                    }
-                                                                } else if let Ok(p) = port_str.parse::<u16>() {
-                                                                    swarm_port = p;
-                                                                    break;
-                                                                }
-                                                            } else if let Some(udp_pos) = addr.find("/udp/") {
-                                                                let port_str = &addr[udp_pos + 5..];
-                                                                if let Some(slash_pos) = port_str.find('/') {
-                                                                    if let Ok(p) = port_str[..slash_pos].parse::<u16>() {
-                                                                        swarm_port = p;
-                                                                        break;
-                                                                    }
-                                                                } else if let Ok(p) = port_str.parse::<u16>() {
-                                                                    swarm_port = p;
-                                                                    break;
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        
-                        running_nodes.push(serde_json::json!({
-                            "pid": pid,
-                            "node_name": node_name.unwrap_or_else(|| format!("node_{}", pid)),
-                            "port": server_port,
-                            "swarm_port": swarm_port,
-                            "home_dir": home_dir.unwrap_or_else(|| "unknown".to_string())
-                        }));
-                    }
-                }
-            }
+async fn detect_running_merod_nodes(
+    app_handle: tauri::AppHandle,
+    info_cache: tauri::State<'_, merod_info::MerodInfoCache>,
+) -> Result<Vec<serde_json::Value>, String> {
+    detect_running_nodes(&AppPaths::from_handle(&app_handle), &info_cache).await
+}
+
+/// Find running merod nodes, including ones not started by this app, from the process list
+async fn detect_running_nodes(
+    paths: &AppPaths,
+    info_cache: &merod_info::MerodInfoCache,
+) -> Result<Vec<serde_json::Value>, String> {
+    let mut running_nodes = Vec::new();
+    for (pid, argv) in node_ops::merod_processes() {
+        if !argv.iter().any(|arg| arg == "run") {
+            continue;
         }
-        
-        Ok(running_nodes)
-    }
-    
-    #[cfg(windows)]
-    {
-        use std::process::Command;
-        
-        // Use tasklist and wmic on Windows
-        let output = Command::new("tasklist")
-            .arg("/FO")
-            .arg("CSV")
-            .output()
-            .map_err(|e| format!("Failed to run tasklist: {}", e))?;
-        
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut running_nodes = Vec::new();
-        
-        // Parse CSV output and find merod processes
-        for line in stdout.lines().skip(1) {
-            if line.contains("merod") {
-                // Extract PID and command line
-                let parts: Vec<&str> = line.split(',').collect();
-                if parts.len() >= 2 {
-                    if let Ok(pid) = parts[1].trim_matches('"').parse::<u32>() {
-                        // Try to get command line using wmic
-                        let cmd_output = Command::new("wmic")
-                            .arg("process")
-                            .arg("where")
-                            .arg(format!("ProcessId={}", pid))
-                            .arg("get")
-                            .arg("CommandLine")
-                            .output();
-                        
-                        if let Ok(cmd_out) = cmd_output {
-                            let cmd_line = String::from_utf8_lossy(&cmd_out.stdout);
-                            // Parse node name and port from command line
-                            let mut node_name = None;
-                            let mut port = None;
-                            
-                            let cmd_parts: Vec<&str> = cmd_line.split_whitespace().collect();
-                            for (i, part) in cmd_parts.iter().enumerate() {
-                                if (part == &"--node" || part == &"-n") && i + 1 < cmd_parts.len() {
-                                    node_name = Some(cmd_parts[i + 1].to_string());
-                                }
-                                if part == &"--port" && i + 1 < cmd_parts.len() {
-                                    if let Ok(p) = cmd_parts[i + 1].parse::<u16>() {
-                                        port = Some(p);
-                                    }
-                                }
-                            }
-                            
-                            let port = port.unwrap_or(2528);
-                            
-                            running_nodes.push(serde_json::json!({
-                                "pid": pid,
-                                "node_name": node_name.unwrap_or_else(|| format!("node_{}", pid)),
-                                "port": port
-                            }));
-                        }
-                    }
-                }
+        let (home_dir, node_name) = node_ops::parse_merod_args(&argv);
+
+        // Ports come from the node's config.toml when it can be found
+        let (server_port, swarm_port) = match (&home_dir, &node_name) {
+            (Some(home), Some(name)) => {
+                node_log_level::configured_ports(&std::path::Path::new(home).join(name)).unwrap_or((None, None))
             }
-        }
-        
-        Ok(running_nodes)
+            _ => (None, None),
+        };
+        let version = info_cache.version_of_process(pid, paths).await;
+
+        running_nodes.push(serde_json::json!({
+            "pid": pid,
+            "node_name": node_name.unwrap_or_else(|| format!("node_{}", pid)),
+            "port": server_port.unwrap_or(2528),
+            "swarm_port": swarm_port.unwrap_or(2428),
+            "home_dir": home_dir.unwrap_or_else(|| "unknown".to_string()),
+            "version": version
+        }));
     }
+    Ok(running_nodes)
 }
 
 /// Read merod logs for a node. Logs are only available for nodes started by the app.
@@ -1448,22 +1713,7 @@ async fn get_merod_logs(
 ) -> Result<String, String> {
     let lines = lines.unwrap_or(500).min(10_000);
     
-    let home_dir_path = if let Some(dir) = home_dir {
-        let expanded = if dir.starts_with("~") {
-            if let Some(home) = dirs::home_dir() {
-                dir.replacen("~", &home.to_string_lossy(), 1)
-            } else {
-                dir
-            }
-        } else {
-            dir
-        };
-        std::path::PathBuf::from(expanded)
-    } else {
-        dirs::home_dir()
-            .ok_or("Failed to get home directory")?
-            .join(".calimero")
-    };
+    let home_dir_path = resolve_calimero_home(home_dir)?;
     
     let log_path = home_dir_path.join(&node_name).join("logs").join("merod.log");
     
@@ -1506,18 +1756,7 @@ async fn pick_directory(default_path: Option<String>) -> Result<Option<String>,
     
     // Set default directory if provided
     if let Some(path_str) = default_path {
-        // Expand ~ to home directory
-        let expanded_path = if path_str.starts_with("~") {
-            if let Some(home) = dirs::home_dir() {
-                path_str.replacen("~", &home.to_string_lossy(), 1)
-            } else {
-                path_str
-            }
-        } else {
-            path_str
-        };
-        
-        let path_buf = std::path::PathBuf::from(&expanded_path);
+        let path_buf = expand_tilde(path_str);
         if path_buf.exists() && path_buf.is_dir() {
             dialog = dialog.set_directory(path_buf);
         } else if let Some(parent) = path_buf.parent() {
@@ -1578,6 +1817,16 @@ async fn autostart_is_enabled(_app: tauri::AppHandle) -> Result<bool, String> {
 /// has the data directory open. Clears MerodState and waits for processes to fully exit.
 #[tauri::command]
 async fn kill_all_merod_processes(merod_state: tauri::State<'_, MerodState>) -> Result<String, String> {
+    let stopped = stop_all_merod_processes().await?;
+    {
+        let mut state = merod_state.lock().unwrap();
+        state.clear();
+    }
+    Ok(format!("Stopped {} merod process(es)", stopped))
+}
+
+/// Stop every running merod process and wait for them to exit. Returns how many there were.
+async fn stop_all_merod_processes() -> Result<usize, String> {
     let pids: Vec<u32> = {
         #[cfg(unix)]
         {
@@ -1623,6 +1872,7 @@ async fn kill_all_merod_processes(merod_state: tauri::State<'_, MerodState>) ->
     };
 
     for pid in &pids {
+        crash_diagnosis::expect_exit(*pid);
         #[cfg(unix)]
         {
             let _ = std::process::Command::new("kill").arg("-TERM").arg(pid.to_string()).output();
@@ -1648,36 +1898,35 @@ async fn kill_all_merod_processes(merod_state: tauri::State<'_, MerodState>) ->
         }
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
     }
-
-    {
-        let mut state = merod_state.lock().unwrap();
-        state.clear();
+    for pid in &pids {
+        crash_diagnosis::stop_completed(*pid);
     }
 
     info!("[Calimero] Killed {} merod process(es)", pids.len());
-    Ok(format!("Stopped {} merod process(es)", pids.len()))
+    Ok(pids.len())
 }
 
 /// Delete the Calimero data directory and all its contents. Used for "total nuke" reset.
-/// Path must be under the user's home directory for safety.
+/// Path must be under the user's home directory for safety, and must look like a Calimero
+/// home (the app's marker file, or nothing but node directories).
 /// Call kill_all_merod_processes first to ensure no process has the directory open.
+/// With `dry_run`, only reports the nodes and sizes that would be removed. With
+/// `use_trash`, the directory is moved to the OS trash instead of being deleted.
 #[tauri::command]
-async fn delete_calimero_data_dir(data_dir: String) -> Result<String, String> {
-    let expanded = if data_dir.starts_with("~") {
-        if let Some(home) = dirs::home_dir() {
-            data_dir.replacen("~", &home.to_string_lossy(), 1)
-        } else {
-            return Err("Could not resolve home directory".to_string());
-        }
-    } else {
-        data_dir
-    };
-
-    let path = std::path::PathBuf::from(&expanded);
+async fn delete_calimero_data_dir(
+    data_dir: String,
+    dry_run: Option<bool>,
+    use_trash: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let path = expand_tilde(data_dir);
 
     // If path doesn't exist, nothing to delete
     if !path.exists() {
-        return Ok("Directory did not exist (nothing to delete)".to_string());
+        return Ok(serde_json::json!({
+            "path": path,
+            "existed": false,
+            "message": "Directory did not exist (nothing to delete)"
+        }));
     }
 
     let path_canonical = path.canonicalize().map_err(|e| {
@@ -1690,6 +1939,9 @@ async fn delete_calimero_data_dir(data_dir: String) -> Result<String, String> {
             if !path_canonical.starts_with(&home_canonical) {
                 return Err("Path must be under your home directory".to_string());
             }
+            if path_canonical == home_canonical {
+                return Err("Refusing to delete your home directory".to_string());
+            }
         }
     }
 
@@ -1697,29 +1949,86 @@ async fn delete_calimero_data_dir(data_dir: String) -> Result<String, String> {
         return Err("Path is not a directory".to_string());
     }
 
-    std::fs::remove_dir_all(&path_canonical).map_err(|e| {
-        format!("Failed to delete directory: {}", e)
-    })?;
+    let report = data_dir::inspect(&path_canonical)?;
+    if dry_run.unwrap_or(false) {
+        return serde_json::to_value(&report).map_err(|e| e.to_string());
+    }
+    if let Some(reason) = &report.reason {
+        warn!("[Calimero] Refusing to delete {:?}: {}", path_canonical, reason);
+        return Err(reason.clone());
+    }
+
+    let trashed = use_trash.unwrap_or(false);
+    if trashed {
+        trash::delete(&path_canonical).map_err(|e| {
+            format!("Failed to move directory to the trash: {}", e)
+        })?;
+        info!(
+            "[Calimero] Moved data directory {:?} to the trash ({} nodes, {} bytes)",
+            path_canonical, report.nodes.len(), report.total_size
+        );
+    } else {
+        std::fs::remove_dir_all(&path_canonical).map_err(|e| {
+            format!("Failed to delete directory: {}", e)
+        })?;
+        info!(
+            "[Calimero] Deleted data directory {:?} ({} nodes, {} bytes)",
+            path_canonical, report.nodes.len(), report.total_size
+        );
+    }
 
-    info!("[Calimero] Deleted data directory: {:?}", path_canonical);
-    Ok(format!("Deleted {}", path_canonical.display()))
+    Ok(serde_json::json!({
+        "path": path_canonical,
+        "existed": true,
+        "trashed": trashed,
+        "nodes": report.nodes,
+        "total_size": report.total_size,
+        "message": format!(
+            "{} {}",
+            if trashed { "Moved to trash:" } else { "Deleted" },
+            path_canonical.display()
+        )
+    }))
 }
 
 fn main() {
-    // Initialize logger - reads from RUST_LOG environment variable
-    // Default: info level in release, debug level in debug builds
-    env_logger::Builder::from_default_env()
-        .filter_level({
-            #[cfg(debug_assertions)]
-            {
-                log::LevelFilter::Debug
-            }
-            #[cfg(not(debug_assertions))]
-            {
-                log::LevelFilter::Info
+    let args: Vec<String> = std::env::args().collect();
+    let invocation = cli::parse(&args);
+    #[cfg(windows)]
+    if !matches!(invocation, Ok(cli::Invocation::Gui { .. })) {
+        cli::attach_parent_console();
+    }
+    let invocation = invocation.unwrap_or_else(|e| {
+        eprint!("{}", e);
+        std::process::exit(2);
+    });
+
+    let context = tauri::generate_context!();
+    let log_dir = tauri::api::path::app_log_dir(context.config());
+    let open_app = match invocation {
+        cli::Invocation::Gui { open_app } => open_app,
+        // Headless mode and subcommands never build the Tauri app, which needs a display even
+        // without windows
+        cli::Invocation::Headless(options) => {
+            backend_log::init(log_dir, backend_log::Console::Stdout);
+            std::process::exit(headless::run(AppPaths::from_context(&context), options));
+        }
+        cli::Invocation::Command { command, json } => {
+            // Subcommands print their own output; RUST_LOG shows the app's log for debugging
+            if std::env::var_os("RUST_LOG").is_some() {
+                backend_log::init(None, backend_log::Console::Stderr);
             }
-        })
-        .init();
+            std::process::exit(cli::run(AppPaths::from_context(&context), command, json));
+        }
+        cli::Invocation::Print(text) => {
+            print!("{}", text);
+            std::process::exit(0);
+        }
+    };
+
+    // Initialize logger - reads from RUST_LOG environment variable, also writes backend.log
+    // in the app log directory for support bundles
+    backend_log::init(log_dir, backend_log::Console::Stderr);
 
     // System tray with context menu
     let show = CustomMenuItem::new("show".to_string(), "Show Calimero");
@@ -1757,6 +2066,10 @@ fn main() {
             }
         })
         .on_window_event(|event| {
+            if let tauri::WindowEvent::Destroyed = event.event() {
+                event.window().state::<WindowCapabilities>().forget(event.window().label());
+                event.window().state::<NavigationPolicies>().forget(event.window().label());
+            }
             if event.window().label() != "main" {
                 return;
             }
@@ -1776,9 +2089,27 @@ fn main() {
                 _ => {}
             }
         })
-        .setup(|app| {
-            let pending = parse_open_app_args();
+        .setup(move |app| {
+            let pending = open_app;
             app.manage(PendingOpenApp(std::sync::Mutex::new(pending.clone())));
+            let profiles = match get_app_data_dir(&app.handle()) {
+                Ok(dir) => NodeProfiles::load(&dir),
+                Err(e) => {
+                    warn!("[Node Profiles] Profiles will not be persisted: {}", e);
+                    NodeProfiles::default()
+                }
+            };
+            app.manage(profiles);
+            let storage_guard = match get_app_data_dir(&app.handle()) {
+                Ok(dir) => storage::StorageGuard::load(&dir),
+                Err(e) => {
+                    warn!("[Storage] Storage settings will not be persisted: {}", e);
+                    storage::StorageGuard::default()
+                }
+            };
+            app.manage(storage_guard);
+            merod_sandbox::log_support();
+            storage::spawn_monitor(app.handle());
             // When launched from a desktop shortcut, hide the main window so only the app window is shown
             if pending.is_some() {
                 if let Some(window) = app.get_window("main") {
@@ -1828,7 +2159,13 @@ fn main() {
             Ok(())
         })
         .manage(MerodState::default())
-        .invoke_handler(tauri::generate_handler![
+        .manage(WindowCapabilities::default())
+        .manage(NavigationPolicies::default())
+        .manage(ProxyInspector::default())
+        .manage(ProxyMockState::from_env())
+        .manage(merod_info::MerodInfoCache::default())
+        .manage(crash_diagnosis::CrashReports::default())
+        .invoke_handler(capabilities::guard_invoke_handler(tauri::generate_handler![
             get_pending_open_app,
             clear_pending_open_app,
             hide_main_window,
@@ -1836,7 +2173,17 @@ fn main() {
             create_desktop_shortcut,
             create_app_window,
             open_devtools,
+            navigation::open_external_link,
             proxy_http_request,
+            proxy_inspector::set_proxy_inspector,
+            proxy_inspector::get_proxy_traffic,
+            proxy_inspector::clear_proxy_traffic,
+            proxy_inspector::export_proxy_har,
+            proxy_fixtures::set_proxy_mock_mode,
+            proxy_fixtures::get_proxy_mock_mode,
+            node_profiles::list_node_profiles,
+            node_profiles::save_node_profile,
+            node_profiles::remove_node_profile,
             start_merod,
             stop_merod,
             stop_merod_by_pid_command,
@@ -1845,16 +2192,46 @@ fn main() {
             check_merod_health,
             pick_directory,
             init_merod_node,
+            node_ops::delete_node,
+            node_ops::rename_node,
+            node_ops::clone_node,
+            node_archive::export_node,
+            node_archive::import_node,
+            home_migration::migrate_home,
+            home_migration::remove_migrated_home,
+            home_migration::get_saved_home,
+            local_cluster::create_local_cluster,
+            local_cluster::teardown_local_cluster,
             detect_running_merod_nodes,
             get_merod_logs,
             set_tray_icon_connected,
             delete_calimero_data_dir,
             kill_all_merod_processes,
+            merod_manager::download_merod,
+            merod_manager::list_merod_versions,
+            merod_manager::remove_merod_version,
+            merod_manager::pin_node_merod_version,
+            merod_manager::get_node_merod_version,
+            merod_info::get_merod_info,
+            merod_sandbox::get_sandbox_support,
+            node_registry::get_node_run_settings,
+            node_registry::set_node_run_settings,
+            node_log_level::set_node_log_level,
+            node_service::install_node_service,
+            node_service::uninstall_node_service,
+            node_service::get_node_service_status,
+            node_service::list_node_services,
+            crash_diagnosis::get_crash_diagnosis,
+            support_bundle::preview_support_bundle,
+            support_bundle::create_support_bundle,
+            storage::get_storage_report,
+            storage::get_storage_settings,
+            storage::set_storage_settings,
             autostart_enable,
             autostart_disable,
             autostart_is_enabled
-        ])
-        .run(tauri::generate_context!())
+        ]))
+        .run(context)
         .expect("error while running tauri application");
 }
 
@@ -1891,6 +2268,16 @@ mod tests {
         assert!(validate_allowed_url("http://localhost:2528/", Some("http://localhost:8080")).is_err());
     }
 
+    #[test]
+    fn test_configured_https_node_url() {
+        // HTTPS nodes are proxied when configured (TLS settings come from the node profile)
+        assert!(validate_allowed_url("https://node.example.com/admin-api/health", Some("https://node.example.com")).is_ok());
+        assert!(validate_allowed_url("https://node.example.com:2528/jsonrpc", Some("https://node.example.com:2528")).is_ok());
+        // Scheme must match the configured node exactly
+        assert!(validate_allowed_url("http://node.example.com:2528/", Some("https://node.example.com:2528")).is_err());
+        assert!(validate_allowed_url("https://node.example.com:443/", Some("https://node.example.com:2528")).is_err());
+    }
+
     #[test]
     fn test_allow_any_localhost_port() {
         // Any localhost port should be allowed (needed for multi-node setups)
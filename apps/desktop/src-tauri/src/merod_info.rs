@@ -0,0 +1,306 @@
+//! Which merod the app is talking to: `merod --version` parsed into a semver and build info,
+//! plus a check against the range of merod versions this app build supports.
+//!
+//! Results are cached per binary SHA-256, so asking again (or asking about another path to
+//! the same binary) doesn't spawn merod. For merod processes the app didn't start, the
+//! executable is only run when it is the bundled or an installed merod.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use log::{debug, warn};
+use serde::Serialize;
+
+use crate::app_paths::AppPaths;
+
+/// Lowest supported merod version (inclusive, pre-releases included).
+pub const SUPPORTED_MEROD_MIN: &str = "0.10.0-0";
+/// First unsupported merod version (exclusive, pre-releases included).
+pub const SUPPORTED_MEROD_MAX: &str = "0.11.0-0";
+
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MerodInfo {
+    pub path: PathBuf,
+    pub binary_sha256: String,
+    /// Raw `merod --version` output, trimmed.
+    pub raw: String,
+    /// Parsed semver, if the output contained one.
+    pub version: Option<String>,
+    /// Anything after the version, e.g. commit hash or build date.
+    pub build: Option<String>,
+    pub supported: bool,
+    pub supported_range: String,
+    /// Why the version isn't supported, or couldn't be checked.
+    pub warning: Option<String>,
+}
+
+pub fn supported_range() -> String {
+    format!(">={}, <{}", SUPPORTED_MEROD_MIN, SUPPORTED_MEROD_MAX)
+}
+
+/// Extracts the semver and trailing build info from `merod --version` output, e.g.
+/// `merod 0.10.0-rc.3 (abc1234 2025-06-01)`.
+pub fn parse_version_output(output: &str) -> Option<(semver::Version, Option<String>)> {
+    static VERSION_RE: OnceLock<regex::Regex> = OnceLock::new();
+    let re = VERSION_RE.get_or_init(|| {
+        regex::Regex::new(r"\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?").expect("valid version regex")
+    });
+    let found = re.find(output)?;
+    let version = semver::Version::parse(found.as_str()).ok()?;
+    let build: String = output[found.end()..]
+        .lines()
+        .map(|line| line.trim().trim_matches(|c| c == '(' || c == ')').trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("; ");
+    Some((version, (!build.is_empty()).then_some(build)))
+}
+
+/// Whether `version` is within the supported range, with a message for the UI if not.
+pub fn check_compatibility(version: &semver::Version) -> (bool, Option<String>) {
+    let min = semver::Version::parse(SUPPORTED_MEROD_MIN).expect("valid minimum merod version");
+    let max = semver::Version::parse(SUPPORTED_MEROD_MAX).expect("valid maximum merod version");
+    if *version < min {
+        (false, Some(format!(
+            "merod {} is older than this app supports ({}). Download a newer merod.",
+            version, supported_range()
+        )))
+    } else if *version >= max {
+        (false, Some(format!(
+            "merod {} is newer than this app supports ({}). Update the desktop app.",
+            version, supported_range()
+        )))
+    } else {
+        (true, None)
+    }
+}
+
+async fn run_version(path: &Path) -> Result<String, String> {
+    let output = tokio::time::timeout(
+        VERSION_TIMEOUT,
+        tokio::process::Command::new(path).arg("--version").kill_on_drop(true).output(),
+    )
+    .await
+    .map_err(|_| format!("`merod --version` timed out after {}s", VERSION_TIMEOUT.as_secs()))?
+    .map_err(|e| format!("Failed to run {:?} --version: {}", path, e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !stdout.is_empty() {
+        return Ok(stdout);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if !output.status.success() {
+        return Err(format!("`merod --version` failed ({}): {}", output.status, stderr));
+    }
+    Ok(stderr)
+}
+
+fn info_from_output(path: &Path, binary_sha256: String, raw: String) -> MerodInfo {
+    let (version, build, supported, warning) = match parse_version_output(&raw) {
+        Some((version, build)) => {
+            let (supported, warning) = check_compatibility(&version);
+            (Some(version.to_string()), build, supported, warning)
+        }
+        None => (
+            None,
+            None,
+            false,
+            Some(format!("Could not parse a version from `merod --version` output: {}", raw)),
+        ),
+    };
+    MerodInfo {
+        path: path.to_path_buf(),
+        binary_sha256,
+        raw,
+        version,
+        build,
+        supported,
+        supported_range: supported_range(),
+        warning,
+    }
+}
+
+#[derive(Default)]
+pub struct MerodInfoCache {
+    by_sha256: Mutex<HashMap<String, MerodInfo>>,
+    /// Version reported for each running (pid, executable) already looked at, so polling the
+    /// process list doesn't hash the executables again.
+    by_process: Mutex<HashMap<(u32, PathBuf), Option<String>>>,
+}
+
+impl MerodInfoCache {
+    /// Version info for the merod binary at `path`, running it only on a cache miss.
+    pub async fn info_for(&self, path: &Path) -> Result<MerodInfo, String> {
+        let binary_sha256 = crate::merod_integrity::binary_sha256(path)?;
+        self.info_with_sha256(path, binary_sha256).await
+    }
+
+    async fn info_with_sha256(&self, path: &Path, binary_sha256: String) -> Result<MerodInfo, String> {
+        let cached = self.by_sha256.lock().ok().and_then(|cache| cache.get(&binary_sha256).cloned());
+        if let Some(mut info) = cached {
+            info.path = path.to_path_buf();
+            return Ok(info);
+        }
+
+        let raw = run_version(path).await?;
+        let info = info_from_output(path, binary_sha256.clone(), raw);
+        match &info.warning {
+            Some(warning) => warn!("[Merod] {}", warning),
+            None => debug!("[Merod] {:?} is merod {}", path, info.version.as_deref().unwrap_or("?")),
+        }
+        if let Ok(mut cache) = self.by_sha256.lock() {
+            cache.insert(binary_sha256, info.clone());
+        }
+        Ok(info)
+    }
+
+    /// Version of the merod running as `pid`, which the app may not have started. Its
+    /// executable is only run when its SHA-256 matches the bundled or an installed merod;
+    /// anything else yields `None`.
+    pub async fn version_of_process(&self, pid: u32, paths: &AppPaths) -> Option<String> {
+        let exe = process_executable(pid)?;
+        let key = (pid, exe.clone());
+        if let Some(version) = self.by_process.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+            return version;
+        }
+
+        let version = match crate::merod_integrity::binary_sha256(&exe) {
+            Ok(hash) if paths.trusted_merod_sha256s().contains(&hash) => {
+                match self.info_with_sha256(&exe, hash).await {
+                    Ok(info) => info.version,
+                    Err(e) => {
+                        warn!("[Merod] Could not determine merod version of {:?}: {}", exe, e);
+                        None
+                    }
+                }
+            }
+            Ok(_) => {
+                debug!("[Merod] Not running {:?}: it is neither the bundled nor an installed merod", exe);
+                None
+            }
+            Err(e) => {
+                warn!("[Merod] Could not hash {:?}: {}", exe, e);
+                None
+            }
+        };
+        if let Ok(mut cache) = self.by_process.lock() {
+            cache.insert(key, version.clone());
+        }
+        version
+    }
+
+    /// Like `info_for`, but only the version string; failures are logged and yield `None`.
+    pub async fn version_of(&self, path: &Path) -> Option<String> {
+        match self.info_for(path).await {
+            Ok(info) => info.version,
+            Err(e) => {
+                warn!("[Merod] Could not determine merod version of {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+}
+
+/// Executable of a running process, used to report the version of merod nodes the app
+/// didn't start.
+fn process_executable(pid: u32) -> Option<PathBuf> {
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_link(format!("/proc/{}/exe", pid)).ok()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        #[cfg(unix)]
+        let output = std::process::Command::new("ps")
+            .arg("-o")
+            .arg("comm=")
+            .arg("-p")
+            .arg(pid.to_string())
+            .output()
+            .ok()?;
+        #[cfg(windows)]
+        let output = std::process::Command::new("wmic")
+            .arg("process")
+            .arg("where")
+            .arg(format!("ProcessId={}", pid))
+            .arg("get")
+            .arg("ExecutablePath")
+            .arg("/value")
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let path = stdout
+            .lines()
+            .map(|line| line.trim().trim_start_matches("ExecutablePath="))
+            .find(|line| !line.is_empty())?;
+        let path = PathBuf::from(path);
+        path.is_absolute().then_some(path)
+    }
+}
+
+/// Reports the version of the merod binary a node would run (its pinned version, otherwise
+/// the bundled binary) and whether this app supports it.
+#[tauri::command]
+pub async fn get_merod_info(
+    node_name: Option<String>,
+    home_dir: Option<String>,
+    app_handle: tauri::AppHandle,
+    cache: tauri::State<'_, MerodInfoCache>,
+) -> Result<MerodInfo, String> {
+    let binary = match node_name {
+        Some(node_name) => {
+            let home_dir_path = crate::resolve_calimero_home(home_dir)?;
+            crate::get_node_merod_binary(&app_handle, &home_dir_path, &node_name)?
+        }
+        None => {
+            let binary = crate::get_merod_binary_path(&app_handle)?;
+            crate::verify_bundled_merod(&app_handle, &binary)?;
+            binary
+        }
+    };
+    cache.info_for(&binary).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_with_build_info() {
+        let (version, build) = parse_version_output("merod 0.10.0-rc.3 (abc1234 2025-06-01)").unwrap();
+        assert_eq!(version.to_string(), "0.10.0-rc.3");
+        assert_eq!(build.as_deref(), Some("abc1234 2025-06-01"));
+
+        let (version, build) = parse_version_output("merod 0.10.2\ncommit: deadbeef\n").unwrap();
+        assert_eq!(version.to_string(), "0.10.2");
+        assert_eq!(build.as_deref(), Some("commit: deadbeef"));
+
+        let (version, build) = parse_version_output("merod 0.10.1").unwrap();
+        assert_eq!(version.to_string(), "0.10.1");
+        assert!(build.is_none());
+
+        assert!(parse_version_output("merod (unknown)").is_none());
+    }
+
+    #[test]
+    fn test_supported_range() {
+        let check = |v: &str| check_compatibility(&semver::Version::parse(v).unwrap()).0;
+        assert!(check("0.10.0-rc.1"));
+        assert!(check("0.10.0"));
+        assert!(check("0.10.7"));
+        assert!(!check("0.9.3"));
+        assert!(!check("0.11.0-rc.1"));
+        assert!(!check("1.0.0"));
+    }
+
+    #[test]
+    fn test_unparseable_output_is_unsupported() {
+        let info = info_from_output(Path::new("/bin/merod"), "00".to_string(), "garbage".to_string());
+        assert!(!info.supported);
+        assert!(info.warning.is_some());
+    }
+}
@@ -0,0 +1,184 @@
+//! Integrity checks for the merod binary before it is executed.
+//!
+//! The bundle ships `merod/merod.manifest.json` next to the binary, written by
+//! `write-merod-manifest.sh` after the last change to the binary (in CI, after codesign). It
+//! records the binary's size and SHA-256 and, when the build was signed, a minisign signature
+//! made with the updater key (the same format `tauri signer sign` produces). Versions installed by the merod manager are checked against the hash recorded at
+//! install time.
+//!
+//! The binary is re-hashed before every spawn: a cache keyed on file metadata would accept a
+//! binary replaced in place with its size and mtime restored.
+
+use std::path::Path;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Manifest for the bundled binary, resolved as a Tauri resource.
+pub const BUNDLED_MANIFEST_RESOURCE: &str = "merod/merod.manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinaryManifest {
+    #[serde(default)]
+    pub version: Option<String>,
+    pub sha256: String,
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// Base64-encoded minisign signature of the binary (`tauri signer sign` output).
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// SHA-256 of the file at `path`.
+pub fn binary_sha256(path: &Path) -> Result<String, String> {
+    crate::merod_manager::sha256_file(path)
+}
+
+fn decode_base64_text(value: &str, what: &str) -> Result<String, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(value.trim())
+        .map_err(|e| format!("Invalid {} encoding: {}", what, e))?;
+    String::from_utf8(bytes).map_err(|_| format!("Invalid {}: not UTF-8", what))
+}
+
+/// Checks a minisign `signature` (base64, as written by `tauri signer sign`) of `data`
+/// against `pubkey` (base64, as stored in the updater config).
+pub fn verify_signature(data: &[u8], signature: &str, pubkey: &str) -> Result<(), String> {
+    let pubkey = minisign_verify::PublicKey::decode(&decode_base64_text(pubkey, "public key")?)
+        .map_err(|e| format!("Invalid public key: {}", e))?;
+    let signature = minisign_verify::Signature::decode(&decode_base64_text(signature, "signature")?)
+        .map_err(|e| format!("Invalid signature: {}", e))?;
+    pubkey
+        .verify(data, &signature, true)
+        .map_err(|e| format!("Signature verification failed: {}", e))
+}
+
+/// Verifies `path` against `manifest`. A signature in the manifest is only accepted when it
+/// validates with `pubkey`.
+pub fn verify_binary(path: &Path, manifest: &BinaryManifest, pubkey: Option<&str>) -> Result<(), String> {
+    let actual_size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to stat merod binary {:?}: {}", path, e))?
+        .len();
+    if let Some(expected_size) = manifest.size {
+        if actual_size != expected_size {
+            return Err(format!(
+                "The merod binary at {:?} is {} bytes but {} bytes were expected{}. Reinstall the app or re-download merod.",
+                path,
+                actual_size,
+                expected_size,
+                if actual_size < expected_size { " (truncated)" } else { "" }
+            ));
+        }
+    }
+
+    let actual = binary_sha256(path)?;
+    if !actual.eq_ignore_ascii_case(manifest.sha256.trim()) {
+        return Err(format!(
+            "The merod binary at {:?} failed its integrity check (expected SHA-256 {}, got {}). It may be corrupted or tampered with; reinstall the app or re-download merod.",
+            path, manifest.sha256, actual
+        ));
+    }
+
+    if let Some(signature) = &manifest.signature {
+        let pubkey = pubkey.filter(|k| !k.is_empty()).ok_or_else(|| {
+            "The merod binary is signed, but no public key is configured to verify it".to_string()
+        })?;
+        let data = std::fs::read(path).map_err(|e| format!("Failed to read merod binary {:?}: {}", path, e))?;
+        verify_signature(&data, signature, pubkey)
+            .map_err(|e| format!("The merod binary at {:?} has an invalid signature: {}", path, e))?;
+    }
+
+    Ok(())
+}
+
+pub fn read_manifest(path: &Path) -> Result<BinaryManifest, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read merod manifest {:?}: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid merod manifest {:?}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_file(name: &str, content: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("calimero-integrity-{}-{}", name, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn manifest_for(content: &[u8]) -> BinaryManifest {
+        use sha2::{Digest, Sha256};
+        BinaryManifest {
+            version: None,
+            sha256: hex::encode(Sha256::digest(content)),
+            size: Some(content.len() as u64),
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_matching_binary_passes() {
+        let path = temp_file("ok", b"merod binary");
+        assert!(verify_binary(&path, &manifest_for(b"merod binary"), None).is_ok());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_truncated_binary_reports_size() {
+        let path = temp_file("truncated", b"merod");
+        let err = verify_binary(&path, &manifest_for(b"merod binary"), None).unwrap_err();
+        assert!(err.contains("truncated"), "{}", err);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_tampered_binary_fails_hash() {
+        let path = temp_file("tampered", b"merod binarY");
+        let err = verify_binary(&path, &manifest_for(b"merod binary"), None).unwrap_err();
+        assert!(err.contains("integrity check"), "{}", err);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_binary_replaced_in_place_fails() {
+        let path = temp_file("replaced", b"merod binary");
+        let manifest = manifest_for(b"merod binary");
+        assert!(verify_binary(&path, &manifest, None).is_ok());
+
+        // Same size, mtime restored: still caught
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        std::fs::write(&path, b"merod binarY").unwrap();
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        assert!(verify_binary(&path, &manifest, None).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_bundled_manifest_matches_binary() {
+        // Only when a merod binary has been downloaded into the source tree for bundling
+        let merod_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("merod");
+        let binary = merod_dir.join("merod");
+        if !binary.exists() {
+            return;
+        }
+        let manifest = read_manifest(&merod_dir.join("merod.manifest.json")).unwrap();
+        let config: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("tauri.conf.json")).unwrap(),
+        )
+        .unwrap();
+        let pubkey = config["tauri"]["updater"]["pubkey"].as_str();
+        verify_binary(&binary, &manifest, pubkey).unwrap();
+    }
+
+    #[test]
+    fn test_signature_requires_valid_key() {
+        let path = temp_file("signed", b"merod binary");
+        let mut manifest = manifest_for(b"merod binary");
+        manifest.signature = Some("bm90IGEgc2lnbmF0dXJl".to_string());
+        assert!(verify_binary(&path, &manifest, None).is_err());
+        assert!(verify_binary(&path, &manifest, Some("bm90IGEga2V5")).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,615 @@
+//! Versioned merod binary manager.
+//!
+//! Besides the merod bundled at build time (`download-merod.sh`), merod versions can be
+//! installed into the app data directory from a release archive, either a local file or a URL
+//! (by default the calimero-network/core GitHub release for this platform). Each install is
+//! checksum-verified (or, only when explicitly allowed, marked unverified) and recorded in a
+//! manifest. A node can pin an installed version;
+//! unpinned nodes, or nodes pinned to a version that is no longer installed, use the bundled
+//! binary.
+//!
+//! Layout under `<app data>/merod`:
+//! - `versions/<version>/merod` and `versions/<version>/manifest.json`
+//! - `pins.json`: node directory -> pinned version
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+
+const RELEASES_API: &str = "https://api.github.com/repos/calimero-network/core/releases?per_page=1";
+const RELEASE_DOWNLOAD_BASE: &str = "https://github.com/calimero-network/core/releases/download";
+
+/// Version name reported for the binary shipped inside the app bundle.
+pub const BUNDLED_VERSION: &str = "bundled";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledMerod {
+    pub version: String,
+    /// SHA-256 of the extracted `merod` binary.
+    pub binary_sha256: String,
+    /// SHA-256 of the archive it was installed from.
+    pub archive_sha256: String,
+    /// Whether `archive_sha256` was checked against a published or user-supplied checksum.
+    pub verified: bool,
+    pub source: String,
+    pub installed_at: String,
+    #[serde(skip_deserializing)]
+    pub path: PathBuf,
+}
+
+/// Release asset name for the current platform, matching `download-merod.sh`.
+pub fn platform_asset_name() -> Result<&'static str, String> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("macos", "aarch64") => Ok("merod_aarch64-apple-darwin.tar.gz"),
+        ("macos", "x86_64") => Ok("merod_x86_64-apple-darwin.tar.gz"),
+        ("linux", "x86_64") => Ok("merod_x86_64-unknown-linux-gnu.tar.gz"),
+        (os, arch) => Err(format!("No merod release is published for {} on {}", arch, os)),
+    }
+}
+
+/// Versions become directory names, so only a conservative character set is accepted.
+pub fn validate_version(version: &str) -> Result<(), String> {
+    let valid = !version.is_empty()
+        && version.len() <= 64
+        && version != BUNDLED_VERSION
+        && !version.starts_with('.')
+        && version.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid merod version '{}'. Use letters, digits, '.', '-', '_' or '+' (e.g. 0.10.0-rc.3)",
+            version
+        ))
+    }
+}
+
+pub fn manager_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("merod")
+}
+
+fn versions_dir(app_data_dir: &Path) -> PathBuf {
+    manager_dir(app_data_dir).join("versions")
+}
+
+fn pins_path(app_data_dir: &Path) -> PathBuf {
+    manager_dir(app_data_dir).join("pins.json")
+}
+
+/// Key a node is pinned under: its directory, `<home>/<node>`.
+fn pin_key(home_dir: &Path, node_name: &str) -> String {
+    home_dir.join(node_name).to_string_lossy().into_owned()
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Accepts `<hex>` or the `sha256sum` format `<hex>  <file name>`.
+fn parse_checksum(text: &str) -> Result<String, String> {
+    let hash = text.split_whitespace().next().unwrap_or("").to_lowercase();
+    if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(hash)
+    } else {
+        Err(format!("'{}' is not a SHA-256 checksum", text.trim()))
+    }
+}
+
+/// Extracts the `merod` entry of a `.tar.gz` release archive into `dest_dir`.
+pub fn extract_merod(archive: &Path, dest_dir: &Path) -> Result<PathBuf, String> {
+    let file = std::fs::File::open(archive).map_err(|e| format!("Failed to open archive {:?}: {}", archive, e))?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let entries = tar.entries().map_err(|e| format!("Failed to read archive {:?}: {}", archive, e))?;
+
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Corrupt archive {:?}: {}", archive, e))?;
+        let is_merod = entry
+            .path()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n == "merod"))
+            .unwrap_or(false);
+        if !is_merod || !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        std::fs::create_dir_all(dest_dir)
+            .map_err(|e| format!("Failed to create {:?}: {}", dest_dir, e))?;
+        let dest = dest_dir.join("merod");
+        let mut out = std::fs::File::create(&dest).map_err(|e| format!("Failed to create {:?}: {}", dest, e))?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| format!("Failed to extract merod: {}", e))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&dest, std::fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("Failed to chmod {:?}: {}", dest, e))?;
+        }
+        return Ok(dest);
+    }
+
+    Err(format!("Archive {:?} does not contain a merod binary", archive))
+}
+
+pub fn list_installed(app_data_dir: &Path) -> Vec<InstalledMerod> {
+    let Ok(entries) = std::fs::read_dir(versions_dir(app_data_dir)) else { return Vec::new() };
+    let mut installed: Vec<InstalledMerod> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let content = std::fs::read_to_string(dir.join("manifest.json")).ok()?;
+            let mut manifest: InstalledMerod = serde_json::from_str(&content).ok()?;
+            manifest.path = dir.join("merod");
+            manifest.path.exists().then_some(manifest)
+        })
+        .collect();
+    installed.sort_by(|a, b| a.version.cmp(&b.version));
+    installed
+}
+
+pub fn find_installed(app_data_dir: &Path, version: &str) -> Option<InstalledMerod> {
+    list_installed(app_data_dir).into_iter().find(|m| m.version == version)
+}
+
+fn load_pins(app_data_dir: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(pins_path(app_data_dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_pins(app_data_dir: &Path, pins: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = pins_path(app_data_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    let content = serde_json::to_string_pretty(pins).map_err(|e| format!("Failed to serialize pins: {}", e))?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+pub fn pinned_version(app_data_dir: &Path, home_dir: &Path, node_name: &str) -> Option<String> {
+    load_pins(app_data_dir).remove(&pin_key(home_dir, node_name))
+}
+
+/// Pins `node_name` to `version`, or removes the pin when `version` is `None`.
+pub fn set_pin(app_data_dir: &Path, home_dir: &Path, node_name: &str, version: Option<&str>) -> Result<(), String> {
+    let mut pins = load_pins(app_data_dir);
+    match version {
+        Some(version) => {
+            pins.insert(pin_key(home_dir, node_name), version.to_string());
+        }
+        None => {
+            pins.remove(&pin_key(home_dir, node_name));
+        }
+    }
+    save_pins(app_data_dir, &pins)
+}
+
+/// The installed merod a node is pinned to. `None` when the node is unpinned or its pinned
+/// version is no longer installed; callers then fall back to the bundled binary.
+pub fn pinned_binary(app_data_dir: &Path, home_dir: &Path, node_name: &str) -> Option<InstalledMerod> {
+    let version = pinned_version(app_data_dir, home_dir, node_name)?;
+    let installed = find_installed(app_data_dir, &version);
+    if installed.is_none() {
+        warn!(
+            "[Merod Manager] Node '{}' is pinned to merod {} which is not installed; using the bundled binary",
+            node_name, version
+        );
+    }
+    installed
+}
+
+async fn latest_release_tag(client: &reqwest::Client) -> Result<String, String> {
+    let releases: Vec<serde_json::Value> = client
+        .get(RELEASES_API)
+        .header("User-Agent", "calimero-desktop")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query merod releases: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse merod releases: {}", e))?;
+    releases
+        .first()
+        .and_then(|r| r.get("tag_name"))
+        .and_then(|t| t.as_str())
+        .map(|t| t.to_string())
+        .ok_or_else(|| "No merod release found on GitHub".to_string())
+}
+
+/// Streams `url` into `dest`, returning the SHA-256 of the downloaded bytes.
+async fn download_to(client: &reqwest::Client, url: &str, dest: &Path) -> Result<String, String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+    let mut file = tokio::fs::File::create(dest)
+        .await
+        .map_err(|e| format!("Failed to create {:?}: {}", dest, e))?;
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Download of {} interrupted: {}", url, e))?
+    {
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write {:?}: {}", dest, e))?;
+    }
+    file.flush().await.map_err(|e| format!("Failed to write {:?}: {}", dest, e))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Checksum published next to a release asset (`<asset>.sha256`), if any. Only trusted for
+/// HTTPS sources: over plain HTTP it could be swapped along with the archive.
+async fn published_checksum(client: &reqwest::Client, url: &str) -> Option<String> {
+    let response = client.get(format!("{}.sha256", url)).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    parse_checksum(&response.text().await.ok()?).ok()
+}
+
+/// Installs merod `version` from `source` (a local `.tar.gz` path or an http(s) URL).
+/// Without a source the GitHub release asset for this platform is used; without a version,
+/// the latest release. `expected_sha256` is checked against the archive; for HTTPS sources a
+/// published `<asset>.sha256` is used when no checksum is given. Plain `http://` sources need
+/// `expected_sha256`. Without any checksum the install is refused unless `allow_unverified`
+/// is true.
+pub async fn install(
+    app_data_dir: &Path,
+    version: Option<String>,
+    source: Option<String>,
+    expected_sha256: Option<String>,
+    allow_unverified: bool,
+) -> Result<InstalledMerod, String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(600))
+        .build()
+        .map_err(|e| format!("Failed to initialize HTTP client: {}", e))?;
+
+    let version = match (version, &source) {
+        (Some(version), _) => version,
+        (None, None) => latest_release_tag(&client).await?,
+        (None, Some(_)) => return Err("A version name is required when installing merod from a custom source".to_string()),
+    };
+    validate_version(&version)?;
+    if find_installed(app_data_dir, &version).is_some() {
+        return Err(format!("merod {} is already installed", version));
+    }
+
+    let source = match source {
+        Some(source) => source,
+        None => format!("{}/{}/{}", RELEASE_DOWNLOAD_BASE, version, platform_asset_name()?),
+    };
+    let expected = expected_sha256.as_deref().map(parse_checksum).transpose()?;
+    if source.starts_with("http://") && expected.is_none() {
+        return Err(format!(
+            "Refusing to download merod over plain HTTP from {} without its SHA-256. Use an https:// URL or provide the checksum.",
+            source
+        ));
+    }
+
+    let staging = manager_dir(app_data_dir).join(format!(".staging-{}", version));
+    let staging_clone = staging.clone();
+    tokio::task::spawn_blocking(move || {
+        let _ = std::fs::remove_dir_all(&staging_clone);
+        std::fs::create_dir_all(&staging_clone).map_err(|e| format!("Failed to create {:?}: {}", staging_clone, e))
+    })
+    .await
+    .map_err(|e| format!("Install task failed: {}", e))??;
+
+    let checked = async {
+        let is_url = source.starts_with("http://") || source.starts_with("https://");
+        let (archive, archive_sha256, expected) = if is_url {
+            info!("[Merod Manager] Downloading merod {} from {}", version, source);
+            let archive = staging.join("merod.tar.gz");
+            let hash = download_to(&client, &source, &archive).await?;
+            let expected = match expected {
+                Some(expected) => Some(expected),
+                None => published_checksum(&client, &source).await,
+            };
+            (archive, hash, expected)
+        } else {
+            let archive = PathBuf::from(&source);
+            let archive_clone = archive.clone();
+            let hash = tokio::task::spawn_blocking(move || sha256_file(&archive_clone))
+                .await
+                .map_err(|e| format!("Install task failed: {}", e))??;
+            (archive, hash, expected)
+        };
+
+        let verified = match &expected {
+            Some(expected) if *expected != archive_sha256 => {
+                return Err(format!(
+                    "Checksum mismatch for {}: expected {}, got {}. The download may be corrupted or tampered with.",
+                    source, expected, archive_sha256
+                ));
+            }
+            Some(_) => true,
+            None if allow_unverified => {
+                warn!("[Merod Manager] No checksum available for {}; installing unverified", source);
+                false
+            }
+            None => {
+                return Err(format!(
+                    "No checksum is available for {}. Provide its SHA-256, or explicitly allow installing an unverified merod.",
+                    source
+                ));
+            }
+        };
+        Ok::<_, String>((archive, archive_sha256, verified))
+    }
+    .await;
+
+    let app_data_dir = app_data_dir.to_path_buf();
+    let installed = tokio::task::spawn_blocking(move || {
+        let installed = checked.and_then(|(archive, archive_sha256, verified)| {
+            install_archive(&app_data_dir, &version, &source, &archive, &staging, archive_sha256, verified)
+        });
+        let _ = std::fs::remove_dir_all(&staging);
+        installed
+    })
+    .await
+    .map_err(|e| format!("Install task failed: {}", e))??;
+    info!("[Merod Manager] Installed merod {} ({})", installed.version, if installed.verified { "verified" } else { "unverified" });
+    Ok(installed)
+}
+
+/// Extracts a checked archive in `staging` and moves its merod into the versions directory.
+fn install_archive(
+    app_data_dir: &Path,
+    version: &str,
+    source: &str,
+    archive: &Path,
+    staging: &Path,
+    archive_sha256: String,
+    verified: bool,
+) -> Result<InstalledMerod, String> {
+    let binary = extract_merod(archive, &staging.join("bin"))?;
+    let target_dir = versions_dir(app_data_dir).join(version);
+    std::fs::create_dir_all(&target_dir).map_err(|e| format!("Failed to create {:?}: {}", target_dir, e))?;
+    let target = target_dir.join("merod");
+    std::fs::rename(&binary, &target).map_err(|e| format!("Failed to install merod binary: {}", e))?;
+
+    let manifest = InstalledMerod {
+        version: version.to_string(),
+        binary_sha256: sha256_file(&target)?,
+        archive_sha256,
+        verified,
+        source: source.to_string(),
+        installed_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        path: target,
+    };
+    let content = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    std::fs::write(target_dir.join("manifest.json"), content)
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+    Ok(manifest)
+}
+
+pub fn uninstall(app_data_dir: &Path, version: &str) -> Result<(), String> {
+    validate_version(version)?;
+    let pinned_by: Vec<String> = load_pins(app_data_dir)
+        .into_iter()
+        .filter(|(_, v)| v == version)
+        .map(|(node, _)| node)
+        .collect();
+    if !pinned_by.is_empty() {
+        return Err(format!(
+            "merod {} is pinned by {}. Unpin those nodes first.",
+            version,
+            pinned_by.join(", ")
+        ));
+    }
+    let dir = versions_dir(app_data_dir).join(version);
+    if !dir.exists() {
+        return Err(format!("merod {} is not installed", version));
+    }
+    std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove {:?}: {}", dir, e))?;
+    info!("[Merod Manager] Removed merod {}", version);
+    Ok(())
+}
+
+/// Installs a merod version into the app data directory. With no arguments, installs the
+/// latest GitHub release for this platform. Archives without a checksum are only installed
+/// with `allow_unverified`.
+#[tauri::command]
+pub async fn download_merod(
+    version: Option<String>,
+    source: Option<String>,
+    sha256: Option<String>,
+    allow_unverified: Option<bool>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    let installed = install(&app_data_dir, version, source, sha256, allow_unverified.unwrap_or(false)).await?;
+    Ok(format!(
+        "merod {} installed at {}{}",
+        installed.version,
+        installed.path.display(),
+        if installed.verified { "" } else { " (unverified: no checksum was available)" }
+    ))
+}
+
+/// Lists the bundled merod and every installed version.
+#[tauri::command]
+pub async fn list_merod_versions(app_handle: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    let bundled = crate::get_merod_binary_path(&app_handle).ok();
+    Ok(serde_json::json!({
+        "bundled": {
+            "version": BUNDLED_VERSION,
+            "available": bundled.is_some(),
+            "path": bundled,
+        },
+        "installed": list_installed(&app_data_dir),
+    }))
+}
+
+#[tauri::command]
+pub async fn remove_merod_version(version: String, app_handle: tauri::AppHandle) -> Result<String, String> {
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    uninstall(&app_data_dir, &version)?;
+    Ok(format!("Removed merod {}", version))
+}
+
+/// Pins a node to an installed merod version. Pass no version (or "bundled") to unpin.
+#[tauri::command]
+pub async fn pin_node_merod_version(
+    node_name: String,
+    home_dir: Option<String>,
+    version: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    let home_dir_path = crate::resolve_calimero_home(home_dir)?;
+    let version = version.filter(|v| v != BUNDLED_VERSION);
+
+    if let Some(version) = &version {
+        if find_installed(&app_data_dir, version).is_none() {
+            return Err(format!("merod {} is not installed. Download it first.", version));
+        }
+    }
+    set_pin(&app_data_dir, &home_dir_path, &node_name, version.as_deref())?;
+
+    let message = match &version {
+        Some(version) => format!("Node '{}' pinned to merod {}", node_name, version),
+        None => format!("Node '{}' uses the bundled merod", node_name),
+    };
+    info!("[Merod Manager] {}", message);
+    Ok(message)
+}
+
+#[tauri::command]
+pub async fn get_node_merod_version(
+    node_name: String,
+    home_dir: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    let home_dir_path = crate::resolve_calimero_home(home_dir)?;
+    Ok(pinned_binary(&app_data_dir, &home_dir_path, &node_name)
+        .map(|installed| installed.version)
+        .unwrap_or_else(|| BUNDLED_VERSION.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("calimero-merod-manager-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_archive(path: &Path, entry_name: &str, content: &[u8]) {
+        let file = std::fs::File::create(path).unwrap();
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, entry_name, content).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_validate_version() {
+        assert!(validate_version("0.10.0-rc.3").is_ok());
+        assert!(validate_version("430df052").is_ok());
+        assert!(validate_version("").is_err());
+        assert!(validate_version("../etc").is_err());
+        assert!(validate_version("a/b").is_err());
+        assert!(validate_version(BUNDLED_VERSION).is_err());
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        let hash = "a".repeat(64);
+        assert_eq!(parse_checksum(&hash).unwrap(), hash);
+        assert_eq!(parse_checksum(&format!("{}  merod.tar.gz\n", hash.to_uppercase())).unwrap(), hash);
+        assert!(parse_checksum("abc").is_err());
+    }
+
+    #[test]
+    fn test_extract_merod_from_archive() {
+        let dir = temp_dir("extract");
+        let archive = dir.join("merod.tar.gz");
+        write_archive(&archive, "merod", b"#!/bin/sh\necho merod\n");
+        let binary = extract_merod(&archive, &dir.join("out")).unwrap();
+        assert_eq!(std::fs::read(&binary).unwrap(), b"#!/bin/sh\necho merod\n");
+
+        let other = dir.join("other.tar.gz");
+        write_archive(&other, "README.md", b"nothing here");
+        assert!(extract_merod(&other, &dir.join("out2")).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_install_from_local_archive_with_checksum() {
+        let dir = temp_dir("install");
+        let archive = dir.join("merod.tar.gz");
+        write_archive(&archive, "merod", b"binary");
+        let archive_hash = sha256_file(&archive).unwrap();
+        let source = archive.to_string_lossy().into_owned();
+
+        let wrong = install(&dir, Some("1.0.0".into()), Some(source.clone()), Some("0".repeat(64)), false).await;
+        assert!(wrong.unwrap_err().contains("Checksum mismatch"));
+        let unchecked = install(&dir, Some("1.0.0".into()), Some(source.clone()), None, false).await;
+        assert!(unchecked.unwrap_err().contains("No checksum"));
+        assert!(list_installed(&dir).is_empty());
+
+        let installed =
+            install(&dir, Some("1.0.0".into()), Some(source.clone()), Some(archive_hash), false).await.unwrap();
+        assert!(installed.verified);
+        assert_eq!(list_installed(&dir).len(), 1);
+        assert!(install(&dir, Some("1.0.0".into()), Some(source.clone()), None, true).await.is_err());
+
+        let unverified = install(&dir, Some("1.0.1".into()), Some(source), None, true).await.unwrap();
+        assert!(!unverified.verified);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_plain_http_requires_checksum() {
+        let dir = temp_dir("http");
+        let source = "http://127.0.0.1:9/merod.tar.gz".to_string();
+        for allow_unverified in [false, true] {
+            let err = install(&dir, Some("1.0.0".into()), Some(source.clone()), None, allow_unverified)
+                .await
+                .unwrap_err();
+            assert!(err.contains("plain HTTP"), "{}", err);
+        }
+        assert!(!manager_dir(&dir).join(".staging-1.0.0").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_pins_fall_back_to_bundled() {
+        let dir = temp_dir("pins");
+        let home = Path::new("/home/user/.calimero");
+
+        assert!(pinned_binary(&dir, home, "node1").is_none());
+        set_pin(&dir, home, "node1", Some("9.9.9")).unwrap();
+        assert_eq!(pinned_version(&dir, home, "node1").as_deref(), Some("9.9.9"));
+        assert!(pinned_version(&dir, home, "node2").is_none());
+        // Pinned but not installed: caller falls back to the bundled binary
+        assert!(pinned_binary(&dir, home, "node1").is_none());
+        assert!(uninstall(&dir, "9.9.9").unwrap_err().contains("pinned"));
+        set_pin(&dir, home, "node1", None).unwrap();
+        assert!(pinned_version(&dir, home, "node1").is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
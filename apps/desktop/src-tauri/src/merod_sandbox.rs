@@ -0,0 +1,452 @@
+//! Optional hardened launch mode for merod (Linux only).
+//!
+//! A node with the `sandbox` run setting starts with no-new-privs, resource limits and its
+//! filesystem access restricted to its node directory, the merod binary and the read-only
+//! system paths a dynamically linked binary needs. Landlock is used when the kernel supports
+//! it, otherwise bubblewrap if `bwrap` is installed. If neither is available the node does
+//! not start: a sandboxed node never silently runs unconfined.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use log::{info, warn};
+use serde::Serialize;
+use tokio::process::Command;
+
+#[cfg(target_os = "linux")]
+use crate::node_limits::lower_rlimit;
+
+/// Data segment limit (heap and other private writable mappings) for sandboxed nodes.
+const MAX_DATA_BYTES: u64 = 8 * 1024 * 1024 * 1024;
+/// Open file limit for sandboxed nodes; RocksDB keeps many SST files open.
+const MAX_OPEN_FILES: u64 = 8192;
+
+/// System directories mounted (bubblewrap) or allowed (Landlock) read-only.
+const SYSTEM_READ_ONLY_DIRS: &[&str] = &[
+    "/usr",
+    "/lib",
+    "/lib64",
+    "/lib32",
+    "/bin",
+    "/sbin",
+    "/etc",
+    "/nix/store",
+    // /etc/resolv.conf commonly links here
+    "/run/systemd/resolve",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxBackend {
+    Landlock,
+    Bubblewrap,
+}
+
+/// What this machine supports, determined once at startup.
+#[derive(Debug, Clone, Serialize)]
+pub struct SandboxSupport {
+    pub supported: bool,
+    /// Backend sandboxed nodes will use.
+    pub backend: Option<SandboxBackend>,
+    pub landlock_abi: Option<u32>,
+    pub bubblewrap: Option<PathBuf>,
+    pub message: String,
+}
+
+static SUPPORT: OnceLock<SandboxSupport> = OnceLock::new();
+
+#[cfg(target_os = "linux")]
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    std::env::var_os("PATH")?
+        .to_str()?
+        .split(':')
+        .map(|dir| Path::new(dir).join(program))
+        .find(|path| path.is_file())
+}
+
+fn detect() -> SandboxSupport {
+    #[cfg(target_os = "linux")]
+    let (landlock_abi, bubblewrap) = (landlock::abi_version(), find_in_path("bwrap"));
+    #[cfg(not(target_os = "linux"))]
+    let (landlock_abi, bubblewrap): (Option<u32>, Option<PathBuf>) = (None, None);
+
+    let (backend, message) = match (landlock_abi, &bubblewrap) {
+        _ if !cfg!(target_os = "linux") => (None, "Sandboxed nodes are only supported on Linux".to_string()),
+        (Some(abi), _) => (
+            Some(SandboxBackend::Landlock),
+            format!("The kernel supports Landlock (ABI v{}); sandboxed nodes are confined with it", abi),
+        ),
+        (None, Some(bwrap)) => (
+            Some(SandboxBackend::Bubblewrap),
+            format!(
+                "The kernel does not support Landlock (Linux 5.13+ with the landlock LSM enabled); \
+                 sandboxed nodes run under bubblewrap ({})",
+                bwrap.display()
+            ),
+        ),
+        (None, None) => (
+            None,
+            "The kernel does not support Landlock (Linux 5.13+ with the landlock LSM enabled) and \
+             bubblewrap is not installed, so sandboxed nodes cannot start"
+                .to_string(),
+        ),
+    };
+    SandboxSupport { supported: backend.is_some(), backend, landlock_abi, bubblewrap, message }
+}
+
+pub fn support() -> &'static SandboxSupport {
+    SUPPORT.get_or_init(detect)
+}
+
+/// Logs the capability check; called once at startup.
+pub fn log_support() {
+    let support = support();
+    if support.supported || !cfg!(target_os = "linux") {
+        info!("[Sandbox] {}", support.message);
+    } else {
+        warn!("[Sandbox] {}", support.message);
+    }
+}
+
+/// A prepared sandbox. Apply it with [`Sandbox::confine`], keep it until the command has been
+/// spawned, then resolve merod's PID with [`Sandbox::merod_pid`].
+pub struct Sandbox {
+    pub backend: SandboxBackend,
+    #[cfg(target_os = "linux")]
+    ruleset: Option<std::os::fd::OwnedFd>,
+    /// bubblewrap's `--info-fd` pipe: (read end, write end)
+    #[cfg(target_os = "linux")]
+    info_pipe: Option<(std::fs::File, std::os::fd::OwnedFd)>,
+}
+
+impl Sandbox {
+    /// Makes `cmd` drop its privileges and enter the sandbox right before exec. Call it after
+    /// any other `pre_exec` setup (such as joining a cgroup), which would otherwise run confined.
+    pub fn confine(&self, cmd: &mut Command) {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::fd::AsRawFd;
+            let ruleset_fd = self.ruleset.as_ref().map(AsRawFd::as_raw_fd);
+            let info_fd = self.info_pipe.as_ref().map(|(_, writer)| writer.as_raw_fd());
+            // Safety: the closure only makes async-signal-safe syscalls, and the descriptors
+            // stay open until the Sandbox is dropped after spawning
+            unsafe {
+                cmd.pre_exec(move || {
+                    // Let bwrap inherit the write end of the info pipe
+                    if let Some(fd) = info_fd {
+                        if libc::fcntl(fd, libc::F_SETFD, 0) != 0 {
+                            return Err(std::io::Error::last_os_error());
+                        }
+                    }
+                    // bwrap may be setuid, which no-new-privs would defeat; it sets
+                    // no-new-privs for merod itself
+                    harden_current_process(ruleset_fd.is_some())?;
+                    match ruleset_fd {
+                        Some(fd) => landlock::restrict_self(fd),
+                        None => Ok(()),
+                    }
+                });
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = cmd;
+    }
+
+    /// PID of merod itself. Under bubblewrap the spawned process is `bwrap`, which reports the
+    /// PID of its child on the info pipe; that is the process to signal when stopping.
+    pub async fn merod_pid(self, spawned_pid: u32) -> Result<u32, String> {
+        #[cfg(target_os = "linux")]
+        {
+            if let Some((mut reader, writer)) = self.info_pipe {
+                // Our copy of the write end would keep the pipe open if bwrap dies before reporting
+                drop(writer);
+                let read = tokio::task::spawn_blocking(move || bubblewrap::read_child_pid(&mut reader));
+                return match tokio::time::timeout(std::time::Duration::from_secs(5), read).await {
+                    Ok(Ok(result)) => result,
+                    Ok(Err(e)) => Err(format!("Failed to read the bubblewrap child PID: {}", e)),
+                    Err(_) => Err("bubblewrap did not report the merod PID within 5 seconds".to_string()),
+                };
+            }
+        }
+        Ok(spawned_pid)
+    }
+}
+
+/// Builds the command that runs `binary` confined to `node_dir`. Arguments, environment and
+/// stdio are added by the caller as for an unconfined command, followed by
+/// [`Sandbox::confine`].
+pub fn command(binary: &Path, node_dir: &Path) -> Result<(Command, Sandbox), String> {
+    let support = support();
+    let backend = support.backend.ok_or_else(|| support.message.clone())?;
+    #[cfg(target_os = "linux")]
+    {
+        match backend {
+            SandboxBackend::Landlock => landlock_command(binary, node_dir, support.landlock_abi.unwrap_or(1)),
+            SandboxBackend::Bubblewrap => {
+                let bwrap = support.bubblewrap.as_deref().ok_or("bubblewrap not found")?;
+                bubblewrap_command(bwrap, binary, node_dir)
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (binary, node_dir);
+        Err(format!("{:?} sandboxing is not available on this platform", backend))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn existing_system_dirs() -> impl Iterator<Item = &'static Path> {
+    SYSTEM_READ_ONLY_DIRS.iter().map(Path::new).filter(|p| p.is_dir())
+}
+
+/// Sets no-new-privs and lowers the resource limits. Runs in the child between fork and exec,
+/// so it only makes async-signal-safe calls.
+#[cfg(target_os = "linux")]
+fn harden_current_process(no_new_privs: bool) -> std::io::Result<()> {
+    let (on, unused): (libc::c_ulong, libc::c_ulong) = (1, 0);
+    if no_new_privs && unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, on, unused, unused, unused) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    lower_rlimit(libc::RLIMIT_DATA, MAX_DATA_BYTES)?;
+    lower_rlimit(libc::RLIMIT_NOFILE, MAX_OPEN_FILES)?;
+    lower_rlimit(libc::RLIMIT_CORE, 0)?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn landlock_command(binary: &Path, node_dir: &Path, abi: u32) -> Result<(Command, Sandbox), String> {
+    use landlock::{Ruleset, ACCESS_EXECUTE, ACCESS_READ_DIR, ACCESS_READ_FILE, ACCESS_WRITE_FILE};
+
+    let mut ruleset = Ruleset::new(abi)?;
+    for dir in existing_system_dirs() {
+        ruleset.allow(dir, ACCESS_READ_FILE | ACCESS_READ_DIR | ACCESS_EXECUTE)?;
+    }
+    for dir in ["/proc", "/sys"] {
+        ruleset.allow(Path::new(dir), ACCESS_READ_FILE | ACCESS_READ_DIR)?;
+    }
+    ruleset.allow(Path::new("/dev"), ACCESS_READ_FILE | ACCESS_WRITE_FILE | ACCESS_READ_DIR)?;
+    ruleset.allow(binary, ACCESS_READ_FILE | ACCESS_EXECUTE)?;
+    ruleset.allow(node_dir, ruleset.handled_access())?;
+
+    let sandbox = Sandbox { backend: SandboxBackend::Landlock, ruleset: Some(ruleset.into_fd()), info_pipe: None };
+    Ok((Command::new(binary), sandbox))
+}
+
+#[cfg(target_os = "linux")]
+fn bubblewrap_command(bwrap: &Path, binary: &Path, node_dir: &Path) -> Result<(Command, Sandbox), String> {
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(format!("Failed to create a pipe: {}", std::io::Error::last_os_error()));
+    }
+    // Safety: pipe2 just returned these descriptors and nothing else owns them
+    let (reader, writer) = unsafe { (std::fs::File::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    let info_fd = writer.as_raw_fd();
+
+    let mut cmd = Command::new(bwrap);
+    for dir in existing_system_dirs() {
+        cmd.arg("--ro-bind").arg(dir).arg(dir);
+    }
+    cmd.args(["--ro-bind-try", "/sys", "/sys", "--proc", "/proc", "--dev", "/dev", "--tmpfs", "/tmp"]);
+    cmd.arg("--ro-bind").arg(binary).arg(binary);
+    cmd.arg("--bind").arg(node_dir).arg(node_dir);
+    // No PID namespace: merod keeps a host PID the app can signal and find by its command line
+    cmd.args(["--unshare-ipc", "--unshare-uts", "--unshare-cgroup-try", "--new-session"]);
+    cmd.arg("--info-fd").arg(info_fd.to_string());
+    cmd.arg("--").arg(binary);
+    Ok((cmd, Sandbox { backend: SandboxBackend::Bubblewrap, ruleset: None, info_pipe: Some((reader, writer)) }))
+}
+
+/// Minimal Landlock bindings (filesystem rules only; see `man 7 landlock`).
+#[cfg(target_os = "linux")]
+mod landlock {
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    const CREATE_RULESET_VERSION: libc::c_uint = 1;
+    const RULE_PATH_BENEATH: libc::c_int = 1;
+
+    pub const ACCESS_EXECUTE: u64 = 1 << 0;
+    pub const ACCESS_WRITE_FILE: u64 = 1 << 1;
+    pub const ACCESS_READ_FILE: u64 = 1 << 2;
+    pub const ACCESS_READ_DIR: u64 = 1 << 3;
+    /// Everything ABI v1 handles: the rights above plus removing and creating entries
+    const ACCESS_V1: u64 = (1 << 13) - 1;
+    const ACCESS_REFER: u64 = 1 << 13;
+    const ACCESS_TRUNCATE: u64 = 1 << 14;
+    /// Rights that apply to a single file rather than a directory tree
+    const FILE_ACCESS: u64 = ACCESS_EXECUTE | ACCESS_WRITE_FILE | ACCESS_READ_FILE | ACCESS_TRUNCATE;
+
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PathBeneathAttr {
+        allowed_access: u64,
+        parent_fd: i32,
+    }
+
+    /// Highest Landlock ABI the kernel supports; None if it is missing or disabled.
+    pub fn abi_version() -> Option<u32> {
+        let version = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<RulesetAttr>(),
+                0usize,
+                CREATE_RULESET_VERSION,
+            )
+        };
+        u32::try_from(version).ok().filter(|v| *v > 0)
+    }
+
+    pub struct Ruleset {
+        fd: OwnedFd,
+        handled: u64,
+    }
+
+    impl Ruleset {
+        pub fn new(abi: u32) -> Result<Self, String> {
+            let mut handled = ACCESS_V1;
+            if abi >= 2 {
+                handled |= ACCESS_REFER;
+            }
+            if abi >= 3 {
+                handled |= ACCESS_TRUNCATE;
+            }
+            let attr = RulesetAttr { handled_access_fs: handled };
+            let fd = unsafe {
+                libc::syscall(
+                    libc::SYS_landlock_create_ruleset,
+                    &attr as *const RulesetAttr,
+                    std::mem::size_of::<RulesetAttr>(),
+                    0,
+                )
+            };
+            if fd < 0 {
+                return Err(format!("Failed to create a Landlock ruleset: {}", std::io::Error::last_os_error()));
+            }
+            // Safety: the syscall returned a new descriptor owned by nobody else
+            Ok(Self { fd: unsafe { OwnedFd::from_raw_fd(fd as RawFd) }, handled })
+        }
+
+        pub fn handled_access(&self) -> u64 {
+            self.handled
+        }
+
+        /// Allows `access` on `path` and, for a directory, everything beneath it.
+        pub fn allow(&mut self, path: &Path, access: u64) -> Result<(), String> {
+            let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+                .map_err(|_| format!("Invalid path {:?}", path))?;
+            let raw = unsafe { libc::open(c_path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+            if raw < 0 {
+                return Err(format!("Failed to open {:?}: {}", path, std::io::Error::last_os_error()));
+            }
+            // Safety: open just returned this descriptor
+            let parent = unsafe { OwnedFd::from_raw_fd(raw) };
+            let mut allowed = access & self.handled;
+            if !path.is_dir() {
+                allowed &= FILE_ACCESS;
+            }
+            let attr = PathBeneathAttr { allowed_access: allowed, parent_fd: parent.as_raw_fd() };
+            let result = unsafe {
+                libc::syscall(
+                    libc::SYS_landlock_add_rule,
+                    self.fd.as_raw_fd(),
+                    RULE_PATH_BENEATH,
+                    &attr as *const PathBeneathAttr,
+                    0,
+                )
+            };
+            if result != 0 {
+                return Err(format!("Failed to add a Landlock rule for {:?}: {}", path, std::io::Error::last_os_error()));
+            }
+            Ok(())
+        }
+
+        pub fn into_fd(self) -> OwnedFd {
+            self.fd
+        }
+    }
+
+    /// Enforces the ruleset on the calling process. Requires no-new-privs; async-signal-safe.
+    pub fn restrict_self(ruleset_fd: RawFd) -> std::io::Result<()> {
+        if unsafe { libc::syscall(libc::SYS_landlock_restrict_self, ruleset_fd, 0) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod bubblewrap {
+    use std::io::Read;
+
+    /// Reads `{"child-pid": N}` from bwrap's info fd.
+    pub fn read_child_pid(reader: &mut impl Read) -> Result<u32, String> {
+        let child_pid = regex::Regex::new(r#""child-pid"\s*:\s*(\d+)"#).unwrap();
+        let mut info = Vec::new();
+        let mut buf = [0u8; 256];
+        loop {
+            if let Some(pid) = child_pid
+                .captures(&String::from_utf8_lossy(&info))
+                .and_then(|c| c[1].parse().ok())
+            {
+                return Ok(pid);
+            }
+            match reader.read(&mut buf) {
+                Ok(0) => return Err("bubblewrap exited before starting merod".to_string()),
+                Ok(n) => info.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(format!("Failed to read bubblewrap info: {}", e)),
+            }
+        }
+    }
+}
+
+/// Whether sandboxed nodes can start on this machine, and with which backend.
+#[tauri::command]
+pub fn get_sandbox_support() -> SandboxSupport {
+    support().clone()
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_child_pid() {
+        let mut info: &[u8] = b"{\n    \"child-pid\": 4242\n}\n";
+        assert_eq!(bubblewrap::read_child_pid(&mut info).unwrap(), 4242);
+        let mut empty: &[u8] = b"";
+        assert!(bubblewrap::read_child_pid(&mut empty).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_landlock_confines_to_node_dir() {
+        let Some(abi) = landlock::abi_version() else { return };
+        let base = std::env::temp_dir().join(format!("calimero-sandbox-{}", std::process::id()));
+        let (node_dir, outside) = (base.join("node1"), base.join("secret"));
+        std::fs::create_dir_all(&node_dir).unwrap();
+        std::fs::write(node_dir.join("config.toml"), "ok").unwrap();
+        std::fs::write(&outside, "secret").unwrap();
+
+        let cat = |path: &Path| {
+            let (mut cmd, sandbox) = landlock_command(Path::new("/bin/cat"), &node_dir, abi).unwrap();
+            cmd.arg(path);
+            sandbox.confine(&mut cmd);
+            async move {
+                let output = cmd.output().await.unwrap();
+                drop(sandbox);
+                output.status.success()
+            }
+        };
+        assert!(cat(&node_dir.join("config.toml")).await);
+        assert!(!cat(&outside).await);
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
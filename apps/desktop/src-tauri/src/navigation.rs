@@ -0,0 +1,189 @@
+//! Navigation policy for app windows.
+//!
+//! An app window may only navigate within its own origin (and the configured node's origin,
+//! which hosts the auth pages). Links to other http(s)/mailto targets are opened in the system
+//! browser instead, and anything else is blocked. Every redirected or blocked navigation is
+//! reported to the main window.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::capabilities::MAIN_WINDOW_LABEL;
+
+/// Event emitted to the main window when an app window navigation leaves the window.
+pub const NAVIGATION_BLOCKED_EVENT: &str = "navigation-blocked";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NavigationDecision {
+    /// Stay in the app window.
+    Allow,
+    /// Open in the system browser and keep the app window where it is.
+    OpenExternal,
+    /// Drop the navigation entirely.
+    Block,
+}
+
+#[derive(Debug, Clone)]
+pub struct NavigationPolicy {
+    allowed_origins: Vec<url::Origin>,
+}
+
+impl NavigationPolicy {
+    /// Policy for an app loaded from `app_url`, optionally also allowing the node's origin.
+    pub fn for_app(app_url: &url::Url, node_url: Option<&str>) -> Self {
+        let mut allowed_origins = vec![app_url.origin()];
+        if let Some(node) = node_url.and_then(|u| url::Url::parse(u).ok()) {
+            let origin = node.origin();
+            if origin.is_tuple() && !allowed_origins.contains(&origin) {
+                allowed_origins.push(origin);
+            }
+        }
+        Self { allowed_origins }
+    }
+
+    pub fn decide(&self, target: &url::Url) -> NavigationDecision {
+        if target.as_str() == "about:blank" {
+            return NavigationDecision::Allow;
+        }
+        let origin = target.origin();
+        if origin.is_tuple() && self.allowed_origins.contains(&origin) {
+            return NavigationDecision::Allow;
+        }
+        match target.scheme() {
+            "http" | "https" | "mailto" => NavigationDecision::OpenExternal,
+            _ => NavigationDecision::Block,
+        }
+    }
+}
+
+/// Window label -> navigation policy, for windows created by `create_app_window`.
+#[derive(Default)]
+pub struct NavigationPolicies(Mutex<HashMap<String, NavigationPolicy>>);
+
+impl NavigationPolicies {
+    pub fn register(&self, label: &str, policy: NavigationPolicy) {
+        if let Ok(mut policies) = self.0.lock() {
+            policies.insert(label.to_string(), policy);
+        }
+    }
+
+    pub fn forget(&self, label: &str) {
+        if let Ok(mut policies) = self.0.lock() {
+            policies.remove(label);
+        }
+    }
+
+    pub fn get(&self, label: &str) -> Option<NavigationPolicy> {
+        self.0.lock().ok().and_then(|policies| policies.get(label).cloned())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct NavigationReport {
+    window: String,
+    url: String,
+    decision: NavigationDecision,
+}
+
+/// Applies `decision` for a navigation that should not stay in the window: opens it in the
+/// system browser when allowed, and reports it to the main window either way.
+pub fn redirect_navigation(
+    app_handle: &AppHandle,
+    window_label: &str,
+    target: &url::Url,
+    decision: NavigationDecision,
+) -> Result<(), String> {
+    let result = match decision {
+        NavigationDecision::Allow => return Ok(()),
+        NavigationDecision::OpenExternal => {
+            info!("[Navigation] Opening {} from window '{}' in the system browser", target, window_label);
+            tauri::api::shell::open(&app_handle.shell_scope(), target.as_str(), None)
+                .map_err(|e| format!("Failed to open {} in the system browser: {}", target, e))
+        }
+        NavigationDecision::Block => {
+            warn!("[Navigation] Blocked navigation to {} from window '{}'", target, window_label);
+            Err(format!("Navigation to '{}' is not allowed from an app window", target))
+        }
+    };
+
+    let _ = app_handle.emit_to(
+        MAIN_WINDOW_LABEL,
+        NAVIGATION_BLOCKED_EVENT,
+        NavigationReport {
+            window: window_label.to_string(),
+            url: target.to_string(),
+            decision,
+        },
+    );
+
+    result
+}
+
+/// Called by the injected navigation guard for `target=_blank` links and `window.open`.
+/// Same-origin targets are loaded in the calling window; everything else goes through
+/// the window's navigation policy.
+#[tauri::command]
+pub fn open_external_link(
+    url: String,
+    window: tauri::Window,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let target = url::Url::parse(&url)
+        .map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    let policy = app_handle
+        .state::<NavigationPolicies>()
+        .get(window.label())
+        .ok_or_else(|| format!("No navigation policy for window '{}'", window.label()))?;
+
+    match policy.decide(&target) {
+        NavigationDecision::Allow => {
+            let js = format!(
+                "window.location.href = {};",
+                serde_json::to_string(target.as_str()).map_err(|e| e.to_string())?
+            );
+            window.eval(&js).map_err(|e| format!("Failed to navigate window: {}", e))
+        }
+        decision => redirect_navigation(&app_handle, window.label(), &target, decision),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> NavigationPolicy {
+        let app = url::Url::parse("https://kv-store.apps.calimero.network/index.html").unwrap();
+        NavigationPolicy::for_app(&app, Some("http://localhost:2528"))
+    }
+
+    fn decide(target: &str) -> NavigationDecision {
+        policy().decide(&url::Url::parse(target).unwrap())
+    }
+
+    #[test]
+    fn test_same_origin_stays_in_window() {
+        assert_eq!(decide("https://kv-store.apps.calimero.network/settings"), NavigationDecision::Allow);
+        assert_eq!(decide("http://localhost:2528/auth/login"), NavigationDecision::Allow);
+        assert_eq!(decide("about:blank"), NavigationDecision::Allow);
+    }
+
+    #[test]
+    fn test_other_origins_open_externally() {
+        assert_eq!(decide("https://github.com/calimero-network"), NavigationDecision::OpenExternal);
+        assert_eq!(decide("http://kv-store.apps.calimero.network/"), NavigationDecision::OpenExternal);
+        assert_eq!(decide("http://localhost:2529/"), NavigationDecision::OpenExternal);
+        assert_eq!(decide("mailto:team@calimero.network"), NavigationDecision::OpenExternal);
+    }
+
+    #[test]
+    fn test_other_schemes_blocked() {
+        assert_eq!(decide("file:///etc/passwd"), NavigationDecision::Block);
+        assert_eq!(decide("javascript:alert(1)"), NavigationDecision::Block);
+        assert_eq!(decide("data:text/html,hi"), NavigationDecision::Block);
+    }
+}
@@ -0,0 +1,53 @@
+(function() {
+    if (window.__TAURI_NAVIGATION_GUARD_INJECTED__) return;
+    window.__TAURI_NAVIGATION_GUARD_INJECTED__ = true;
+
+    // Route new-window requests (target=_blank links, window.open) through the Rust
+    // navigation policy instead of letting the webview open them in-process.
+    // Same-origin targets stay in this window; other origins open in the system browser.
+    function openViaPolicy(rawUrl) {
+        var target;
+        try {
+            target = new URL(rawUrl, window.location.href);
+        } catch (e) {
+            console.warn('[Tauri Navigation] Ignoring invalid URL:', rawUrl);
+            return;
+        }
+
+        if (target.origin === window.location.origin) {
+            window.location.href = target.href;
+            return;
+        }
+
+        var invokeFn = typeof window.__TAURI_INVOKE__ === 'function' ? window.__TAURI_INVOKE__ : null;
+        if (!invokeFn) {
+            console.error('[Tauri Navigation] Tauri invoke API not available, dropping:', target.href);
+            return;
+        }
+        invokeFn('open_external_link', { url: target.href }).catch(function(error) {
+            console.warn('[Tauri Navigation] Navigation refused:', error);
+        });
+    }
+
+    window.open = function(url) {
+        if (url) {
+            openViaPolicy(String(url));
+        }
+        return null;
+    };
+
+    document.addEventListener('click', function(event) {
+        var el = event.target;
+        var anchor = el && typeof el.closest === 'function' ? el.closest('a[href]') : null;
+        if (!anchor) return;
+
+        var target = (anchor.getAttribute('target') || '').toLowerCase();
+        if (target === '' || target === '_self' || target === '_top' || target === '_parent') {
+            // Regular in-window navigation is checked by the window's navigation handler
+            return;
+        }
+
+        event.preventDefault();
+        openViaPolicy(anchor.href);
+    }, true);
+})();
@@ -0,0 +1,445 @@
+//! Backup and restore of a single node as a `.tar.gz` archive.
+//!
+//! Layout: `manifest.json` first, then the node directory under `node/`. The manifest
+//! records where the node came from, the merod version it ran and a SHA-256 for every file,
+//! so an import can refuse a truncated or modified archive before anything lands in the
+//! Calimero home.
+
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::node_ops;
+
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_NAME: &str = "manifest.json";
+const NODE_PREFIX: &str = "node";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveFile {
+    /// Path relative to the node directory, `/`-separated.
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeArchiveManifest {
+    pub format_version: u32,
+    pub node_name: String,
+    /// Node directory at export time, used to rewrite config paths on import.
+    pub source_dir: String,
+    pub exported_at: String,
+    pub app_version: String,
+    pub merod_version: Option<String>,
+    pub includes_logs: bool,
+    pub files: Vec<ArchiveFile>,
+}
+
+/// Files under `dir` as `/`-separated relative paths, skipping top-level `skip` directories.
+pub fn collect_files(dir: &Path, skip: &[&str]) -> Result<Vec<(String, PathBuf)>, String> {
+    fn walk(root: &Path, dir: &Path, skip: &[&str], out: &mut Vec<(String, PathBuf)>) -> Result<(), String> {
+        let mut entries: Vec<_> = std::fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read {:?}: {}", dir, e))?
+            .filter_map(|e| e.ok())
+            .collect();
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let path = entry.path();
+            if dir == root && skip.iter().any(|s| entry.file_name() == *s) {
+                continue;
+            }
+            let file_type = entry.file_type().map_err(|e| format!("Failed to stat {:?}: {}", path, e))?;
+            if file_type.is_dir() {
+                walk(root, &path, skip, out)?;
+            } else if file_type.is_file() {
+                let relative = path
+                    .strip_prefix(root)
+                    .map_err(|_| format!("{:?} is outside {:?}", path, root))?
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                out.push((relative, path));
+            } else {
+                warn!("[Node Archive] Skipping {:?}: not a regular file or directory", path);
+            }
+        }
+        Ok(())
+    }
+    let mut files = Vec::new();
+    walk(dir, dir, skip, &mut files)?;
+    Ok(files)
+}
+
+/// Writes `node_dir` to `dest` as a node archive. Returns the manifest that was written.
+pub fn write_archive(
+    node_dir: &Path,
+    node_name: &str,
+    dest: &Path,
+    include_logs: bool,
+    merod_version: Option<String>,
+    app_version: &str,
+) -> Result<NodeArchiveManifest, String> {
+    let skip: &[&str] = if include_logs { &[] } else { &["logs"] };
+    let files = collect_files(node_dir, skip)?;
+
+    let mut manifest = NodeArchiveManifest {
+        format_version: ARCHIVE_FORMAT_VERSION,
+        node_name: node_name.to_string(),
+        source_dir: node_dir.to_string_lossy().into_owned(),
+        exported_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        app_version: app_version.to_string(),
+        merod_version,
+        includes_logs: include_logs,
+        files: Vec::with_capacity(files.len()),
+    };
+    for (relative, path) in &files {
+        manifest.files.push(ArchiveFile {
+            path: relative.clone(),
+            size: std::fs::metadata(path).map_err(|e| format!("Failed to stat {:?}: {}", path, e))?.len(),
+            sha256: crate::merod_manager::sha256_file(path)?,
+        });
+    }
+
+    let out = std::fs::File::create(dest).map_err(|e| format!("Failed to create {:?}: {}", dest, e))?;
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(out, flate2::Compression::default()));
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize archive manifest: {}", e))?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+
+    for (relative, path) in &files {
+        builder
+            .append_path_with_name(path, format!("{}/{}", NODE_PREFIX, relative))
+            .map_err(|e| format!("Failed to add {:?} to archive: {}", path, e))?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| format!("Failed to finish archive {:?}: {}", dest, e))?;
+
+    Ok(manifest)
+}
+
+/// Maps an archive entry path to a path relative to the node directory, rejecting anything
+/// that could escape it.
+fn node_relative_path(entry_path: &Path) -> Result<PathBuf, String> {
+    let mut components = entry_path.components();
+    if components.next() != Some(Component::Normal(NODE_PREFIX.as_ref())) {
+        return Err(format!("Unexpected archive entry {:?}", entry_path));
+    }
+    let relative: PathBuf = components.collect();
+    if relative.as_os_str().is_empty()
+        || relative.components().any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return Err(format!("Unsafe archive entry {:?}", entry_path));
+    }
+    Ok(relative)
+}
+
+/// Extracts a node archive into `dest_dir` (which must not exist), verifying every file
+/// against the manifest. On error `dest_dir` is removed.
+pub fn extract_archive(archive: &Path, dest_dir: &Path) -> Result<NodeArchiveManifest, String> {
+    let result = extract_archive_inner(archive, dest_dir);
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(dest_dir);
+    }
+    result
+}
+
+fn extract_archive_inner(archive: &Path, dest_dir: &Path) -> Result<NodeArchiveManifest, String> {
+    let file = std::fs::File::open(archive).map_err(|e| format!("Failed to open archive {:?}: {}", archive, e))?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let mut entries = tar.entries().map_err(|e| format!("Failed to read archive {:?}: {}", archive, e))?;
+
+    let mut first = entries
+        .next()
+        .ok_or_else(|| format!("{:?} is empty", archive))?
+        .map_err(|e| format!("Corrupt archive {:?}: {}", archive, e))?;
+    if first.path().map(|p| p.as_ref() != Path::new(MANIFEST_NAME)).unwrap_or(true) {
+        return Err(format!("{:?} is not a node archive (no manifest)", archive));
+    }
+    let mut manifest_json = String::new();
+    first
+        .read_to_string(&mut manifest_json)
+        .map_err(|e| format!("Failed to read archive manifest: {}", e))?;
+    let manifest: NodeArchiveManifest = serde_json::from_str(&manifest_json)
+        .map_err(|e| format!("Invalid archive manifest: {}", e))?;
+    if manifest.format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(format!(
+            "This archive was made by a newer app (format {}). Update the app to import it.",
+            manifest.format_version
+        ));
+    }
+
+    let mut expected: std::collections::HashMap<&str, &ArchiveFile> =
+        manifest.files.iter().map(|f| (f.path.as_str(), f)).collect();
+    std::fs::create_dir_all(dest_dir).map_err(|e| format!("Failed to create {:?}: {}", dest_dir, e))?;
+
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Corrupt archive {:?}: {}", archive, e))?;
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            continue;
+        }
+        let entry_path = entry.path().map_err(|e| format!("Invalid entry path: {}", e))?.into_owned();
+        if !entry_type.is_file() {
+            return Err(format!("Unsupported archive entry {:?} (links are not allowed)", entry_path));
+        }
+        let relative = node_relative_path(&entry_path)?;
+        let key = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        let expected_file = expected
+            .remove(key.as_str())
+            .ok_or_else(|| format!("Archive contains {} which is not in its manifest", key))?;
+
+        let target = dest_dir.join(&relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let mut out = std::fs::File::create(&target).map_err(|e| format!("Failed to create {:?}: {}", target, e))?;
+        let mut hasher = Sha256::new();
+        let mut buf = [0u8; 64 * 1024];
+        let mut size = 0u64;
+        loop {
+            let n = entry.read(&mut buf).map_err(|e| format!("Failed to read {} from archive: {}", key, e))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            std::io::Write::write_all(&mut out, &buf[..n])
+                .map_err(|e| format!("Failed to write {:?}: {}", target, e))?;
+            size += n as u64;
+        }
+        let sha256 = hex::encode(hasher.finalize());
+        if size != expected_file.size || sha256 != expected_file.sha256 {
+            return Err(format!("{} in the archive does not match its checksum; the archive is corrupted", key));
+        }
+    }
+
+    if let Some(missing) = expected.keys().next() {
+        return Err(format!("Archive is missing {} listed in its manifest", missing));
+    }
+    Ok(manifest)
+}
+
+/// Archives `<home>/<node>` (config, data, auth store and optionally logs) to `dest`. When
+/// `dest` is a directory the archive is named `<node>-<timestamp>.tar.gz`. The node must be
+/// stopped.
+#[tauri::command]
+pub async fn export_node(
+    node_name: String,
+    dest: String,
+    home_dir: Option<String>,
+    include_logs: Option<bool>,
+    app_handle: tauri::AppHandle,
+    info_cache: tauri::State<'_, crate::merod_info::MerodInfoCache>,
+) -> Result<serde_json::Value, String> {
+    let home_dir_path = crate::resolve_calimero_home(home_dir)?;
+    let node_dir = node_ops::existing_node_dir(&home_dir_path, &node_name)?;
+    node_ops::ensure_not_running(&home_dir_path, &node_name)?;
+
+    let dest = crate::expand_tilde(dest);
+    let dest = if dest.is_dir() {
+        dest.join(format!("{}-{}.tar.gz", node_name, chrono::Utc::now().format("%Y%m%dT%H%M%SZ")))
+    } else {
+        dest
+    };
+    if dest.exists() {
+        return Err(format!("{:?} already exists", dest));
+    }
+
+    let merod_version = match crate::get_node_merod_binary(&app_handle, &home_dir_path, &node_name) {
+        Ok(binary) => info_cache.version_of(&binary).await,
+        Err(e) => {
+            warn!("[Node Archive] Could not resolve merod for '{}': {}", node_name, e);
+            None
+        }
+    };
+    let app_version = app_handle.package_info().version.to_string();
+
+    let (node_dir_clone, node_name_clone, dest_clone) = (node_dir.clone(), node_name.clone(), dest.clone());
+    let include_logs = include_logs.unwrap_or(false);
+    let manifest = tokio::task::spawn_blocking(move || {
+        write_archive(&node_dir_clone, &node_name_clone, &dest_clone, include_logs, merod_version, &app_version)
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {}", e))?;
+    let manifest = match manifest {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = std::fs::remove_file(&dest);
+            return Err(e);
+        }
+    };
+
+    let size = std::fs::metadata(&dest).map(|m| m.len()).unwrap_or(0);
+    info!(
+        "[Node Archive] Exported node '{}' to {:?} ({} files, {} bytes)",
+        node_name, dest, manifest.files.len(), size
+    );
+    Ok(serde_json::json!({
+        "path": dest,
+        "size": size,
+        "files": manifest.files.len(),
+        "merod_version": manifest.merod_version,
+        "exported_at": manifest.exported_at,
+    }))
+}
+
+/// Restores a node archive into `<home>/<node_name>` (default: the exported node's name).
+/// The archive is fully verified before the node appears in the home.
+#[tauri::command]
+pub async fn import_node(
+    archive: String,
+    node_name: Option<String>,
+    home_dir: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    let home_dir_path = crate::resolve_calimero_home(home_dir)?;
+    let archive_path = crate::expand_tilde(archive);
+    if !archive_path.is_file() {
+        return Err(format!("Archive {:?} not found", archive_path));
+    }
+    crate::data_dir::create_calimero_home(&home_dir_path)?;
+
+    // Extract next to the final location so the last step is a rename
+    let staging = home_dir_path.join(format!(".import-{}-{}", std::process::id(), chrono::Utc::now().timestamp_millis()));
+    let staging_clone = staging.clone();
+    let archive_clone = archive_path.clone();
+    let manifest = tokio::task::spawn_blocking(move || extract_archive(&archive_clone, &staging_clone))
+        .await
+        .map_err(|e| format!("Import task failed: {}", e))??;
+
+    let node_name = node_name.unwrap_or_else(|| manifest.node_name.clone());
+    let node_dir = match node_ops::new_node_dir(&home_dir_path, &node_name) {
+        Ok(dir) => dir,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+    if let Err(e) = std::fs::rename(&staging, &node_dir) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(format!("Failed to move imported node into place: {}", e));
+    }
+    if let Err(e) = node_ops::update_config_paths(&node_dir, Path::new(&manifest.source_dir)) {
+        warn!("[Node Archive] Imported '{}' but could not update its config: {}", node_name, e);
+    }
+
+    // Keep running the merod version the node was exported with, if it's installed here
+    let mut warnings = Vec::new();
+    if let Some(version) = &manifest.merod_version {
+        let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+        if crate::merod_manager::find_installed(&app_data_dir, version).is_some() {
+            crate::merod_manager::set_pin(&app_data_dir, &home_dir_path, &node_name, Some(version))?;
+        } else {
+            warnings.push(format!(
+                "The node was exported with merod {}, which is not installed here; it will use the bundled merod",
+                version
+            ));
+        }
+    }
+
+    info!(
+        "[Node Archive] Imported node '{}' from {:?} ({} files, exported {})",
+        node_name, archive_path, manifest.files.len(), manifest.exported_at
+    );
+    Ok(serde_json::json!({
+        "node_name": node_name,
+        "files": manifest.files.len(),
+        "merod_version": manifest.merod_version,
+        "exported_at": manifest.exported_at,
+        "warnings": warnings,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("calimero-archive-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn make_node(root: &Path) -> PathBuf {
+        let node = root.join("node1");
+        std::fs::create_dir_all(node.join("data")).unwrap();
+        std::fs::create_dir_all(node.join("logs")).unwrap();
+        std::fs::write(node.join("config.toml"), "[identity]\npeer_id = \"abc\"\n").unwrap();
+        std::fs::write(node.join("data/CURRENT"), "MANIFEST-000001").unwrap();
+        std::fs::write(node.join("logs/merod.log"), "started").unwrap();
+        node
+    }
+
+    #[test]
+    fn test_export_then_import_roundtrip() {
+        let root = temp_root("roundtrip");
+        let node = make_node(&root);
+        let archive = root.join("node1.tar.gz");
+
+        let manifest = write_archive(&node, "node1", &archive, false, Some("0.10.0".to_string()), "0.1.0").unwrap();
+        assert_eq!(manifest.files.len(), 2);
+
+        let restored = root.join("restored");
+        let read = extract_archive(&archive, &restored).unwrap();
+        assert_eq!(read.node_name, "node1");
+        assert_eq!(read.merod_version.as_deref(), Some("0.10.0"));
+        assert_eq!(std::fs::read_to_string(restored.join("data/CURRENT")).unwrap(), "MANIFEST-000001");
+        assert!(!restored.join("logs").exists());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_import_rejects_modified_archive() {
+        let root = temp_root("tampered");
+        let node = make_node(&root);
+        let archive = root.join("node1.tar.gz");
+        let mut manifest = write_archive(&node, "node1", &archive, true, None, "0.1.0").unwrap();
+
+        // Rewrite the archive with a manifest whose checksum no longer matches
+        manifest.files[0].sha256 = "0".repeat(64);
+        let out = std::fs::File::create(&archive).unwrap();
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(out, flate2::Compression::default()));
+        let json = serde_json::to_vec(&manifest).unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_size(json.len() as u64);
+        header.set_cksum();
+        builder.append_data(&mut header, MANIFEST_NAME, json.as_slice()).unwrap();
+        for file in &manifest.files {
+            builder.append_path_with_name(node.join(&file.path), format!("node/{}", file.path)).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let restored = root.join("restored");
+        let err = extract_archive(&archive, &restored).unwrap_err();
+        assert!(err.contains("checksum"), "{}", err);
+        assert!(!restored.exists());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_node_relative_path_rejects_escapes() {
+        assert!(node_relative_path(Path::new("node/data/CURRENT")).is_ok());
+        assert!(node_relative_path(Path::new("node/../etc/passwd")).is_err());
+        assert!(node_relative_path(Path::new("other/file")).is_err());
+        assert!(node_relative_path(Path::new("node")).is_err());
+    }
+}
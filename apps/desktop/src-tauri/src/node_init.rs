@@ -0,0 +1,318 @@
+//! Options for `init_merod_node`, validated before merod is spawned so mistakes show up as
+//! clear messages instead of merod usage errors.
+//!
+//! Every option maps to a `merod init` flag. `extra_args` is an escape hatch for flags that
+//! aren't modelled yet; it may not repeat flags the app sets itself.
+
+use std::net::IpAddr;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Emitted with `InitProgress` for each line merod prints while initializing a node.
+pub const INIT_PROGRESS_EVENT: &str = "merod-init-progress";
+
+pub const DEFAULT_INIT_TIMEOUT_SECS: u64 = 30;
+const MAX_INIT_TIMEOUT_SECS: u64 = 600;
+
+/// Flags the app always passes (or derives from typed options); not allowed in `extra_args`.
+const RESERVED_INIT_FLAGS: &[&str] = &[
+    "--home",
+    "--node",
+    "--node-name",
+    "--server-port",
+    "--server-host",
+    "--swarm-port",
+    "--swarm-host",
+    "--boot-nodes",
+    "--boot-network",
+    "--relay-registrations-limit",
+    "--auth-mode",
+    "--protocol",
+    "--mdns",
+    "--no-mdns",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthMode {
+    /// merod serves its own auth endpoints (what the desktop app expects).
+    #[default]
+    Embedded,
+    /// Auth is handled by an external proxy in front of the node.
+    Proxy,
+}
+
+impl AuthMode {
+    fn as_arg(self) -> &'static str {
+        match self {
+            AuthMode::Embedded => "embedded",
+            AuthMode::Proxy => "proxy",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BootNetwork {
+    CalimeroDev,
+    Ipfs,
+}
+
+impl BootNetwork {
+    fn as_arg(self) -> &'static str {
+        match self {
+            BootNetwork::CalimeroDev => "calimero-dev",
+            BootNetwork::Ipfs => "ipfs",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeInitOptions {
+    pub server_port: Option<u16>,
+    pub server_host: Option<String>,
+    pub swarm_port: Option<u16>,
+    pub swarm_host: Option<String>,
+    /// Multiaddrs of peers to bootstrap from, e.g. `/ip4/1.2.3.4/tcp/2428/p2p/12D3Koo...`.
+    pub boot_nodes: Vec<String>,
+    /// Well-known bootstrap network, used in addition to `boot_nodes`.
+    pub boot_network: Option<BootNetwork>,
+    /// Local peer discovery via mDNS (merod's default when unset).
+    pub mdns: Option<bool>,
+    /// How many peers may register with this node when it acts as a relay.
+    pub relay_registrations_limit: Option<u32>,
+    pub auth_mode: AuthMode,
+    /// Blockchain protocol the node's contexts use, e.g. `near`.
+    pub protocol: Option<String>,
+    pub extra_args: Vec<String>,
+    /// How long `merod init` may take before it is killed.
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitProgress {
+    pub node_name: String,
+    /// `stdout` or `stderr`.
+    pub stream: &'static str,
+    pub line: String,
+}
+
+pub fn validate_node_name(node_name: &str) -> Result<(), String> {
+    if node_name.is_empty() {
+        return Err("Node name is required".to_string());
+    }
+    if node_name.len() > 64 {
+        return Err("Node name must be at most 64 characters".to_string());
+    }
+    if node_name.starts_with('.')
+        || !node_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    {
+        return Err(format!(
+            "Invalid node name '{}'. Use letters, digits, '-', '_' or '.', not starting with '.'",
+            node_name
+        ));
+    }
+    Ok(())
+}
+
+fn validate_host(name: &str, host: &str) -> Result<(), String> {
+    host.parse::<IpAddr>()
+        .map(|_| ())
+        .map_err(|_| format!("Invalid {} '{}': expected an IP address such as 127.0.0.1", name, host))
+}
+
+fn validate_boot_node(addr: &str) -> Result<(), String> {
+    let components: Vec<&str> = addr.split('/').collect();
+    let valid = addr.starts_with('/')
+        && components.len() >= 5
+        && matches!(components[1], "ip4" | "ip6" | "dns" | "dns4" | "dns6")
+        && components.windows(2).any(|w| w[0] == "p2p" && !w[1].is_empty());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid boot node '{}': expected a multiaddr like /ip4/1.2.3.4/tcp/2428/p2p/<peer id>",
+            addr
+        ))
+    }
+}
+
+/// Whether `port` can currently be bound on localhost.
+pub fn port_is_free(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+impl NodeInitOptions {
+    pub fn timeout_secs(&self) -> u64 {
+        self.timeout_secs.unwrap_or(DEFAULT_INIT_TIMEOUT_SECS)
+    }
+
+    /// Checks every option; `check_ports` also verifies the ports aren't already taken.
+    pub fn validate(&self, check_ports: bool) -> Result<(), String> {
+        for (name, port) in [("server port", self.server_port), ("swarm port", self.swarm_port)] {
+            if let Some(port) = port {
+                if port == 0 {
+                    return Err(format!("Invalid {}: 0", name));
+                }
+                if check_ports && !port_is_free(port) {
+                    return Err(format!("The {} {} is already in use. Choose another port.", name, port));
+                }
+            }
+        }
+        if let (Some(server), Some(swarm)) = (self.server_port, self.swarm_port) {
+            if server == swarm {
+                return Err(format!("Server and swarm ports must differ (both are {})", server));
+            }
+        }
+        if let Some(host) = &self.server_host {
+            validate_host("server host", host)?;
+        }
+        if let Some(host) = &self.swarm_host {
+            validate_host("swarm host", host)?;
+        }
+        for addr in &self.boot_nodes {
+            validate_boot_node(addr)?;
+        }
+        if let Some(protocol) = &self.protocol {
+            if protocol.is_empty()
+                || !protocol.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            {
+                return Err(format!("Invalid protocol '{}': use lowercase letters, digits or '-'", protocol));
+            }
+        }
+        match self.timeout_secs {
+            Some(0) => return Err("Init timeout must be at least 1 second".to_string()),
+            Some(secs) if secs > MAX_INIT_TIMEOUT_SECS => {
+                return Err(format!("Init timeout must be at most {} seconds", MAX_INIT_TIMEOUT_SECS))
+            }
+            _ => {}
+        }
+        for arg in &self.extra_args {
+            let flag = arg.split('=').next().unwrap_or(arg);
+            if RESERVED_INIT_FLAGS.contains(&flag) {
+                return Err(format!(
+                    "'{}' cannot be passed in extra args; it is set by the app or has a dedicated option",
+                    flag
+                ));
+            }
+            if arg.contains('\0') {
+                return Err("Extra args must not contain NUL characters".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Arguments for `merod --home <home> --node <name> init ...`.
+    pub fn to_args(&self, home_dir: &Path, node_name: &str) -> Vec<String> {
+        let mut args = vec![
+            "--home".to_string(),
+            home_dir.to_string_lossy().into_owned(),
+            "--node".to_string(),
+            node_name.to_string(),
+            "init".to_string(),
+            "--auth-mode".to_string(),
+            self.auth_mode.as_arg().to_string(),
+        ];
+        let mut push = |flag: &str, value: String| {
+            args.push(flag.to_string());
+            args.push(value);
+        };
+        if let Some(port) = self.server_port {
+            push("--server-port", port.to_string());
+        }
+        if let Some(host) = &self.server_host {
+            push("--server-host", host.clone());
+        }
+        if let Some(port) = self.swarm_port {
+            push("--swarm-port", port.to_string());
+        }
+        if let Some(host) = &self.swarm_host {
+            push("--swarm-host", host.clone());
+        }
+        for addr in &self.boot_nodes {
+            push("--boot-nodes", addr.clone());
+        }
+        if let Some(network) = self.boot_network {
+            push("--boot-network", network.as_arg().to_string());
+        }
+        if let Some(limit) = self.relay_registrations_limit {
+            push("--relay-registrations-limit", limit.to_string());
+        }
+        if let Some(protocol) = &self.protocol {
+            push("--protocol", protocol.clone());
+        }
+        match self.mdns {
+            Some(true) => args.push("--mdns".to_string()),
+            Some(false) => args.push("--no-mdns".to_string()),
+            None => {}
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_options_match_previous_init() {
+        let args = NodeInitOptions::default().to_args(Path::new("/home/me/.calimero"), "node1");
+        assert_eq!(
+            args,
+            ["--home", "/home/me/.calimero", "--node", "node1", "init", "--auth-mode", "embedded"]
+        );
+    }
+
+    #[test]
+    fn test_typed_options_become_flags() {
+        let options = NodeInitOptions {
+            server_port: Some(2530),
+            swarm_port: Some(2430),
+            boot_nodes: vec!["/ip4/10.0.0.1/tcp/2428/p2p/12D3KooWabc".to_string()],
+            boot_network: Some(BootNetwork::CalimeroDev),
+            mdns: Some(false),
+            auth_mode: AuthMode::Proxy,
+            extra_args: vec!["--force".to_string()],
+            ..Default::default()
+        };
+        assert!(options.validate(false).is_ok());
+        let args = options.to_args(Path::new("/h"), "n").join(" ");
+        assert!(args.contains("--auth-mode proxy"));
+        assert!(args.contains("--server-port 2530"));
+        assert!(args.contains("--swarm-port 2430"));
+        assert!(args.contains("--boot-nodes /ip4/10.0.0.1/tcp/2428/p2p/12D3KooWabc"));
+        assert!(args.contains("--boot-network calimero-dev"));
+        assert!(args.ends_with("--no-mdns --force"));
+    }
+
+    #[test]
+    fn test_invalid_options_are_rejected() {
+        let invalid = [
+            NodeInitOptions { server_port: Some(0), ..Default::default() },
+            NodeInitOptions { server_port: Some(2528), swarm_port: Some(2528), ..Default::default() },
+            NodeInitOptions { server_host: Some("localhost:80".to_string()), ..Default::default() },
+            NodeInitOptions { boot_nodes: vec!["1.2.3.4:2428".to_string()], ..Default::default() },
+            NodeInitOptions { protocol: Some("NEAR; rm".to_string()), ..Default::default() },
+            NodeInitOptions { timeout_secs: Some(0), ..Default::default() },
+            NodeInitOptions { extra_args: vec!["--home=/tmp".to_string()], ..Default::default() },
+            NodeInitOptions { extra_args: vec!["--server-port".to_string(), "1".to_string()], ..Default::default() },
+        ];
+        for options in invalid {
+            assert!(options.validate(false).is_err(), "{:?} should be invalid", options);
+        }
+    }
+
+    #[test]
+    fn test_node_name_validation() {
+        assert!(validate_node_name("node1").is_ok());
+        assert!(validate_node_name("my_node-2.dev").is_ok());
+        assert!(validate_node_name("").is_err());
+        assert!(validate_node_name("../etc").is_err());
+        assert!(validate_node_name(".hidden").is_err());
+        assert!(validate_node_name("a/b").is_err());
+    }
+}
@@ -0,0 +1,389 @@
+//! Optional per-node resource limits: memory, CPU share and open files.
+//!
+//! On Linux, memory and CPU are enforced with a cgroup v2 created next to the app's own
+//! cgroup, which works when the user's systemd instance delegates the memory and cpu
+//! controllers (the default on current distributions). Otherwise memory falls back to
+//! `RLIMIT_DATA` and the CPU share is not enforced. Open files always use `RLIMIT_NOFILE`.
+//! The applied limits are reported in `get_merod_status`, and a breach shows up as its own
+//! failure kind in the crash diagnosis.
+
+use std::path::PathBuf;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::crash_diagnosis::{CrashDiagnosis, FailureKind};
+
+const MIN_MEMORY_MB: u64 = 128;
+const MIN_OPEN_FILES: u64 = 256;
+/// cgroup `cpu.max` period; the quota is a share of it.
+#[cfg(target_os = "linux")]
+const CPU_PERIOD_US: u64 = 100_000;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeResourceLimits {
+    pub max_memory_mb: Option<u64>,
+    /// Percent of one CPU core; 200 allows two full cores.
+    pub cpu_percent: Option<u32>,
+    pub max_open_files: Option<u64>,
+}
+
+impl NodeResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_memory_mb.is_some_and(|mb| mb < MIN_MEMORY_MB) {
+            return Err(format!("The memory limit must be at least {} MB", MIN_MEMORY_MB));
+        }
+        if let Some(percent) = self.cpu_percent {
+            let cores = std::thread::available_parallelism().map(|n| n.get() as u32).unwrap_or(1);
+            if percent == 0 || percent > cores * 100 {
+                return Err(format!("The CPU limit must be between 1% and {}% ({} cores)", cores * 100, cores));
+            }
+        }
+        if self.max_open_files.is_some_and(|n| n < MIN_OPEN_FILES) {
+            return Err(format!("The open files limit must be at least {}", MIN_OPEN_FILES));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitMechanism {
+    Cgroup,
+    Rlimit,
+    /// Requested, but this system offers no way to enforce it.
+    NotEnforced,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppliedLimit {
+    pub value: u64,
+    pub enforced_by: LimitMechanism,
+}
+
+/// The limits a running node was started with and how each is enforced.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AppliedLimits {
+    pub memory_mb: Option<AppliedLimit>,
+    pub cpu_percent: Option<AppliedLimit>,
+    pub open_files: Option<AppliedLimit>,
+    /// The node's cgroup directory, when cgroups are used.
+    pub cgroup: Option<PathBuf>,
+    /// Why a limit could not be enforced as requested.
+    pub note: Option<String>,
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type Resource = libc::c_int;
+
+/// Lowers `resource` to at most `max`; never raises a limit that is already lower. Runs in the
+/// child between fork and exec, so it only makes async-signal-safe calls.
+#[cfg(unix)]
+pub fn lower_rlimit(resource: Resource, max: u64) -> std::io::Result<()> {
+    let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    if unsafe { libc::getrlimit(resource, &mut limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    limit.rlim_max = limit.rlim_max.min(max as libc::rlim_t);
+    limit.rlim_cur = limit.rlim_cur.min(limit.rlim_max);
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Sets up `limits` for the merod process `cmd` will spawn.
+pub fn apply(cmd: &mut Command, node_name: &str, limits: &NodeResourceLimits) -> Result<AppliedLimits, String> {
+    let mut applied = AppliedLimits::default();
+    if limits.is_empty() {
+        return Ok(applied);
+    }
+    let mut notes = Vec::new();
+
+    #[cfg(target_os = "linux")]
+    let cgroup = if limits.max_memory_mb.is_some() || limits.cpu_percent.is_some() {
+        match cgroup::create(node_name, limits) {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                notes.push(format!("cgroups v2 unavailable ({})", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+    #[cfg(not(target_os = "linux"))]
+    let cgroup: Option<PathBuf> = None;
+
+    let via_cgroup = |value: u64| AppliedLimit { value, enforced_by: LimitMechanism::Cgroup };
+    let via_rlimit = |value: u64| AppliedLimit {
+        value,
+        enforced_by: if cfg!(unix) { LimitMechanism::Rlimit } else { LimitMechanism::NotEnforced },
+    };
+    applied.memory_mb = limits.max_memory_mb.map(|mb| if cgroup.is_some() { via_cgroup(mb) } else { via_rlimit(mb) });
+    applied.cpu_percent = limits.cpu_percent.map(|percent| match cgroup {
+        Some(_) => via_cgroup(percent as u64),
+        None => {
+            notes.push("the CPU limit needs cgroups v2 and is not enforced".to_string());
+            AppliedLimit { value: percent as u64, enforced_by: LimitMechanism::NotEnforced }
+        }
+    });
+    applied.open_files = limits.max_open_files.map(via_rlimit);
+    applied.cgroup = cgroup;
+
+    #[cfg(unix)]
+    {
+        let rlimit_memory = applied
+            .memory_mb
+            .as_ref()
+            .filter(|l| l.enforced_by == LimitMechanism::Rlimit)
+            .map(|l| l.value * 1024 * 1024);
+        let open_files = limits.max_open_files;
+        #[cfg(target_os = "linux")]
+        let procs_path = match &applied.cgroup {
+            Some(dir) => Some(
+                std::ffi::CString::new(dir.join("cgroup.procs").to_string_lossy().into_owned())
+                    .map_err(|_| format!("Invalid cgroup path {:?}", dir))?,
+            ),
+            None => None,
+        };
+        // Safety: the closure only makes async-signal-safe calls on data prepared beforehand
+        unsafe {
+            cmd.pre_exec(move || {
+                #[cfg(target_os = "linux")]
+                if let Some(procs) = &procs_path {
+                    cgroup::join(procs)?;
+                }
+                if let Some(bytes) = rlimit_memory {
+                    lower_rlimit(libc::RLIMIT_DATA, bytes)?;
+                }
+                if let Some(files) = open_files {
+                    lower_rlimit(libc::RLIMIT_NOFILE, files)?;
+                }
+                Ok(())
+            });
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+
+    if !notes.is_empty() {
+        warn!("[Limits] Node '{}': {}", node_name, notes.join("; "));
+        applied.note = Some(notes.join("; "));
+    }
+    info!("[Limits] Node '{}' limits: {:?}", node_name, applied);
+    Ok(applied)
+}
+
+/// Removes the node's cgroup once its process has exited.
+pub fn release(applied: &AppliedLimits) {
+    if let Some(dir) = &applied.cgroup {
+        let _ = std::fs::remove_dir(dir);
+    }
+}
+
+/// Re-labels `diagnosis` when the exit was caused by one of the node's limits.
+pub fn attribute_breach(diagnosis: CrashDiagnosis, applied: &AppliedLimits) -> CrashDiagnosis {
+    let log_mentions = |patterns: &[&str]| {
+        diagnosis.log_tail.iter().rev().find(|line| {
+            let line = line.to_lowercase();
+            patterns.iter().any(|p| line.contains(p))
+        })
+    };
+    if let Some(limit) = &applied.memory_mb {
+        let oom_killed = applied.cgroup.as_ref().is_some_and(|dir| oom_kills(dir) > 0);
+        let matched = log_mentions(&["memory allocation of", "out of memory", "cannot allocate memory"]).cloned();
+        if oom_killed || (limit.enforced_by == LimitMechanism::Rlimit && matched.is_some()) {
+            let limit = format!("{} MB", limit.value);
+            return diagnosis.limit_breached(FailureKind::MemoryLimit, &limit, matched);
+        }
+    }
+    if let Some(limit) = &applied.open_files {
+        if let Some(line) = log_mentions(&["too many open files", "os error 24)"]).cloned() {
+            let limit = limit.value.to_string();
+            return diagnosis.limit_breached(FailureKind::OpenFilesLimit, &limit, Some(line));
+        }
+    }
+    diagnosis
+}
+
+/// `oom_kill` count from the cgroup's `memory.events`.
+fn oom_kills(cgroup_dir: &std::path::Path) -> u64 {
+    std::fs::read_to_string(cgroup_dir.join("memory.events"))
+        .ok()
+        .and_then(|events| {
+            events
+                .lines()
+                .find_map(|line| line.strip_prefix("oom_kill ").and_then(|n| n.trim().parse().ok()))
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(target_os = "linux")]
+mod cgroup {
+    use std::ffi::CStr;
+    use std::path::{Path, PathBuf};
+
+    use super::{NodeResourceLimits, CPU_PERIOD_US};
+
+    const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+    /// The app's cgroup v2 path from `/proc/self/cgroup` (the `0::` entry).
+    pub fn own_cgroup(proc_self_cgroup: &str) -> Option<&str> {
+        proc_self_cgroup.lines().find_map(|line| line.strip_prefix("0::")).map(str::trim)
+    }
+
+    fn writable(path: &Path) -> bool {
+        std::ffi::CString::new(path.to_string_lossy().into_owned())
+            .map(|c| unsafe { libc::access(c.as_ptr(), libc::W_OK) } == 0)
+            .unwrap_or(false)
+    }
+
+    /// Creates a cgroup for the node next to the app's own one and writes its limits.
+    pub fn create(node_name: &str, limits: &NodeResourceLimits) -> Result<PathBuf, String> {
+        let root = Path::new(CGROUP_ROOT);
+        if !root.join("cgroup.controllers").exists() {
+            return Err("no unified cgroup v2 hierarchy".to_string());
+        }
+        let own = std::fs::read_to_string("/proc/self/cgroup").map_err(|e| e.to_string())?;
+        let own = own_cgroup(&own).ok_or("the app is not in a cgroup v2")?;
+        let own_dir = root.join(own.trim_start_matches('/'));
+        let parent = own_dir
+            .parent()
+            .filter(|p| p.starts_with(root))
+            .ok_or("the app runs in the root cgroup")?
+            .to_path_buf();
+        // Moving merod out of the app's cgroup needs write access to the common parent
+        if !writable(&parent) || !writable(&parent.join("cgroup.procs")) {
+            return Err(format!("{:?} is not delegated to this user", parent));
+        }
+        let listed = |file: &str, controller: &str| {
+            std::fs::read_to_string(parent.join(file))
+                .map(|list| list.split_whitespace().any(|c| c == controller))
+                .unwrap_or(false)
+        };
+        for (needed, controller) in [(limits.max_memory_mb.is_some(), "memory"), (limits.cpu_percent.is_some(), "cpu")] {
+            if !needed || listed("cgroup.subtree_control", controller) {
+                continue;
+            }
+            // Delegated but not yet enabled for children
+            let enabled = listed("cgroup.controllers", controller)
+                && std::fs::write(parent.join("cgroup.subtree_control"), format!("+{}", controller)).is_ok();
+            if !enabled {
+                return Err(format!("the {} controller is not delegated to {:?}", controller, parent));
+            }
+        }
+
+        // Clean up cgroups left by earlier runs of this node; busy ones refuse removal
+        let prefix = format!("calimero-merod-{}-", node_name);
+        if let Ok(entries) = std::fs::read_dir(&parent) {
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                    let _ = std::fs::remove_dir(entry.path());
+                }
+            }
+        }
+        let dir = parent.join(format!("{}{}", prefix, chrono::Utc::now().timestamp_millis()));
+        std::fs::create_dir(&dir).map_err(|e| format!("failed to create {:?}: {}", dir, e))?;
+        let write = |file: &str, value: String| {
+            std::fs::write(dir.join(file), value).map_err(|e| format!("failed to set {}: {}", file, e))
+        };
+        let result = (|| {
+            if let Some(mb) = limits.max_memory_mb {
+                write("memory.max", (mb * 1024 * 1024).to_string())?;
+                // Without swap a breach kills the node instead of slowing the machine down
+                let _ = write("memory.swap.max", "0".to_string());
+            }
+            if let Some(percent) = limits.cpu_percent {
+                write("cpu.max", format!("{} {}", percent as u64 * CPU_PERIOD_US / 100, CPU_PERIOD_US))?;
+            }
+            Ok::<_, String>(())
+        })();
+        if let Err(e) = result {
+            let _ = std::fs::remove_dir(&dir);
+            return Err(e);
+        }
+        Ok(dir)
+    }
+
+    /// Moves the calling process into the cgroup whose `cgroup.procs` is `procs`.
+    /// Async-signal-safe.
+    pub fn join(procs: &CStr) -> std::io::Result<()> {
+        let fd = unsafe { libc::open(procs.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        // "0" means the writing process
+        let written = unsafe { libc::write(fd, b"0".as_ptr().cast(), 1) };
+        let error = std::io::Error::last_os_error();
+        unsafe { libc::close(fd) };
+        if written != 1 {
+            return Err(error);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_validate_limits() {
+        assert!(NodeResourceLimits::default().validate().is_ok());
+        let limits = NodeResourceLimits { max_memory_mb: Some(2048), cpu_percent: Some(50), max_open_files: Some(4096) };
+        assert!(limits.validate().is_ok());
+        assert!(NodeResourceLimits { max_memory_mb: Some(64), ..Default::default() }.validate().is_err());
+        assert!(NodeResourceLimits { cpu_percent: Some(0), ..Default::default() }.validate().is_err());
+        assert!(NodeResourceLimits { cpu_percent: Some(100_000), ..Default::default() }.validate().is_err());
+        assert!(NodeResourceLimits { max_open_files: Some(10), ..Default::default() }.validate().is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_own_cgroup() {
+        let content = "0::/user.slice/user-1000.slice/user@1000.service/app.slice/calimero.scope\n";
+        assert_eq!(
+            cgroup::own_cgroup(content),
+            Some("/user.slice/user-1000.slice/user@1000.service/app.slice/calimero.scope")
+        );
+        assert_eq!(cgroup::own_cgroup("12:memory:/user.slice\n"), None);
+    }
+
+    #[test]
+    fn test_attribute_breach() {
+        let dir = std::env::temp_dir().join(format!("calimero-limits-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("memory.events"), "low 0\nhigh 0\nmax 12\noom 1\noom_kill 1\n").unwrap();
+        let log = Path::new("merod.log");
+        let killed = crate::crash_diagnosis::diagnose("node1", 42, None, Some(9), log, vec!["INFO syncing".to_string()]);
+
+        let cgroup_limits = AppliedLimits {
+            memory_mb: Some(AppliedLimit { value: 512, enforced_by: LimitMechanism::Cgroup }),
+            cgroup: Some(dir.clone()),
+            ..Default::default()
+        };
+        let diagnosis = attribute_breach(killed.clone(), &cgroup_limits);
+        assert_eq!(diagnosis.kind, FailureKind::MemoryLimit);
+        assert!(diagnosis.summary.contains("512 MB"));
+
+        let files_limits = AppliedLimits {
+            open_files: Some(AppliedLimit { value: 1024, enforced_by: LimitMechanism::Rlimit }),
+            ..Default::default()
+        };
+        let tail = vec!["ERROR IO error: Too many open files (os error 24)".to_string()];
+        let exited = crate::crash_diagnosis::diagnose("node1", 42, Some(1), None, log, tail);
+        assert_eq!(attribute_breach(exited, &files_limits).kind, FailureKind::OpenFilesLimit);
+        assert_eq!(attribute_breach(killed, &files_limits).kind, FailureKind::Signal);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,137 @@
+//! Changing a node's log filter while it runs.
+//!
+//! The new filter is saved as the node's `log_filter` run setting first, so it survives later
+//! restarts. merod reads `RUST_LOG` only at startup and its admin API has no route to change
+//! the filter of a running node, so a running node is restarted gracefully on the same ports
+//! with the new filter.
+
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use log::info;
+use regex::Regex;
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::{node_ops, node_registry};
+
+/// How long a restarted node may take to become healthy again.
+const RESTART_HEALTH_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevelMethod {
+    /// The node was stopped and started again with the new filter.
+    Restart,
+    /// The node isn't running; the filter applies when it next starts.
+    Saved,
+}
+
+/// Server and swarm ports from the node's config (the first listen address of each).
+pub fn configured_ports(node_dir: &Path) -> Result<(Option<u16>, Option<u16>), String> {
+    let config = node_ops::read_config(node_dir)?.ok_or_else(|| format!("{:?} has no config.toml", node_dir))?;
+    static LISTEN_PORT: OnceLock<Regex> = OnceLock::new();
+    let listen_port = LISTEN_PORT.get_or_init(|| Regex::new(r"/(?:tcp|udp)/(\d+)").unwrap());
+    let port_of = |section: &str| {
+        config
+            .get(section)
+            .and_then(|s| s.get("listen"))
+            .and_then(|l| l.as_array())
+            .and_then(|addrs| addrs.iter().filter_map(|a| a.as_str()).find_map(|a| listen_port.captures(a)))
+            .and_then(|c| c[1].parse().ok())
+    };
+    Ok((port_of("server"), port_of("swarm")))
+}
+
+/// Applies `filter` (RUST_LOG syntax, e.g. `calimero_node=trace,libp2p=warn`) to a node,
+/// restarting it gracefully if it is running.
+#[tauri::command]
+pub async fn set_node_log_level(
+    node_name: String,
+    filter: String,
+    home_dir: Option<String>,
+    app_handle: tauri::AppHandle,
+    merod_state: tauri::State<'_, crate::MerodState>,
+) -> Result<serde_json::Value, String> {
+    let filter = filter.trim().to_string();
+    node_registry::validate_log_filter(&filter)?;
+    let home = crate::resolve_calimero_home(home_dir)?;
+    let node_dir = node_ops::existing_node_dir(&home, &node_name)?;
+
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    node_registry::update(&app_data_dir, &node_dir, |settings| settings.log_filter = Some(filter.clone()))?;
+
+    let pids = node_ops::running_node_pids(&home, &node_name);
+    let Some(&pid) = pids.first() else {
+        info!("[Log Level] Saved log filter '{}' for stopped node '{}'", filter, node_name);
+        return Ok(serde_json::json!({
+            "node_name": node_name,
+            "filter": filter,
+            "method": LogLevelMethod::Saved,
+        }));
+    };
+
+    // Prefer the port the app started the node on; fall back to its config
+    let (config_server_port, swarm_port) = configured_ports(&node_dir)?;
+    let tracked_port = merod_state
+        .lock()
+        .ok()
+        .and_then(|state| state.iter().find(|p| p.pid == pid).map(|p| p.port));
+    let server_port = tracked_port
+        .or(config_server_port)
+        .ok_or_else(|| format!("Could not determine the server port of '{}'", node_name))?;
+
+    info!("[Log Level] Restarting '{}' to apply log filter '{}'", node_name, filter);
+    for pid in &pids {
+        crate::stop_merod_pid(*pid).await?;
+    }
+    if let Ok(mut state) = merod_state.lock() {
+        state.retain(|p| !pids.contains(&p.pid));
+    }
+    let started = crate::start_merod(
+        Some(server_port),
+        swarm_port,
+        Some(home.to_string_lossy().into_owned()),
+        Some(node_name.clone()),
+        None,
+        Some(RESTART_HEALTH_TIMEOUT_SECS),
+        app_handle.clone(),
+        merod_state,
+        app_handle.state(),
+        app_handle.state(),
+    )
+    .await
+    .map_err(|e| format!("Stopped '{}' to apply the log filter, but it failed to start again: {}", node_name, e))?;
+
+    Ok(serde_json::json!({
+        "node_name": node_name,
+        "filter": filter,
+        "method": LogLevelMethod::Restart,
+        "pid": started.pid,
+        "previous_pid": pid,
+        "time_to_ready_ms": started.time_to_ready_ms,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_ports() {
+        let dir = std::env::temp_dir().join(format!("calimero-log-level-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            "[server]\nlisten = [\"/ip4/127.0.0.1/tcp/2530\", \"/ip6/::1/tcp/2530\"]\n\n\
+             [swarm]\nlisten = [\"/ip4/0.0.0.0/tcp/2430\", \"/ip4/0.0.0.0/udp/2430/quic-v1\"]\n",
+        )
+        .unwrap();
+        assert_eq!(configured_ports(&dir).unwrap(), (Some(2530), Some(2430)));
+
+        std::fs::write(dir.join("config.toml"), "[identity]\npeer_id = \"x\"\n").unwrap();
+        assert_eq!(configured_ports(&dir).unwrap(), (None, None));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,597 @@
+//! Operations on a single node directory, `<home>/<node>`: delete, rename and clone.
+//!
+//! All of them refuse to touch a node while a merod process is running it. Config values
+//! that point into the node directory are rewritten when it moves, and merod version pins
+//! follow the node.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use log::{info, warn};
+
+use crate::node_init::validate_node_name;
+
+/// Directories that belong to one run of a node and aren't copied to a clone.
+const CLONE_SKIP_DIRS: &[&str] = &["logs"];
+
+/// Extracts `--home` and `--node` from a merod argument vector.
+pub fn parse_merod_args(argv: &[String]) -> (Option<String>, Option<String>) {
+    let mut home = None;
+    let mut node = None;
+    for (i, arg) in argv.iter().enumerate() {
+        let next = argv.get(i + 1).cloned();
+        match arg.as_str() {
+            "--home" => home = next,
+            "--node" | "-n" | "--node-name" => node = next,
+            _ => {
+                if let Some(value) = arg.strip_prefix("--home=") {
+                    home = Some(value.to_string());
+                } else if let Some(value) = arg.strip_prefix("--node=") {
+                    node = Some(value.to_string());
+                }
+            }
+        }
+    }
+    (home, node)
+}
+
+pub fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn is_merod(argv: &[String]) -> bool {
+    argv.first()
+        .and_then(|program| program.rsplit(['/', '\\']).next())
+        .is_some_and(|name| name.starts_with("merod"))
+}
+
+/// Arguments of a NUL-separated argument block (`/proc/<pid>/cmdline`).
+#[cfg(any(target_os = "linux", test))]
+fn split_nul_args(block: &[u8]) -> Vec<String> {
+    let block = block.strip_suffix(&[0]).unwrap_or(block);
+    if block.is_empty() {
+        return Vec::new();
+    }
+    block.split(|b| *b == 0).map(|arg| String::from_utf8_lossy(arg).into_owned()).collect()
+}
+
+/// Arguments from a `KERN_PROCARGS2` buffer: `argc`, the executable path, NUL padding, then
+/// `argc` NUL-terminated arguments.
+#[cfg(any(target_os = "macos", test))]
+fn parse_procargs2(buf: &[u8]) -> Option<Vec<String>> {
+    let argc = i32::from_ne_bytes(buf.get(..4)?.try_into().ok()?).max(0) as usize;
+    let rest = &buf[4..];
+    let exec_end = rest.iter().position(|b| *b == 0)?;
+    let args_start = exec_end + rest[exec_end..].iter().position(|b| *b != 0)?;
+    Some(
+        rest[args_start..]
+            .split(|b| *b == 0)
+            .take(argc)
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect(),
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn process_argv(pid: u32) -> Option<Vec<String>> {
+    let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, pid as libc::c_int];
+    let mut size: libc::size_t = 0;
+    // SAFETY: a size query with a null buffer; `mib` and `size` outlive the call
+    let queried = unsafe {
+        libc::sysctl(mib.as_mut_ptr(), 3, std::ptr::null_mut(), &mut size, std::ptr::null_mut(), 0)
+    };
+    if queried != 0 || size == 0 {
+        return None;
+    }
+    let mut buf = vec![0u8; size];
+    // SAFETY: `buf` holds `size` bytes, and sysctl writes at most `size` and updates it
+    let read = unsafe {
+        libc::sysctl(mib.as_mut_ptr(), 3, buf.as_mut_ptr().cast(), &mut size, std::ptr::null_mut(), 0)
+    };
+    if read != 0 {
+        return None;
+    }
+    buf.truncate(size);
+    parse_procargs2(&buf)
+}
+
+/// Splits a Windows command line the way `CommandLineToArgvW` does: whitespace separates
+/// arguments outside double quotes, and backslashes only escape a following quote.
+#[cfg(any(windows, test))]
+fn split_windows_command_line(command_line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut in_quotes = false;
+    let mut backslashes = 0;
+    for c in command_line.chars() {
+        match c {
+            '\\' => {
+                backslashes += 1;
+                in_arg = true;
+                continue;
+            }
+            '"' => {
+                current.push_str(&"\\".repeat(backslashes / 2));
+                if backslashes % 2 == 1 {
+                    current.push('"');
+                } else {
+                    in_quotes = !in_quotes;
+                }
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                current.push_str(&"\\".repeat(backslashes));
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                }
+                in_arg = false;
+            }
+            c => {
+                current.push_str(&"\\".repeat(backslashes));
+                current.push(c);
+                in_arg = true;
+            }
+        }
+        backslashes = 0;
+    }
+    current.push_str(&"\\".repeat(backslashes));
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Running merod processes with their arguments, as `(pid, argv)`. Arguments are read per
+/// process rather than split from a `ps` line, so paths with spaces survive.
+pub fn merod_processes() -> Vec<(u32, Vec<String>)> {
+    #[cfg(target_os = "linux")]
+    {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let pid = entry.file_name().to_str()?.parse().ok()?;
+                let cmdline = std::fs::read(entry.path().join("cmdline")).ok()?;
+                Some((pid, split_nul_args(&cmdline)))
+            })
+            .filter(|(_, argv)| is_merod(argv))
+            .collect()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // `ps` only picks the candidates; their arguments come from the kernel
+        let Ok(output) = std::process::Command::new("ps").arg("ax").arg("-o").arg("pid=,comm=").output() else {
+            return Vec::new();
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| line.contains("merod"))
+            .filter_map(|line| line.trim().split_once(' ')?.0.parse().ok())
+            .filter_map(|pid| Some((pid, process_argv(pid)?)))
+            .filter(|(_, argv)| is_merod(argv))
+            .collect()
+    }
+
+    #[cfg(windows)]
+    {
+        let Ok(output) = std::process::Command::new("wmic")
+            .arg("process")
+            .arg("where")
+            .arg("name like 'merod%'")
+            .arg("get")
+            .arg("CommandLine,ProcessId")
+            .arg("/format:list")
+            .output()
+        else {
+            return Vec::new();
+        };
+        let mut processes = Vec::new();
+        let mut command = None;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if let Some(value) = line.trim().strip_prefix("CommandLine=") {
+                command = Some(split_windows_command_line(value));
+            } else if let Some(value) = line.trim().strip_prefix("ProcessId=") {
+                if let (Some(argv), Ok(pid)) = (command.take(), value.parse()) {
+                    if is_merod(&argv) {
+                        processes.push((pid, argv));
+                    }
+                }
+            }
+        }
+        processes
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    {
+        Vec::new()
+    }
+}
+
+/// PIDs of merod processes running `node_name` in `home_dir`.
+pub fn running_node_pids(home_dir: &Path, node_name: &str) -> Vec<u32> {
+    merod_processes()
+        .into_iter()
+        .filter(|(_, argv)| {
+            let (home, node) = parse_merod_args(argv);
+            let home = home
+                .map(PathBuf::from)
+                .or_else(|| crate::resolve_calimero_home(None).ok());
+            node.as_deref() == Some(node_name) && home.is_some_and(|home| same_dir(&home, home_dir))
+        })
+        .map(|(pid, _)| pid)
+        .collect()
+}
+
+pub fn ensure_not_running(home_dir: &Path, node_name: &str) -> Result<(), String> {
+    let pids = running_node_pids(home_dir, node_name);
+    if pids.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Node '{}' is running (pid {}). Stop it first.",
+            node_name,
+            pids.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
+        ))
+    }
+}
+
+/// `<home>/<node>`, which must exist and be a directory directly inside `home_dir`.
+pub fn existing_node_dir(home_dir: &Path, node_name: &str) -> Result<PathBuf, String> {
+    validate_node_name(node_name)?;
+    let node_dir = home_dir.join(node_name);
+    if !node_dir.is_dir() {
+        return Err(format!("Node '{}' does not exist in {:?}", node_name, home_dir));
+    }
+    // Refuse to follow a symlinked node directory out of the home
+    if node_dir.symlink_metadata().map(|m| m.file_type().is_symlink()).unwrap_or(true) {
+        return Err(format!("{:?} is a symlink, not a node directory", node_dir));
+    }
+    Ok(node_dir)
+}
+
+pub fn new_node_dir(home_dir: &Path, node_name: &str) -> Result<PathBuf, String> {
+    validate_node_name(node_name)?;
+    let node_dir = home_dir.join(node_name);
+    if node_dir.symlink_metadata().is_ok() {
+        return Err(format!("Node '{}' already exists in {:?}", node_name, home_dir));
+    }
+    Ok(node_dir)
+}
+
+/// Copies `from` into `to` recursively, skipping top-level directories named in `skip`.
+pub fn copy_dir_all(from: &Path, to: &Path, skip: &[&str]) -> Result<u64, String> {
+    std::fs::create_dir_all(to).map_err(|e| format!("Failed to create {:?}: {}", to, e))?;
+    let mut copied = 0;
+    for entry in std::fs::read_dir(from).map_err(|e| format!("Failed to read {:?}: {}", from, e))? {
+        let entry = entry.map_err(|e| format!("Failed to read {:?}: {}", from, e))?;
+        let name = entry.file_name();
+        if skip.iter().any(|s| name == *s) {
+            continue;
+        }
+        let file_type = entry.file_type().map_err(|e| format!("Failed to stat {:?}: {}", entry.path(), e))?;
+        let target = to.join(&name);
+        if file_type.is_dir() {
+            copied += copy_dir_all(&entry.path(), &target, &[])?;
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), &target)
+                .map_err(|e| format!("Failed to copy {:?}: {}", entry.path(), e))?;
+            copied += 1;
+        } else {
+            warn!("[Node Ops] Skipping {:?}: not a regular file or directory", entry.path());
+        }
+    }
+    Ok(copied)
+}
+
+/// Rewrites every string in `value` that points into `old_dir` to point into `new_dir`.
+/// Returns how many values changed.
+fn rewrite_paths(value: &mut toml::Value, old_dir: &str, new_dir: &str) -> usize {
+    match value {
+        toml::Value::String(s) => match s.strip_prefix(old_dir) {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\') => {
+                *s = format!("{}{}", new_dir, rest);
+                1
+            }
+            _ => 0,
+        },
+        toml::Value::Array(items) => items.iter_mut().map(|v| rewrite_paths(v, old_dir, new_dir)).sum(),
+        toml::Value::Table(table) => table.iter_mut().map(|(_, v)| rewrite_paths(v, old_dir, new_dir)).sum(),
+        _ => 0,
+    }
+}
+
+pub fn read_config(node_dir: &Path) -> Result<Option<toml::Value>, String> {
+    let config_path = node_dir.join("config.toml");
+    if !config_path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed to read {:?}: {}", config_path, e))?;
+    content
+        .parse::<toml::Value>()
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {:?}: {}", config_path, e))
+}
+
+pub fn write_config(node_dir: &Path, config: &toml::Value) -> Result<(), String> {
+    let config_path = node_dir.join("config.toml");
+    let content = toml::to_string(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    std::fs::write(&config_path, content).map_err(|e| format!("Failed to write {:?}: {}", config_path, e))
+}
+
+/// Points config values that mention the old node directory at the new one.
+pub fn update_config_paths(new_node_dir: &Path, old_node_dir: &Path) -> Result<(), String> {
+    let Some(mut config) = read_config(new_node_dir)? else {
+        return Ok(());
+    };
+    let changed = rewrite_paths(
+        &mut config,
+        &old_node_dir.to_string_lossy(),
+        &new_node_dir.to_string_lossy(),
+    );
+    if changed > 0 {
+        write_config(new_node_dir, &config)?;
+        info!("[Node Ops] Updated {} config path(s) in {:?}", changed, new_node_dir);
+    }
+    Ok(())
+}
+
+static SCRATCH_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A scratch directory removed when dropped, on every return path.
+struct ScratchDir(PathBuf);
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Gives the node at `node_dir` a fresh identity by initializing a throwaway node with
+/// `merod_binary` and taking its `[identity]` section.
+async fn regenerate_identity(merod_binary: &Path, node_dir: &Path) -> Result<(), String> {
+    let Some(mut config) = read_config(node_dir)? else {
+        return Err(format!("{:?} has no config.toml to regenerate the identity in", node_dir));
+    };
+
+    // Unique per call: concurrent clones in this process must not share a scratch home
+    let scratch_home = ScratchDir(std::env::temp_dir().join(format!(
+        "calimero-identity-{}-{}",
+        std::process::id(),
+        SCRATCH_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )));
+    let _ = std::fs::remove_dir_all(&scratch_home.0);
+    let output = tokio::time::timeout(
+        tokio::time::Duration::from_secs(30),
+        tokio::process::Command::new(merod_binary)
+            .arg("--home")
+            .arg(&scratch_home.0)
+            .arg("--node")
+            .arg("identity")
+            .arg("init")
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await;
+    let identity = match output {
+        Ok(Ok(output)) if output.status.success() => read_config(&scratch_home.0.join("identity"))
+            .ok()
+            .flatten()
+            .and_then(|c| c.get("identity").cloned()),
+        Ok(Ok(output)) => {
+            return Err(format!(
+                "Failed to generate a new identity: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(Err(e)) => return Err(format!("Failed to run merod to generate a new identity: {}", e)),
+        Err(_) => return Err("Generating a new identity timed out".to_string()),
+    };
+    drop(scratch_home);
+
+    let identity = identity.ok_or("merod did not write an [identity] section")?;
+    let table = config.as_table_mut().ok_or("config.toml is not a table")?;
+    table.insert("identity".to_string(), identity);
+    write_config(node_dir, &config)
+}
+
+/// Moves (or with `keep_old`, copies) the node's merod pin and run settings to `to`; with no
+/// `to` they are removed.
+fn move_node_settings(app_handle: &tauri::AppHandle, home_dir: &Path, from: &str, to: Option<&str>, keep_old: bool) {
+    let Ok(app_data_dir) = crate::get_app_data_dir(app_handle) else {
+        return;
+    };
+    let to_dir = to.map(|to| home_dir.join(to));
+    if let Err(e) = crate::node_registry::transfer(&app_data_dir, &home_dir.join(from), to_dir.as_deref(), keep_old) {
+        warn!("[Node Ops] Failed to move run settings of '{}': {}", from, e);
+    }
+    let Some(version) = crate::merod_manager::pinned_version(&app_data_dir, home_dir, from) else {
+        return;
+    };
+    if let Some(to) = to {
+        if let Err(e) = crate::merod_manager::set_pin(&app_data_dir, home_dir, to, Some(&version)) {
+            warn!("[Node Ops] Failed to pin '{}' to merod {}: {}", to, version, e);
+        }
+    }
+    if !keep_old {
+        if let Err(e) = crate::merod_manager::set_pin(&app_data_dir, home_dir, from, None) {
+            warn!("[Node Ops] Failed to remove merod pin of '{}': {}", from, e);
+        }
+    }
+}
+
+/// Deletes one node directory, `<home>/<node>`. Other nodes in the home are untouched.
+#[tauri::command]
+pub async fn delete_node(
+    node_name: String,
+    home_dir: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let home_dir_path = crate::resolve_calimero_home(home_dir)?;
+    let node_dir = existing_node_dir(&home_dir_path, &node_name)?;
+    ensure_not_running(&home_dir_path, &node_name)?;
+    // A unit left behind would fail on every login and restart forever
+    crate::node_service::remove_unit(&home_dir_path, &node_name)?;
+
+    std::fs::remove_dir_all(&node_dir).map_err(|e| format!("Failed to delete node '{}': {}", node_name, e))?;
+    move_node_settings(&app_handle, &home_dir_path, &node_name, None, false);
+
+    info!("[Node Ops] Deleted node '{}' ({:?})", node_name, node_dir);
+    Ok(format!("Deleted node '{}'", node_name))
+}
+
+/// Renames `<home>/<node>` to `<home>/<new_name>`, updating config paths and merod pins.
+#[tauri::command]
+pub async fn rename_node(
+    node_name: String,
+    new_name: String,
+    home_dir: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let home_dir_path = crate::resolve_calimero_home(home_dir)?;
+    let node_dir = existing_node_dir(&home_dir_path, &node_name)?;
+    let new_dir = new_node_dir(&home_dir_path, &new_name)?;
+    ensure_not_running(&home_dir_path, &node_name)?;
+    crate::node_service::ensure_no_units(&home_dir_path, Some(&node_name))?;
+
+    std::fs::rename(&node_dir, &new_dir)
+        .map_err(|e| format!("Failed to rename node '{}' to '{}': {}", node_name, new_name, e))?;
+    if let Err(e) = update_config_paths(&new_dir, &node_dir) {
+        warn!("[Node Ops] Renamed '{}' but could not update its config: {}", new_name, e);
+    }
+    move_node_settings(&app_handle, &home_dir_path, &node_name, Some(&new_name), false);
+
+    info!("[Node Ops] Renamed node '{}' to '{}'", node_name, new_name);
+    Ok(format!("Renamed node '{}' to '{}'", node_name, new_name))
+}
+
+/// Copies `<home>/<node>` to `<home>/<new_name>` (without logs). Unless
+/// `regenerate_identity` is false, the clone gets new identity keys so both nodes can run
+/// side by side; a clone keeping the same identity will conflict with the original on the
+/// network.
+#[tauri::command]
+pub async fn clone_node(
+    node_name: String,
+    new_name: String,
+    home_dir: Option<String>,
+    regenerate_identity: Option<bool>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let home_dir_path = crate::resolve_calimero_home(home_dir)?;
+    let node_dir = existing_node_dir(&home_dir_path, &node_name)?;
+    let new_dir = new_node_dir(&home_dir_path, &new_name)?;
+    ensure_not_running(&home_dir_path, &node_name)?;
+
+    let result = async {
+        let copied = copy_dir_all(&node_dir, &new_dir, CLONE_SKIP_DIRS)?;
+        update_config_paths(&new_dir, &node_dir)?;
+        if regenerate_identity.unwrap_or(true) {
+            let merod_binary = crate::get_node_merod_binary(&app_handle, &home_dir_path, &node_name)?;
+            self::regenerate_identity(&merod_binary, &new_dir).await?;
+            info!("[Node Ops] Generated a new identity for clone '{}'", new_name);
+        } else {
+            warn!("[Node Ops] Clone '{}' keeps the identity of '{}'", new_name, node_name);
+        }
+        Ok::<u64, String>(copied)
+    }
+    .await;
+
+    match result {
+        Ok(copied) => {
+            move_node_settings(&app_handle, &home_dir_path, &node_name, Some(&new_name), true);
+            info!("[Node Ops] Cloned node '{}' to '{}' ({} files)", node_name, new_name, copied);
+            Ok(format!("Cloned node '{}' to '{}'", node_name, new_name))
+        }
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&new_dir);
+            warn!("[Node Ops] Failed to clone '{}' to '{}': {}", node_name, new_name, e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_merod_args() {
+        let argv = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_merod_args(&argv(&["/app/merod", "--home", "/home/me/.calimero", "--node", "node1", "run"])),
+            (Some("/home/me/.calimero".to_string()), Some("node1".to_string()))
+        );
+        assert_eq!(parse_merod_args(&argv(&["merod", "--node=node2", "run"])), (None, Some("node2".to_string())));
+    }
+
+    #[test]
+    fn test_home_with_spaces_survives() {
+        let expected = (Some("/home/u/my nodes".to_string()), Some("node1".to_string()));
+
+        let cmdline = b"/app/merod\0--home\0/home/u/my nodes\0--node\0node1\0run\0";
+        let argv = split_nul_args(cmdline);
+        assert!(is_merod(&argv));
+        assert_eq!(parse_merod_args(&argv), expected);
+
+        let mut procargs = 6i32.to_ne_bytes().to_vec();
+        procargs.extend_from_slice(b"/app/merod\0\0\0\0");
+        procargs.extend_from_slice(cmdline);
+        procargs.extend_from_slice(b"PATH=/usr/bin\0");
+        assert_eq!(parse_merod_args(&parse_procargs2(&procargs).unwrap()), expected);
+
+        let argv = split_windows_command_line(
+            r#""C:\Program Files\Calimero\merod.exe" --home "C:\Users\u\my nodes" --node node1 run"#,
+        );
+        assert_eq!(argv[0], r"C:\Program Files\Calimero\merod.exe");
+        assert!(is_merod(&argv));
+        assert_eq!(
+            parse_merod_args(&argv),
+            (Some(r"C:\Users\u\my nodes".to_string()), Some("node1".to_string()))
+        );
+        assert_eq!(split_windows_command_line(r#"a\\"b c" d\"e"#), [r"a\b c", r#"d"e"#]);
+    }
+
+    #[test]
+    fn test_rewrite_paths_only_touches_node_dir() {
+        let mut config: toml::Value = r#"
+            [datastore]
+            path = "/home/me/.calimero/node1/data"
+            [blobstore]
+            path = "/home/me/.calimero/node10/blobs"
+            [server]
+            listen = ["/ip4/127.0.0.1/tcp/2528"]
+        "#
+        .parse()
+        .unwrap();
+        let changed = rewrite_paths(&mut config, "/home/me/.calimero/node1", "/home/me/.calimero/copy");
+        assert_eq!(changed, 1);
+        assert_eq!(config["datastore"]["path"].as_str(), Some("/home/me/.calimero/copy/data"));
+        assert_eq!(config["blobstore"]["path"].as_str(), Some("/home/me/.calimero/node10/blobs"));
+    }
+
+    #[test]
+    fn test_copy_dir_skips_logs() {
+        let root = std::env::temp_dir().join(format!("calimero-node-ops-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let from = root.join("node1");
+        std::fs::create_dir_all(from.join("data")).unwrap();
+        std::fs::create_dir_all(from.join("logs")).unwrap();
+        std::fs::write(from.join("config.toml"), "x = 1").unwrap();
+        std::fs::write(from.join("data/db"), "db").unwrap();
+        std::fs::write(from.join("logs/merod.log"), "log").unwrap();
+
+        let copied = copy_dir_all(&from, &root.join("node2"), CLONE_SKIP_DIRS).unwrap();
+        assert_eq!(copied, 2);
+        assert!(root.join("node2/data/db").exists());
+        assert!(!root.join("node2/logs").exists());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
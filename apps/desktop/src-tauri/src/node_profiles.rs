@@ -0,0 +1,362 @@
+//! Per-node connection profiles.
+//!
+//! A profile lets a remote node be reached over HTTPS when its certificate does not chain to
+//! the Mozilla root set bundled with the app (`webpki-roots`; the OS trust store is not
+//! consulted): either through an extra CA bundle or by pinning the server certificate's
+//! SHA-256 fingerprint. A client certificate can be added for mutual TLS. The proxy and the
+//! health check build their reqwest client from the profile matching the request's origin.
+//!
+//! Profiles are persisted as JSON in the app data directory.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const PROFILES_FILE: &str = "node-profiles.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeProfile {
+    /// Base URL of the node, e.g. `https://node.team.internal:2528`.
+    pub node_url: String,
+    /// PEM file with one or more CA certificates trusted in addition to the bundled Mozilla
+    /// roots. CAs installed only in the OS trust store must be listed here too.
+    #[serde(default)]
+    pub ca_bundle_path: Option<String>,
+    /// Hex SHA-256 of the server's DER certificate (colons allowed). When set, the server is
+    /// trusted if and only if it presents exactly this certificate.
+    #[serde(default)]
+    pub pinned_cert_sha256: Option<String>,
+    /// PEM file containing the client certificate chain and its private key, for mTLS.
+    #[serde(default)]
+    pub client_identity_path: Option<String>,
+}
+
+impl NodeProfile {
+    fn origin(&self) -> Result<String, String> {
+        origin_of(&self.node_url)
+    }
+
+    fn has_tls_options(&self) -> bool {
+        self.ca_bundle_path.is_some() || self.pinned_cert_sha256.is_some() || self.client_identity_path.is_some()
+    }
+}
+
+fn origin_of(url: &str) -> Result<String, String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid node URL '{}': {}", url, e))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(parsed.origin().ascii_serialization()),
+        scheme => Err(format!("Unsupported node URL scheme '{}'. Use http:// or https://", scheme)),
+    }
+}
+
+/// Parses a fingerprint like `AB:CD:...` or `abcd...` into 32 bytes.
+pub fn parse_fingerprint(fingerprint: &str) -> Result<[u8; 32], String> {
+    let cleaned: String = fingerprint.chars().filter(|c| *c != ':' && !c.is_whitespace()).collect();
+    let bytes = hex::decode(&cleaned)
+        .map_err(|_| format!("Certificate fingerprint '{}' is not valid hex", fingerprint))?;
+    bytes
+        .try_into()
+        .map_err(|_| "Certificate fingerprint must be a SHA-256 hash (32 bytes, 64 hex characters)".to_string())
+}
+
+fn read_pem(path: &str, what: &str) -> Result<Vec<u8>, String> {
+    let path = crate::expand_tilde(path.to_string());
+    std::fs::read(&path).map_err(|e| format!("Failed to read {} {:?}: {}", what, path, e))
+}
+
+fn pem_certificates(pem: &[u8], what: &str) -> Result<Vec<rustls::Certificate>, String> {
+    let certs = rustls_pemfile::certs(&mut &pem[..])
+        .map_err(|e| format!("Failed to parse {}: {}", what, e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", what));
+    }
+    Ok(certs.into_iter().map(rustls::Certificate).collect())
+}
+
+fn pem_private_key(pem: &[u8]) -> Result<rustls::PrivateKey, String> {
+    let mut reader = pem;
+    loop {
+        match rustls_pemfile::read_one(&mut reader).map_err(|e| format!("Failed to parse client identity: {}", e))? {
+            Some(rustls_pemfile::Item::PKCS8Key(key))
+            | Some(rustls_pemfile::Item::RSAKey(key))
+            | Some(rustls_pemfile::Item::ECKey(key)) => return Ok(rustls::PrivateKey(key)),
+            Some(_) => continue,
+            None => return Err("No private key found in client identity PEM".to_string()),
+        }
+    }
+}
+
+/// Trusts exactly one server certificate, identified by its SHA-256 fingerprint.
+struct PinnedCertVerifier {
+    fingerprint: [u8; 32],
+}
+
+impl rustls::client::ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: std::time::SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        let actual = Sha256::digest(&end_entity.0);
+        if actual.as_slice() == self.fingerprint {
+            Ok(rustls::client::ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(format!(
+                "server certificate fingerprint {} does not match the pinned fingerprint",
+                hex::encode(actual)
+            )))
+        }
+    }
+}
+
+/// Builds an HTTP client for `profile` (or a default client without one).
+pub fn build_client(profile: Option<&NodeProfile>) -> Result<reqwest::Client, String> {
+    let builder = reqwest::Client::builder().danger_accept_invalid_certs(false);
+    let Some(profile) = profile.filter(|p| p.has_tls_options()) else {
+        return builder
+            .build()
+            .map_err(|e| format!("Failed to initialize HTTP client: {}", e));
+    };
+
+    if !profile.node_url.starts_with("https://") {
+        return Err(format!(
+            "TLS options were given for '{}', but only https:// node URLs use TLS",
+            profile.node_url
+        ));
+    }
+
+    let identity = match &profile.client_identity_path {
+        Some(path) => {
+            let pem = read_pem(path, "client identity")?;
+            Some((pem_certificates(&pem, "client identity")?, pem_private_key(&pem)?))
+        }
+        None => None,
+    };
+
+    // Same Mozilla roots as the default rustls client, not the OS store
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(ta.subject, ta.spki, ta.name_constraints)
+    }));
+    if let Some(path) = &profile.ca_bundle_path {
+        let pem = read_pem(path, "CA bundle")?;
+        for cert in pem_certificates(&pem, "CA bundle")? {
+            roots
+                .add(&cert)
+                .map_err(|e| format!("Invalid CA certificate in {}: {}", path, e))?;
+        }
+    }
+
+    // The verifier and root-store builders are different types, so each combination with
+    // client auth is spelled out
+    let config = rustls::ClientConfig::builder().with_safe_defaults();
+    let client_auth_error = |e: rustls::Error| format!("Invalid client certificate or key: {}", e);
+    let config = match (&profile.pinned_cert_sha256, identity) {
+        (Some(fingerprint), identity) => {
+            let config = config.with_custom_certificate_verifier(Arc::new(PinnedCertVerifier {
+                fingerprint: parse_fingerprint(fingerprint)?,
+            }));
+            match identity {
+                Some((certs, key)) => config.with_client_auth_cert(certs, key).map_err(client_auth_error)?,
+                None => config.with_no_client_auth(),
+            }
+        }
+        (None, Some((certs, key))) => config
+            .with_root_certificates(roots)
+            .with_client_auth_cert(certs, key)
+            .map_err(client_auth_error)?,
+        (None, None) => config.with_root_certificates(roots).with_no_client_auth(),
+    };
+
+    builder
+        .use_preconfigured_tls(config)
+        .build()
+        .map_err(|e| format!("Failed to initialize HTTP client for {}: {}", profile.node_url, e))
+}
+
+/// Profiles keyed by origin, plus a cache of clients built from them.
+pub struct NodeProfiles {
+    path: Option<PathBuf>,
+    profiles: Mutex<HashMap<String, NodeProfile>>,
+    clients: Mutex<HashMap<String, reqwest::Client>>,
+}
+
+impl NodeProfiles {
+    /// Loads profiles from `<app data>/node-profiles.json`; a missing or unreadable file
+    /// yields an empty set.
+    pub fn load(app_data_dir: &Path) -> Self {
+        let path = app_data_dir.join(PROFILES_FILE);
+        let profiles = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| match serde_json::from_str::<Vec<NodeProfile>>(&content) {
+                Ok(list) => Some(list),
+                Err(e) => {
+                    warn!("[Node Profiles] Ignoring unreadable {:?}: {}", path, e);
+                    None
+                }
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|p| p.origin().ok().map(|origin| (origin, p)))
+            .collect();
+        Self {
+            path: Some(path),
+            profiles: Mutex::new(profiles),
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn list(&self) -> Vec<NodeProfile> {
+        let mut list: Vec<NodeProfile> = self.profiles.lock().map(|p| p.values().cloned().collect()).unwrap_or_default();
+        list.sort_by(|a, b| a.node_url.cmp(&b.node_url));
+        list
+    }
+
+    pub fn get(&self, url: &str) -> Option<NodeProfile> {
+        let origin = origin_of(url).ok()?;
+        self.profiles.lock().ok()?.get(&origin).cloned()
+    }
+
+    /// Validates the profile by building a client from it, then stores and persists it.
+    pub fn save(&self, profile: NodeProfile) -> Result<(), String> {
+        let origin = profile.origin()?;
+        let client = build_client(Some(&profile))?;
+        {
+            let mut profiles = self.profiles.lock().map_err(|e| e.to_string())?;
+            profiles.insert(origin.clone(), profile);
+        }
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.insert(origin, client);
+        }
+        self.persist()
+    }
+
+    pub fn remove(&self, node_url: &str) -> Result<bool, String> {
+        let origin = origin_of(node_url)?;
+        let removed = self.profiles.lock().map_err(|e| e.to_string())?.remove(&origin).is_some();
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.remove(&origin);
+        }
+        self.persist()?;
+        Ok(removed)
+    }
+
+    /// Client for requests to `url`, configured from the matching profile if there is one.
+    pub fn client_for(&self, url: &str) -> Result<reqwest::Client, String> {
+        let origin = origin_of(url)?;
+        if let Some(client) = self.clients.lock().ok().and_then(|c| c.get(&origin).cloned()) {
+            return Ok(client);
+        }
+        let client = build_client(self.get(url).as_ref())?;
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.insert(origin, client.clone());
+        }
+        Ok(client)
+    }
+
+    fn persist(&self) -> Result<(), String> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let content = serde_json::to_string_pretty(&self.list())
+            .map_err(|e| format!("Failed to serialize node profiles: {}", e))?;
+        std::fs::write(path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+}
+
+impl Default for NodeProfiles {
+    fn default() -> Self {
+        Self {
+            path: None,
+            profiles: Mutex::new(HashMap::new()),
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn list_node_profiles(profiles: tauri::State<'_, NodeProfiles>) -> Vec<NodeProfile> {
+    profiles.list()
+}
+
+/// Adds or replaces the profile for a node. The CA bundle, fingerprint and client identity
+/// are validated before anything is stored.
+#[tauri::command]
+pub fn save_node_profile(profile: NodeProfile, profiles: tauri::State<'_, NodeProfiles>) -> Result<(), String> {
+    let node_url = profile.node_url.clone();
+    profiles.save(profile)?;
+    info!("[Node Profiles] Saved profile for {}", node_url);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn remove_node_profile(node_url: String, profiles: tauri::State<'_, NodeProfiles>) -> Result<bool, String> {
+    let removed = profiles.remove(&node_url)?;
+    if removed {
+        info!("[Node Profiles] Removed profile for {}", node_url);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fingerprint() {
+        let hex64 = "ab".repeat(32);
+        assert_eq!(parse_fingerprint(&hex64).unwrap(), [0xab; 32]);
+        let with_colons = vec!["AB"; 32].join(":");
+        assert_eq!(parse_fingerprint(&with_colons).unwrap(), [0xab; 32]);
+        assert!(parse_fingerprint("abcd").is_err());
+        assert!(parse_fingerprint(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_profiles_match_by_origin() {
+        let profiles = NodeProfiles::default();
+        profiles
+            .save(NodeProfile {
+                node_url: "https://node.example.com:2528/".to_string(),
+                ca_bundle_path: None,
+                pinned_cert_sha256: Some("00".repeat(32)),
+                client_identity_path: None,
+            })
+            .unwrap();
+        assert!(profiles.get("https://node.example.com:2528/admin-api/health").is_some());
+        assert!(profiles.get("https://NODE.example.com:2528").is_some());
+        assert!(profiles.get("http://node.example.com:2528/").is_none());
+        assert!(profiles.get("https://node.example.com:2529/").is_none());
+        assert!(profiles.remove("https://node.example.com:2528").unwrap());
+        assert!(profiles.list().is_empty());
+    }
+
+    #[test]
+    fn test_tls_options_require_https() {
+        let profile = NodeProfile {
+            node_url: "http://localhost:2528".to_string(),
+            ca_bundle_path: None,
+            pinned_cert_sha256: Some("00".repeat(32)),
+            client_identity_path: None,
+        };
+        assert!(build_client(Some(&profile)).is_err());
+    }
+
+    #[test]
+    fn test_missing_ca_bundle_is_an_error() {
+        let profile = NodeProfile {
+            node_url: "https://node.example.com".to_string(),
+            ca_bundle_path: Some("/nonexistent/ca.pem".to_string()),
+            pinned_cert_sha256: None,
+            client_identity_path: None,
+        };
+        assert!(build_client(Some(&profile)).is_err());
+    }
+}
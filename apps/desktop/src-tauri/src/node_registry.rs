@@ -0,0 +1,299 @@
+//! Per-node run settings: `RUST_LOG` filter, extra environment variables, extra
+//! `merod run` arguments, the sandboxed launch mode, resource limits and whether headless
+//! mode runs the node.
+//!
+//! Settings are stored in `node-registry.json` in the app data directory, keyed by node
+//! directory like merod version pins, and applied by `start_merod`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::node_limits::NodeResourceLimits;
+
+const REGISTRY_FILE: &str = "node-registry.json";
+
+/// Flags `start_merod` always passes; not allowed in `extra_args`.
+const RESERVED_RUN_FLAGS: &[&str] = &["--home", "--node", "--node-name"];
+
+/// Variables set through a dedicated option instead of `env`.
+const RESERVED_ENV_VARS: &[&str] = &["RUST_LOG"];
+
+const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "off"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeRunSettings {
+    /// `RUST_LOG` directives, e.g. `calimero_node=trace,libp2p=warn`. Takes precedence over
+    /// the global debug logs toggle.
+    pub log_filter: Option<String>,
+    pub env: BTreeMap<String, String>,
+    /// Appended after `merod --home <home> --node <name> run`.
+    pub extra_args: Vec<String>,
+    /// Start the node in the hardened launch mode (Linux only, see `merod_sandbox`).
+    pub sandbox: bool,
+    pub limits: NodeResourceLimits,
+    /// Started and kept running by headless mode (`--headless`).
+    pub autostart: bool,
+}
+
+impl NodeRunSettings {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(filter) = &self.log_filter {
+            validate_log_filter(filter)?;
+        }
+        self.limits.validate()?;
+        for (name, value) in &self.env {
+            let valid_name = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_name {
+                return Err(format!("Invalid environment variable name '{}'", name));
+            }
+            if RESERVED_ENV_VARS.contains(&name.as_str()) {
+                return Err(format!("{} is set through the log filter, not as an environment variable", name));
+            }
+            if value.contains(char::is_control) {
+                return Err(format!("The value of {} must not contain control characters such as newlines", name));
+            }
+        }
+        for arg in &self.extra_args {
+            let flag = arg.split('=').next().unwrap_or(arg);
+            if RESERVED_RUN_FLAGS.contains(&flag) {
+                return Err(format!("'{}' cannot be passed in extra args; it is set by the app", flag));
+            }
+            if arg.contains(char::is_control) {
+                return Err("Extra args must not contain control characters such as newlines".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks `RUST_LOG` syntax: comma-separated `level` or `target=level` directives.
+pub fn validate_log_filter(filter: &str) -> Result<(), String> {
+    let invalid = |directive: &str, why: &str| Err(format!("Invalid log filter directive '{}': {}", directive, why));
+    if filter.trim().is_empty() {
+        return Err("The log filter is empty".to_string());
+    }
+    for directive in filter.split(',').map(str::trim) {
+        if directive.is_empty() {
+            return invalid(directive, "empty directive");
+        }
+        let (target, level) = match directive.split_once('=') {
+            Some((target, level)) => (Some(target), level),
+            None if LOG_LEVELS.contains(&directive.to_lowercase().as_str()) => (None, directive),
+            None => (Some(directive), "trace"),
+        };
+        if !LOG_LEVELS.contains(&level.to_lowercase().as_str()) {
+            return invalid(directive, "level must be one of trace, debug, info, warn, error, off");
+        }
+        if let Some(target) = target {
+            if target.is_empty() || !target.chars().all(|c| c.is_ascii_alphanumeric() || "_:-.".contains(c)) {
+                return invalid(directive, "targets are module paths like calimero_node or libp2p::gossipsub");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn registry_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(REGISTRY_FILE)
+}
+
+fn node_key(node_dir: &Path) -> String {
+    node_dir.to_string_lossy().into_owned()
+}
+
+/// Serializes every read-modify-write of the registry file within this process.
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Reads the registry; a missing file is an empty registry. A file that doesn't parse is an
+/// error and is left in place, so no save overwrites the settings in it.
+fn load(app_data_dir: &Path) -> Result<BTreeMap<String, NodeRunSettings>, String> {
+    let path = registry_path(app_data_dir);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(format!("Failed to read {:?}: {}", path, e)),
+    };
+    serde_json::from_str(&content).map_err(|e| {
+        format!(
+            "The node registry {:?} could not be parsed ({}). Fix or remove it; it is left untouched until then.",
+            path, e
+        )
+    })
+}
+
+/// Writes the registry through a temporary file, so a crash mid-write can't truncate it.
+fn save(app_data_dir: &Path, registry: &BTreeMap<String, NodeRunSettings>) -> Result<(), String> {
+    let path = registry_path(app_data_dir);
+    let tmp_path = path.with_extension("json.tmp");
+    let content = serde_json::to_string_pretty(registry).map_err(|e| format!("Failed to serialize node registry: {}", e))?;
+    std::fs::write(&tmp_path, content).map_err(|e| format!("Failed to write {:?}: {}", tmp_path, e))?;
+    std::fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to replace {:?}: {}", path, e))
+}
+
+/// Run settings for the node at `node_dir`; defaults if none were saved.
+pub fn get(app_data_dir: &Path, node_dir: &Path) -> Result<NodeRunSettings, String> {
+    let _guard = REGISTRY_LOCK.lock().unwrap();
+    Ok(load(app_data_dir)?.remove(&node_key(node_dir)).unwrap_or_default())
+}
+
+/// Directories of the nodes headless mode runs.
+pub fn autostart_nodes(app_data_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let _guard = REGISTRY_LOCK.lock().unwrap();
+    Ok(load(app_data_dir)?
+        .into_iter()
+        .filter(|(_, settings)| settings.autostart)
+        .map(|(key, _)| PathBuf::from(key))
+        .collect())
+}
+
+/// Saves (or with `None` or empty settings, removes) the node's run settings.
+pub fn set(app_data_dir: &Path, node_dir: &Path, settings: Option<NodeRunSettings>) -> Result<(), String> {
+    let _guard = REGISTRY_LOCK.lock().unwrap();
+    let mut registry = load(app_data_dir)?;
+    match settings.filter(|s| !s.is_empty()) {
+        Some(settings) => registry.insert(node_key(node_dir), settings),
+        None => registry.remove(&node_key(node_dir)),
+    };
+    save(app_data_dir, &registry)
+}
+
+/// Applies `change` to the node's run settings and saves them, under one lock so a
+/// concurrent save can't be lost in between.
+pub fn update(
+    app_data_dir: &Path,
+    node_dir: &Path,
+    change: impl FnOnce(&mut NodeRunSettings),
+) -> Result<NodeRunSettings, String> {
+    let _guard = REGISTRY_LOCK.lock().unwrap();
+    let mut registry = load(app_data_dir)?;
+    let mut settings = registry.remove(&node_key(node_dir)).unwrap_or_default();
+    change(&mut settings);
+    if !settings.is_empty() {
+        registry.insert(node_key(node_dir), settings.clone());
+    }
+    save(app_data_dir, &registry)?;
+    Ok(settings)
+}
+
+/// Carries a node's settings over to `to` (a rename, clone or move); the old entry is kept
+/// only if `keep_old`.
+pub fn transfer(app_data_dir: &Path, from: &Path, to: Option<&Path>, keep_old: bool) -> Result<(), String> {
+    let _guard = REGISTRY_LOCK.lock().unwrap();
+    let mut registry = load(app_data_dir)?;
+    let Some(settings) = registry.get(&node_key(from)).cloned() else { return Ok(()) };
+    if let Some(to) = to {
+        registry.insert(node_key(to), settings);
+    }
+    if !keep_old {
+        registry.remove(&node_key(from));
+    }
+    save(app_data_dir, &registry)
+}
+
+#[tauri::command]
+pub fn get_node_run_settings(
+    node_name: String,
+    home_dir: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<NodeRunSettings, String> {
+    let home = crate::resolve_calimero_home(home_dir)?;
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    get(&app_data_dir, &home.join(node_name))
+}
+
+/// Validates and saves the node's run settings. They apply the next time the node starts.
+#[tauri::command]
+pub fn set_node_run_settings(
+    node_name: String,
+    home_dir: Option<String>,
+    settings: NodeRunSettings,
+    app_handle: tauri::AppHandle,
+) -> Result<NodeRunSettings, String> {
+    settings.validate()?;
+    let home = crate::resolve_calimero_home(home_dir)?;
+    let node_dir = crate::node_ops::existing_node_dir(&home, &node_name)?;
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    set(&app_data_dir, &node_dir, Some(settings.clone()))?;
+    info!("[Node Registry] Saved run settings for '{}'", node_name);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_filter_validation() {
+        assert!(validate_log_filter("info").is_ok());
+        assert!(validate_log_filter("calimero_node=trace,libp2p=warn").is_ok());
+        assert!(validate_log_filter("warn, libp2p::gossipsub=DEBUG").is_ok());
+        assert!(validate_log_filter("calimero_node").is_ok());
+        assert!(validate_log_filter("").is_err());
+        assert!(validate_log_filter("libp2p=loud").is_err());
+        assert!(validate_log_filter("info,,debug").is_err());
+        assert!(validate_log_filter("a b=info").is_err());
+    }
+
+    #[test]
+    fn test_reserved_flags_and_env_rejected() {
+        let settings = |env: &[(&str, &str)], args: &[&str]| NodeRunSettings {
+            log_filter: None,
+            env: env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            extra_args: args.iter().map(|a| a.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(settings(&[("MEROD_FLAG", "1")], &["--some-flag", "value"]).validate().is_ok());
+        assert!(settings(&[], &["--home=/tmp"]).validate().is_err());
+        assert!(settings(&[], &["--node", "other"]).validate().is_err());
+        assert!(settings(&[("RUST_LOG", "debug")], &[]).validate().is_err());
+        assert!(settings(&[("1BAD", "x")], &[]).validate().is_err());
+        assert!(settings(&[("A", "1\nExecStartPre=/bin/sh")], &[]).validate().is_err());
+        assert!(settings(&[], &["--flag\r\nExecStartPre=/bin/sh"]).validate().is_err());
+    }
+
+    #[test]
+    fn test_registry_round_trip_and_transfer() {
+        let dir = std::env::temp_dir().join(format!("calimero-registry-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (node1, node2) = (Path::new("/home/u/.calimero/node1"), Path::new("/home/u/.calimero/node2"));
+        let settings = NodeRunSettings { log_filter: Some("debug".to_string()), ..Default::default() };
+
+        set(&dir, node1, Some(settings.clone())).unwrap();
+        assert_eq!(get(&dir, node1).unwrap(), settings);
+        transfer(&dir, node1, Some(node2), false).unwrap();
+        assert!(get(&dir, node1).unwrap().is_empty());
+        assert_eq!(get(&dir, node2).unwrap(), settings);
+        assert!(autostart_nodes(&dir).unwrap().is_empty());
+        set(&dir, node1, Some(NodeRunSettings { autostart: true, ..Default::default() })).unwrap();
+        assert_eq!(autostart_nodes(&dir).unwrap(), vec![node1.to_path_buf()]);
+        set(&dir, node1, None).unwrap();
+        set(&dir, node2, Some(NodeRunSettings::default())).unwrap();
+        assert!(load(&dir).unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unparseable_registry_is_not_overwritten() {
+        let dir = std::env::temp_dir().join(format!("calimero-registry-corrupt-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let node = Path::new("/home/u/.calimero/node1");
+        std::fs::write(registry_path(&dir), "{ not json").unwrap();
+
+        assert!(get(&dir, node).is_err());
+        assert!(autostart_nodes(&dir).is_err());
+        assert!(set(&dir, node, Some(NodeRunSettings { autostart: true, ..Default::default() })).is_err());
+        assert!(update(&dir, node, |s| s.autostart = true).is_err());
+        assert_eq!(std::fs::read_to_string(registry_path(&dir)).unwrap(), "{ not json");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,471 @@
+//! Running nodes as `systemd --user` services, so they keep running without the app.
+//!
+//! The generated unit runs the node's merod (bundled or pinned, copied into the app data
+//! directory so app updates and AppImage mounts don't pull it away) with the same
+//! `--home <home> --node <name> run` invocation, environment and `merod.log` redirection as
+//! `start_merod`. The node's run settings (log filter, env, extra args, limits, sandbox) are
+//! translated into unit directives at install time; reinstall to pick up later changes.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde::Serialize;
+use sha2::Digest;
+
+use crate::node_limits::NodeResourceLimits;
+use crate::node_registry::NodeRunSettings;
+
+const UNIT_PREFIX: &str = "calimero-node-";
+/// Section identifying the node a generated unit runs (systemd ignores `X-` sections).
+const METADATA_SECTION: &str = "[X-Calimero]";
+/// Properties read by `systemctl --user show`.
+const SHOW_PROPERTIES: &str =
+    "LoadState,ActiveState,SubState,UnitFileState,MainPID,NRestarts,ExecMainStatus,ActiveEnterTimestamp";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeServiceStatus {
+    pub node_name: String,
+    pub home_dir: PathBuf,
+    pub unit: String,
+    pub unit_path: PathBuf,
+    pub installed: bool,
+    /// systemd's UnitFileState is `enabled`: the node starts with the user's session.
+    pub enabled: bool,
+    /// `active`, `inactive`, `failed`, `activating`, ...
+    pub active_state: Option<String>,
+    pub sub_state: Option<String>,
+    pub main_pid: Option<u32>,
+    pub restarts: Option<u32>,
+    pub exit_status: Option<i32>,
+    pub active_since: Option<String>,
+    /// Whether the user's services keep running after logout (`loginctl enable-linger`).
+    pub linger: Option<bool>,
+}
+
+/// `calimero-node-<node>.service`; nodes outside the default home get a suffix derived from
+/// the home path so equally named nodes in different homes don't collide.
+pub fn unit_name(home_dir: &Path, node_name: &str) -> String {
+    let default_home = dirs::home_dir().map(|h| h.join(".calimero"));
+    if default_home.as_deref() == Some(home_dir) {
+        return format!("{}{}.service", UNIT_PREFIX, node_name);
+    }
+    let digest = sha2::Sha256::digest(home_dir.to_string_lossy().as_bytes());
+    format!("{}{}-{}.service", UNIT_PREFIX, node_name, &hex::encode(digest)[..8])
+}
+
+fn unit_dir() -> Result<PathBuf, String> {
+    Ok(dirs::config_dir().ok_or("Failed to get the config directory")?.join("systemd").join("user"))
+}
+
+/// Quotes a word for `ExecStart=`/`Environment=`; `%` and `$` are escaped so systemd doesn't
+/// expand them, and control characters are written as `\xNN` so they can't end the line.
+pub fn systemd_quote(word: &str) -> String {
+    let escaped = word.replace('%', "%%").replace('$', "$$");
+    if !escaped.is_empty()
+        && !escaped.contains(|c: char| c.is_whitespace() || c.is_control() || "\"'\\;".contains(c))
+    {
+        return escaped;
+    }
+    let mut quoted = String::from("\"");
+    for c in escaped.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            c if c.is_control() => quoted.push_str(&format!("\\x{:02x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Everything a unit is generated from.
+pub struct UnitSpec<'a> {
+    pub binary: &'a Path,
+    pub home_dir: &'a Path,
+    pub node_name: &'a str,
+    pub log_path: &'a Path,
+    pub settings: &'a NodeRunSettings,
+}
+
+fn limit_directives(limits: &NodeResourceLimits) -> Vec<String> {
+    let mut directives = Vec::new();
+    if let Some(mb) = limits.max_memory_mb {
+        directives.push(format!("MemoryMax={}M", mb));
+        directives.push("MemorySwapMax=0".to_string());
+    }
+    if let Some(percent) = limits.cpu_percent {
+        directives.push(format!("CPUQuota={}%", percent));
+    }
+    if let Some(files) = limits.max_open_files {
+        directives.push(format!("LimitNOFILE={}", files));
+    }
+    directives
+}
+
+pub fn render_unit(spec: &UnitSpec) -> String {
+    let node_dir = spec.home_dir.join(spec.node_name);
+    let log = spec.log_path.to_string_lossy().replace('%', "%%");
+    let mut exec = vec![
+        systemd_quote(&spec.binary.to_string_lossy()),
+        "--home".to_string(),
+        systemd_quote(&spec.home_dir.to_string_lossy()),
+        "--node".to_string(),
+        systemd_quote(spec.node_name),
+        "run".to_string(),
+    ];
+    exec.extend(spec.settings.extra_args.iter().map(|a| systemd_quote(a)));
+
+    let mut env = vec![
+        ("RUST_LOG".to_string(), spec.settings.log_filter.clone().unwrap_or_else(|| "info".to_string())),
+        ("CLICOLOR_FORCE".to_string(), "1".to_string()),
+        ("FORCE_COLOR".to_string(), "1".to_string()),
+    ];
+    env.extend(spec.settings.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    let mut unit = format!(
+        "# Generated by Calimero Desktop. Reinstall the service from the app instead of editing.\n\
+         [Unit]\n\
+         Description=Calimero node {node} ({home})\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={exec}\n\
+         WorkingDirectory={dir}\n\
+         StandardOutput=append:{log}\n\
+         StandardError=append:{log}\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         TimeoutStopSec=30\n",
+        node = spec.node_name,
+        home = spec.home_dir.display().to_string().replace('%', "%%"),
+        exec = exec.join(" "),
+        dir = systemd_quote(&node_dir.to_string_lossy()),
+    );
+    for (key, value) in env {
+        unit.push_str(&format!("Environment={}\n", systemd_quote(&format!("{}={}", key, value))));
+    }
+    for directive in limit_directives(&spec.settings.limits) {
+        unit.push_str(&directive);
+        unit.push('\n');
+    }
+    if spec.settings.sandbox {
+        // In the user manager these imply a user namespace (PrivateUsers=yes)
+        unit.push_str(&format!(
+            "NoNewPrivileges=yes\nLimitCORE=0\nPrivateTmp=yes\nProtectSystem=strict\nProtectHome=tmpfs\n\
+             BindPaths={}\nBindReadOnlyPaths={}\n",
+            systemd_quote(&node_dir.to_string_lossy()),
+            systemd_quote(&spec.binary.to_string_lossy()),
+        ));
+    }
+    unit.push_str(&format!(
+        "\n[Install]\nWantedBy=default.target\n\n{}\nHome={}\nNode={}\n",
+        METADATA_SECTION,
+        spec.home_dir.display(),
+        spec.node_name
+    ));
+    unit
+}
+
+/// Home and node name of a unit generated by [`render_unit`].
+pub fn unit_metadata(unit: &str) -> Option<(PathBuf, String)> {
+    let section = unit.split_once(METADATA_SECTION)?.1;
+    let value = |key: &str| {
+        section
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('=').map(str::to_string))
+    };
+    Some((PathBuf::from(value("Home")?), value("Node")?))
+}
+
+/// `Key=Value` lines from `systemctl show`.
+pub fn parse_show(output: &str) -> BTreeMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn systemctl(args: &[&str]) -> Result<String, String> {
+    if !cfg!(target_os = "linux") {
+        return Err("Node services are only available on Linux (systemd)".to_string());
+    }
+    let output = std::process::Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run systemctl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "systemctl --user {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(unix)]
+fn linger_enabled() -> Option<bool> {
+    let uid = unsafe { libc::getuid() };
+    let output = std::process::Command::new("loginctl")
+        .args(["show-user", &uid.to_string(), "--property=Linger", "--value"])
+        .output()
+        .ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim() == "yes")
+}
+
+#[cfg(not(unix))]
+fn linger_enabled() -> Option<bool> {
+    None
+}
+
+fn status_of(home_dir: &Path, node_name: &str) -> Result<NodeServiceStatus, String> {
+    let unit = unit_name(home_dir, node_name);
+    let unit_path = unit_dir()?.join(&unit);
+    let installed = unit_path.exists();
+    let show = match installed {
+        true => parse_show(&systemctl(&["show", &unit, "--property", SHOW_PROPERTIES])?),
+        false => BTreeMap::new(),
+    };
+    let field = |key: &str| show.get(key).filter(|v| !v.is_empty()).cloned();
+    Ok(NodeServiceStatus {
+        node_name: node_name.to_string(),
+        home_dir: home_dir.to_path_buf(),
+        installed,
+        enabled: field("UnitFileState").as_deref() == Some("enabled"),
+        active_state: field("ActiveState"),
+        sub_state: field("SubState"),
+        main_pid: field("MainPID").and_then(|p| p.parse().ok()).filter(|p| *p != 0),
+        restarts: field("NRestarts").and_then(|n| n.parse().ok()),
+        exit_status: field("ExecMainStatus").and_then(|s| s.parse().ok()),
+        active_since: field("ActiveEnterTimestamp"),
+        linger: if installed { linger_enabled() } else { None },
+        unit,
+        unit_path,
+    })
+}
+
+/// Copies the node's merod into `<app data>/services` (named by its hash) so the unit keeps
+/// working when the app is updated or its AppImage unmounted.
+fn stable_binary(app_data_dir: &Path, binary: &Path) -> Result<PathBuf, String> {
+    let hash = crate::merod_manager::sha256_file(binary)?;
+    let dir = app_data_dir.join("services");
+    let target = dir.join(format!("merod-{}", &hash[..12]));
+    if !target.exists() {
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+        let staging = dir.join(format!(".merod-{}.tmp", &hash[..12]));
+        std::fs::copy(binary, &staging).map_err(|e| format!("Failed to copy merod to {:?}: {}", staging, e))?;
+        std::fs::rename(&staging, &target).map_err(|e| format!("Failed to install {:?}: {}", target, e))?;
+    }
+    Ok(target)
+}
+
+/// Generates, enables and (unless `start` is false) starts a `systemd --user` unit for the node.
+/// The node must not be running in the app.
+#[tauri::command]
+pub async fn install_node_service(
+    node_name: String,
+    home_dir: Option<String>,
+    start: Option<bool>,
+    app_handle: tauri::AppHandle,
+) -> Result<NodeServiceStatus, String> {
+    let home = crate::resolve_calimero_home(home_dir)?;
+    let node_dir = crate::node_ops::existing_node_dir(&home, &node_name)?;
+    if !node_dir.join("config.toml").exists() {
+        return Err(format!("Node '{}' is not initialized", node_name));
+    }
+    // Paths are also written unquoted (log paths, metadata), where a newline would end the line
+    if node_dir.to_string_lossy().contains(char::is_control) {
+        return Err(format!("{:?} contains control characters and can't be used in a service unit", node_dir));
+    }
+    let start = start.unwrap_or(true);
+    if start {
+        crate::node_ops::ensure_not_running(&home, &node_name)?;
+    }
+
+    let app_data_dir = crate::get_app_data_dir(&app_handle)?;
+    let settings = crate::node_registry::get(&app_data_dir, &node_dir)?;
+    settings.validate()?;
+    let binary = crate::get_node_merod_binary(&app_handle, &home, &node_name)?;
+    let binary = stable_binary(&app_data_dir, &binary)?;
+
+    let log_dir = node_dir.join("logs");
+    std::fs::create_dir_all(&log_dir).map_err(|e| format!("Failed to create logs directory: {}", e))?;
+    let unit = render_unit(&UnitSpec {
+        binary: &binary,
+        home_dir: &home,
+        node_name: &node_name,
+        log_path: &log_dir.join("merod.log"),
+        settings: &settings,
+    });
+    let unit_dir = unit_dir()?;
+    std::fs::create_dir_all(&unit_dir).map_err(|e| format!("Failed to create {:?}: {}", unit_dir, e))?;
+    let unit_name = unit_name(&home, &node_name);
+    let unit_path = unit_dir.join(&unit_name);
+    std::fs::write(&unit_path, unit).map_err(|e| format!("Failed to write {:?}: {}", unit_path, e))?;
+
+    systemctl(&["daemon-reload"])?;
+    if start {
+        systemctl(&["enable", "--now", &unit_name])?;
+    } else {
+        systemctl(&["enable", &unit_name])?;
+    }
+    info!("[Node Service] Installed {} for node '{}' ({:?})", unit_name, node_name, home);
+    status_of(&home, &node_name)
+}
+
+/// Stops and disables the node's unit and removes its unit file. Returns false if no unit
+/// was installed.
+pub fn remove_unit(home_dir: &Path, node_name: &str) -> Result<bool, String> {
+    let unit_name = unit_name(home_dir, node_name);
+    let unit_path = unit_dir()?.join(&unit_name);
+    if !unit_path.exists() {
+        return Ok(false);
+    }
+    systemctl(&["disable", "--now", &unit_name])?;
+    std::fs::remove_file(&unit_path).map_err(|e| format!("Failed to remove {:?}: {}", unit_path, e))?;
+    systemctl(&["daemon-reload"])?;
+    // Forget a failed state so the unit disappears from `systemctl --user list-units`
+    let _ = systemctl(&["reset-failed", &unit_name]);
+    info!("[Node Service] Uninstalled {} for node '{}'", unit_name, node_name);
+    Ok(true)
+}
+
+/// Names of the nodes in `home_dir` that have a unit in `unit_dir`, from the units' metadata.
+fn nodes_with_units(unit_dir: &Path, home_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(unit_dir) else { return Vec::new() };
+    let mut nodes: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(UNIT_PREFIX))
+        .filter_map(|entry| std::fs::read_to_string(entry.path()).ok().and_then(|u| unit_metadata(&u)))
+        .filter(|(unit_home, _)| crate::node_ops::same_dir(unit_home, home_dir))
+        .map(|(_, node_name)| node_name)
+        .collect();
+    nodes.sort();
+    nodes
+}
+
+/// Refuses to move nodes out from under their units: a unit keeps pointing at the old
+/// `--home`/`--node` and restarts forever. `node_name` limits the check to one node.
+pub fn ensure_no_units(home_dir: &Path, node_name: Option<&str>) -> Result<(), String> {
+    check_no_units(&unit_dir()?, home_dir, node_name)
+}
+
+fn check_no_units(unit_dir: &Path, home_dir: &Path, node_name: Option<&str>) -> Result<(), String> {
+    let nodes: Vec<String> = nodes_with_units(unit_dir, home_dir)
+        .into_iter()
+        .filter(|node| node_name.is_none() || node_name == Some(node.as_str()))
+        .collect();
+    if nodes.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "{} installed as a service ({}). Uninstall the service first, then install it again afterwards.",
+        if nodes.len() == 1 { "This node is" } else { "These nodes are" },
+        nodes.join(", ")
+    ))
+}
+
+/// Stops and disables the node's unit and removes its unit file.
+#[tauri::command]
+pub async fn uninstall_node_service(node_name: String, home_dir: Option<String>) -> Result<NodeServiceStatus, String> {
+    let home = crate::resolve_calimero_home(home_dir)?;
+    crate::node_init::validate_node_name(&node_name)?;
+    if !remove_unit(&home, &node_name)? {
+        return Err(format!("Node '{}' is not installed as a service", node_name));
+    }
+    status_of(&home, &node_name)
+}
+
+#[tauri::command]
+pub async fn get_node_service_status(node_name: String, home_dir: Option<String>) -> Result<NodeServiceStatus, String> {
+    let home = crate::resolve_calimero_home(home_dir)?;
+    crate::node_init::validate_node_name(&node_name)?;
+    status_of(&home, &node_name)
+}
+
+/// Services installed for nodes in `home_dir`. Empty where systemd is not available.
+#[tauri::command]
+pub async fn list_node_services(home_dir: Option<String>) -> Result<Vec<NodeServiceStatus>, String> {
+    let home = crate::resolve_calimero_home(home_dir)?;
+    nodes_with_units(&unit_dir()?, &home).iter().map(|node_name| status_of(&home, node_name)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_systemd_quote() {
+        assert_eq!(systemd_quote("/usr/bin/merod"), "/usr/bin/merod");
+        assert_eq!(systemd_quote("/home/a b/.calimero"), "\"/home/a b/.calimero\"");
+        assert_eq!(systemd_quote("50%"), "50%%");
+        assert_eq!(systemd_quote("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(systemd_quote(""), "\"\"");
+        assert_eq!(systemd_quote("a\nExecStartPre=/bin/sh"), "\"a\\x0aExecStartPre=/bin/sh\"");
+    }
+
+    #[test]
+    fn test_render_unit_round_trips_metadata() {
+        let settings = NodeRunSettings {
+            log_filter: Some("calimero_node=debug".to_string()),
+            extra_args: vec!["--flag".to_string()],
+            limits: NodeResourceLimits { max_memory_mb: Some(1024), cpu_percent: Some(150), max_open_files: None },
+            ..Default::default()
+        };
+        let home = Path::new("/home/u/my nodes");
+        let unit = render_unit(&UnitSpec {
+            binary: Path::new("/data/services/merod-abc"),
+            home_dir: home,
+            node_name: "node1",
+            log_path: &home.join("node1/logs/merod.log"),
+            settings: &settings,
+        });
+        assert!(unit.contains("ExecStart=/data/services/merod-abc --home \"/home/u/my nodes\" --node node1 run --flag\n"));
+        assert!(unit.contains("StandardOutput=append:/home/u/my nodes/node1/logs/merod.log\n"));
+        assert!(unit.contains("Environment=RUST_LOG=calimero_node=debug\n"));
+        assert!(unit.contains("MemoryMax=1024M\n") && unit.contains("CPUQuota=150%\n"));
+        assert!(!unit.contains("NoNewPrivileges"));
+        assert_eq!(unit_metadata(&unit), Some((home.to_path_buf(), "node1".to_string())));
+    }
+
+    #[test]
+    fn test_installed_units_block_moving_their_nodes() {
+        let root = std::env::temp_dir().join(format!("calimero-units-{}", std::process::id()));
+        let (unit_dir, home) = (root.join("systemd/user"), root.join("my nodes"));
+        std::fs::create_dir_all(&unit_dir).unwrap();
+        std::fs::create_dir_all(&home).unwrap();
+        let unit = render_unit(&UnitSpec {
+            binary: Path::new("/data/services/merod-abc"),
+            home_dir: &home,
+            node_name: "node1",
+            log_path: &home.join("node1/logs/merod.log"),
+            settings: &NodeRunSettings::default(),
+        });
+        std::fs::write(unit_dir.join(unit_name(&home, "node1")), unit).unwrap();
+
+        assert!(check_no_units(&unit_dir, &home, None).unwrap_err().contains("node1"));
+        assert!(check_no_units(&unit_dir, &home, Some("node1")).is_err());
+        assert!(check_no_units(&unit_dir, &home, Some("node2")).is_ok());
+        assert!(check_no_units(&unit_dir, &root, None).is_ok());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_parse_show() {
+        let show = parse_show("ActiveState=active\nSubState=running\nMainPID=4242\nActiveEnterTimestamp=\n");
+        assert_eq!(show.get("MainPID").map(String::as_str), Some("4242"));
+        assert_eq!(show.get("ActiveEnterTimestamp").map(String::as_str), Some(""));
+    }
+
+    #[test]
+    fn test_unit_name_includes_home_suffix_outside_default_home() {
+        assert!(unit_name(Path::new("/srv/calimero"), "node1").starts_with("calimero-node-node1-"));
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(unit_name(&home.join(".calimero"), "node1"), "calimero-node-node1.service");
+        }
+    }
+}
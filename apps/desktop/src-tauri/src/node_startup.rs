@@ -0,0 +1,263 @@
+//! Observable node start: progress stages for `start_merod` and waiting until the node serves.
+//!
+//! `spawned` is emitted by `start_merod` itself, `listening` once the server port accepts TCP
+//! connections and `healthy` once the health endpoint answers with a 2xx status.
+//! `config_loaded` is a best-effort hint recognised in the lines merod appends to its log
+//! file; the other stages don't depend on merod's log wording.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use log::info;
+use serde::Serialize;
+use tauri::Manager;
+
+/// Emitted to the main window with `StartProgress` each time a starting node reaches a stage.
+pub const START_PROGRESS_EVENT: &str = "merod-start-progress";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_WAIT_SECS: u64 = 600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartStage {
+    Spawned,
+    ConfigLoaded,
+    Listening,
+    Healthy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartProgress {
+    pub node_name: String,
+    pub pid: u32,
+    pub stage: StartStage,
+    pub elapsed_ms: u64,
+    /// The log line that marked the stage, if it came from the log.
+    pub detail: Option<String>,
+}
+
+/// What `start_merod` returns once the node is running (and, if asked, healthy).
+#[derive(Debug, Clone, Serialize)]
+pub struct MerodStartResult {
+    pub pid: u32,
+    pub node_name: String,
+    pub server_port: u16,
+    pub swarm_port: u16,
+    pub node_url: String,
+    pub admin_api_url: String,
+    pub version: Option<String>,
+    /// Furthest stage reached before returning.
+    pub stage: StartStage,
+    /// Milliseconds from spawn until the health check passed; None if start didn't wait.
+    pub time_to_ready_ms: Option<u64>,
+    pub warning: Option<String>,
+}
+
+pub fn node_url(server_port: u16) -> String {
+    format!("http://localhost:{}", server_port)
+}
+
+/// Whether the local node on `server_port` answers its health endpoint with a 2xx status.
+/// For callers without the app's node profiles (headless mode and the CLI).
+pub async fn is_healthy(client: &reqwest::Client, server_port: u16) -> bool {
+    client
+        .get(format!("{}/health", node_url(server_port)))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .is_ok_and(|response| response.status().is_success())
+}
+
+/// Whether something accepts TCP connections on the local `port`.
+pub async fn port_accepts(port: u16) -> bool {
+    let connect = tokio::net::TcpStream::connect(("127.0.0.1", port));
+    matches!(tokio::time::timeout(Duration::from_millis(200), connect).await, Ok(Ok(_)))
+}
+
+fn strip_ansi(line: &str) -> String {
+    static ANSI: OnceLock<regex::Regex> = OnceLock::new();
+    ANSI.get_or_init(|| regex::Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").expect("valid ANSI regex"))
+        .replace_all(line, "")
+        .into_owned()
+}
+
+/// The stage a merod log line marks, if any. Only `config_loaded` is taken from the log.
+pub fn stage_for_line(line: &str) -> Option<StartStage> {
+    let line = strip_ansi(line).to_lowercase();
+    ["loaded config", "config loaded", "loading config"]
+        .iter()
+        .any(|m| line.contains(m))
+        .then_some(StartStage::ConfigLoaded)
+}
+
+/// Follows a log file from a byte offset, returning only complete lines.
+pub struct LogFollower {
+    offset: u64,
+}
+
+impl LogFollower {
+    pub fn new(offset: u64) -> Self {
+        Self { offset }
+    }
+
+    pub fn new_lines(&mut self, path: &Path) -> Vec<String> {
+        let Ok(mut file) = std::fs::File::open(path) else { return Vec::new() };
+        if file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return Vec::new();
+        }
+        let mut buf = Vec::new();
+        if file.read_to_end(&mut buf).is_err() {
+            return Vec::new();
+        }
+        let Some(end) = buf.iter().rposition(|&b| b == b'\n') else { return Vec::new() };
+        self.offset += end as u64 + 1;
+        String::from_utf8_lossy(&buf[..end]).lines().map(str::to_string).collect()
+    }
+}
+
+/// Emits progress for one starting node, only ever moving forward through the stages.
+pub struct StartTracker {
+    app_handle: tauri::AppHandle,
+    node_name: String,
+    pid: u32,
+    started: Instant,
+    stage: StartStage,
+}
+
+impl StartTracker {
+    /// Starts tracking a node that has just been spawned and emits `spawned`.
+    pub fn spawned(app_handle: &tauri::AppHandle, node_name: &str, pid: u32, started: Instant) -> Self {
+        let tracker = Self {
+            app_handle: app_handle.clone(),
+            node_name: node_name.to_string(),
+            pid,
+            started,
+            stage: StartStage::Spawned,
+        };
+        tracker.emit(StartStage::Spawned, None);
+        tracker
+    }
+
+    pub fn stage(&self) -> StartStage {
+        self.stage
+    }
+
+    pub fn elapsed_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    pub fn advance(&mut self, stage: StartStage, detail: Option<String>) {
+        if stage > self.stage {
+            self.stage = stage;
+            self.emit(stage, detail);
+        }
+    }
+
+    fn emit(&self, stage: StartStage, detail: Option<String>) {
+        info!("[Merod] Node '{}' {:?} after {} ms", self.node_name, stage, self.elapsed_ms());
+        let _ = self.app_handle.emit_to(
+            crate::capabilities::MAIN_WINDOW_LABEL,
+            START_PROGRESS_EVENT,
+            StartProgress {
+                node_name: self.node_name.clone(),
+                pid: self.pid,
+                stage,
+                elapsed_ms: self.elapsed_ms(),
+                detail,
+            },
+        );
+    }
+}
+
+fn is_running(merod_state: &crate::MerodState, pid: u32) -> bool {
+    merod_state.lock().map(|s| s.iter().any(|p| p.pid == pid)).unwrap_or(false)
+}
+
+/// Follows the node's log and polls its health endpoint until it is healthy, it exits, or
+/// `timeout_secs` pass. Polls quietly with `is_healthy` rather than the logging health command.
+pub async fn wait_until_ready(
+    merod_state: &crate::MerodState,
+    tracker: &mut StartTracker,
+    log_path: &Path,
+    mut log: LogFollower,
+    server_port: u16,
+    timeout_secs: u64,
+) -> Result<u64, String> {
+    let timeout = Duration::from_secs(timeout_secs.min(MAX_WAIT_SECS));
+    let deadline = Instant::now() + timeout;
+    let client = reqwest::Client::new();
+    loop {
+        for line in log.new_lines(log_path) {
+            if let Some(stage) = stage_for_line(&line) {
+                tracker.advance(stage, Some(strip_ansi(&line)));
+            }
+        }
+        if tracker.stage() < StartStage::Listening && port_accepts(server_port).await {
+            tracker.advance(StartStage::Listening, None);
+        }
+        if is_healthy(&client, server_port).await {
+            tracker.advance(StartStage::Healthy, None);
+            return Ok(tracker.elapsed_ms());
+        }
+        if !is_running(merod_state, tracker.pid) {
+            return Err(format!(
+                "Merod (PID {}) exited while starting, after reaching '{:?}'. Check merod logs for details.",
+                tracker.pid,
+                tracker.stage()
+            ));
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "Node did not become healthy within {} s (last stage: {:?}). It is still running as PID {}.",
+                timeout.as_secs(),
+                tracker.stage(),
+                tracker.pid
+            ));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_for_line() {
+        assert_eq!(
+            stage_for_line("\x1b[32mINFO\x1b[0m merod::cli: Loaded config from \"/home/u/.calimero/node1\""),
+            Some(StartStage::ConfigLoaded)
+        );
+        // Listening comes from the server port, not from log wording
+        assert_eq!(stage_for_line("INFO calimero_server: Server listening on 127.0.0.1:2528"), None);
+        assert_eq!(stage_for_line("INFO libp2p: dialing peer"), None);
+        assert!(StartStage::Healthy > StartStage::Listening);
+    }
+
+    #[tokio::test]
+    async fn test_port_accepts() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(port_accepts(port).await);
+        drop(listener);
+        assert!(!port_accepts(port).await);
+    }
+
+    #[test]
+    fn test_log_follower_returns_complete_lines_once() {
+        let path = std::env::temp_dir().join(format!("calimero-start-{}.log", std::process::id()));
+        std::fs::write(&path, "old line\n").unwrap();
+        let mut follower = LogFollower::new(std::fs::metadata(&path).unwrap().len());
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        std::io::Write::write_all(&mut file, b"first\nsecond\npart").unwrap();
+        assert_eq!(follower.new_lines(&path), ["first", "second"]);
+        std::io::Write::write_all(&mut file, b"ial\n").unwrap();
+        assert_eq!(follower.new_lines(&path), ["partial"]);
+        assert!(follower.new_lines(&path).is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
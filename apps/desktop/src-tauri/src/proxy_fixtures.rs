@@ -0,0 +1,329 @@
+//! Record-and-replay mode for `proxy_http_request`.
+//!
+//! In record mode every proxied request/response pair is saved to a fixture directory. In
+//! replay mode responses are served from that directory and the network is never touched,
+//! so app windows can run against a canned node on CI or offline.
+//!
+//! Fixtures are matched on method, path, query (order-independent) and a SHA-256 of the
+//! request body. Scheme, host and port are ignored so recordings from one node port replay
+//! against any other.
+//!
+//! Recorded responses never hold credentials: sensitive headers are redacted the same way as
+//! in the traffic inspector, and credential fields in JSON bodies are replaced.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{proxy_inspector, HttpRequest, HttpResponse};
+
+/// JSON fields in response bodies whose values are credentials.
+const CREDENTIAL_FIELDS: &[&str] = &["access_token", "refresh_token", "accesstoken", "refreshtoken", "token", "password", "secret"];
+const REDACTED: &str = "[REDACTED]";
+
+/// Selects the mode at startup, e.g. `CALIMERO_PROXY_MODE=replay` on CI.
+pub const PROXY_MODE_ENV: &str = "CALIMERO_PROXY_MODE";
+/// Fixture directory used with `PROXY_MODE_ENV`.
+pub const PROXY_FIXTURES_ENV: &str = "CALIMERO_PROXY_FIXTURES";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyMode {
+    /// Forward requests to the node (default).
+    #[default]
+    Live,
+    /// Forward requests and save each pair to the fixture directory.
+    Record,
+    /// Serve responses from the fixture directory only.
+    Replay,
+}
+
+impl std::str::FromStr for ProxyMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "live" => Ok(ProxyMode::Live),
+            "record" => Ok(ProxyMode::Record),
+            "replay" => Ok(ProxyMode::Replay),
+            other => Err(format!("Unknown proxy mode '{}'. Expected live, record or replay.", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProxyMockConfig {
+    pub mode: ProxyMode,
+    pub fixture_dir: Option<PathBuf>,
+}
+
+#[derive(Default)]
+pub struct ProxyMockState(Mutex<ProxyMockConfig>);
+
+impl ProxyMockState {
+    /// Reads `CALIMERO_PROXY_MODE` / `CALIMERO_PROXY_FIXTURES`, falling back to live mode.
+    pub fn from_env() -> Self {
+        let mode = std::env::var(PROXY_MODE_ENV)
+            .ok()
+            .and_then(|m| m.parse::<ProxyMode>().map_err(|e| log::warn!("[Proxy Fixtures] {}", e)).ok())
+            .unwrap_or_default();
+        let fixture_dir = std::env::var(PROXY_FIXTURES_ENV).ok().map(PathBuf::from);
+        if mode != ProxyMode::Live {
+            info!("[Proxy Fixtures] Starting in {:?} mode (fixtures: {:?})", mode, fixture_dir);
+        }
+        Self(Mutex::new(ProxyMockConfig { mode, fixture_dir }))
+    }
+
+    pub fn config(&self) -> ProxyMockConfig {
+        self.0.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    pub fn set(&self, config: ProxyMockConfig) {
+        if let Ok(mut current) = self.0.lock() {
+            *current = config;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Fixture {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    body_sha256: String,
+    recorded_at: String,
+    response: HttpResponse,
+}
+
+struct FixtureKey {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    body_sha256: String,
+}
+
+impl FixtureKey {
+    fn from_request(request: &HttpRequest) -> Result<Self, String> {
+        let parsed = url::Url::parse(&request.url)
+            .map_err(|e| format!("Failed to parse URL '{}': {}", request.url, e))?;
+        let mut query: Vec<(String, String)> = parsed
+            .query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        query.sort();
+        Ok(Self {
+            method: request.method.to_uppercase(),
+            path: parsed.path().to_string(),
+            query,
+            body_sha256: hex::encode(Sha256::digest(request.body.as_deref().unwrap_or("").as_bytes())),
+        })
+    }
+
+    /// File name of the fixture: a hash over every matched component.
+    fn file_name(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.method.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(self.path.as_bytes());
+        hasher.update(b"\n");
+        for (k, v) in &self.query {
+            hasher.update(k.as_bytes());
+            hasher.update(b"=");
+            hasher.update(v.as_bytes());
+            hasher.update(b"&");
+        }
+        hasher.update(b"\n");
+        hasher.update(self.body_sha256.as_bytes());
+        format!("{}.json", hex::encode(hasher.finalize()))
+    }
+}
+
+/// Serves a recorded response for `request`. Errors if there is no matching fixture.
+pub fn replay(fixture_dir: &Path, request: &HttpRequest) -> Result<HttpResponse, String> {
+    let key = FixtureKey::from_request(request)?;
+    let path = fixture_dir.join(key.file_name());
+    let content = std::fs::read_to_string(&path).map_err(|_| {
+        format!(
+            "No recorded fixture for {} {} in {:?}. Replay mode never contacts the node; record this request first.",
+            key.method, key.path, fixture_dir
+        )
+    })?;
+    let fixture: Fixture = serde_json::from_str(&content)
+        .map_err(|e| format!("Corrupt fixture {:?}: {}", path, e))?;
+    debug!("[Proxy Fixtures] Replaying {} {} from {:?}", key.method, key.path, path);
+    Ok(fixture.response)
+}
+
+/// Replaces the values of `CREDENTIAL_FIELDS` anywhere in a JSON body; returns whether any were.
+fn redact_json(value: &mut serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Object(fields) => {
+            let mut redacted = false;
+            for (key, value) in fields.iter_mut() {
+                if CREDENTIAL_FIELDS.contains(&key.to_lowercase().as_str()) && !value.is_null() {
+                    *value = serde_json::Value::String(REDACTED.to_string());
+                    redacted = true;
+                } else {
+                    redacted |= redact_json(value);
+                }
+            }
+            redacted
+        }
+        serde_json::Value::Array(items) => items.iter_mut().fold(false, |redacted, item| redact_json(item) | redacted),
+        _ => false,
+    }
+}
+
+/// `response` without credentials, as it is written to disk.
+fn redact_response(response: &HttpResponse) -> HttpResponse {
+    let body = match serde_json::from_str::<serde_json::Value>(&response.body) {
+        Ok(mut json) => match redact_json(&mut json) {
+            true => json.to_string(),
+            false => response.body.clone(),
+        },
+        Err(_) => response.body.clone(),
+    };
+    HttpResponse {
+        status: response.status,
+        headers: proxy_inspector::redact_headers(&response.headers).into_iter().collect(),
+        body,
+    }
+}
+
+/// Saves `response` as the fixture for `request`, replacing an earlier recording.
+/// Credentials are redacted first (see `redact_response`).
+pub fn record(fixture_dir: &Path, request: &HttpRequest, response: &HttpResponse) -> Result<PathBuf, String> {
+    let key = FixtureKey::from_request(request)?;
+    std::fs::create_dir_all(fixture_dir)
+        .map_err(|e| format!("Failed to create fixture directory {:?}: {}", fixture_dir, e))?;
+    let path = fixture_dir.join(key.file_name());
+    let fixture = Fixture {
+        method: key.method,
+        path: key.path,
+        query: key.query,
+        body_sha256: key.body_sha256,
+        recorded_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        response: redact_response(response),
+    };
+    let content = serde_json::to_string_pretty(&fixture)
+        .map_err(|e| format!("Failed to serialize fixture: {}", e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write fixture {:?}: {}", path, e))?;
+    debug!("[Proxy Fixtures] Recorded {} {} to {:?}", fixture.method, fixture.path, path);
+    Ok(path)
+}
+
+/// Switches the proxy between live, record and replay. Without `fixture_dir`, fixtures
+/// live in `<app data>/proxy-fixtures`.
+#[tauri::command]
+pub fn set_proxy_mock_mode(
+    mode: ProxyMode,
+    fixture_dir: Option<String>,
+    app_handle: tauri::AppHandle,
+    mock_state: tauri::State<'_, ProxyMockState>,
+) -> Result<ProxyMockConfig, String> {
+    let fixture_dir = match fixture_dir {
+        Some(dir) => Some(crate::expand_tilde(dir)),
+        None if mode == ProxyMode::Live => None,
+        None => Some(crate::get_app_data_dir(&app_handle)?.join("proxy-fixtures")),
+    };
+
+    if mode == ProxyMode::Replay {
+        if let Some(dir) = &fixture_dir {
+            if !dir.is_dir() {
+                return Err(format!("Fixture directory {:?} does not exist. Record some traffic first.", dir));
+            }
+        }
+    }
+
+    let config = ProxyMockConfig { mode, fixture_dir };
+    mock_state.set(config.clone());
+    info!("[Proxy Fixtures] Proxy mode set to {:?} (fixtures: {:?})", config.mode, config.fixture_dir);
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn get_proxy_mock_mode(mock_state: tauri::State<'_, ProxyMockState>) -> ProxyMockConfig {
+    mock_state.config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, url: &str, body: Option<&str>) -> HttpRequest {
+        HttpRequest {
+            url: url.to_string(),
+            method: method.to_string(),
+            headers: None,
+            body: body.map(|b| b.to_string()),
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("calimero-fixtures-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_record_then_replay_ignores_host_and_query_order() {
+        let dir = temp_dir("roundtrip");
+        let response = HttpResponse {
+            status: 200,
+            headers: [("content-type".to_string(), "application/json".to_string())].into_iter().collect(),
+            body: "{\"ok\":true}".to_string(),
+        };
+        record(&dir, &request("POST", "http://localhost:2528/jsonrpc?a=1&b=2", Some("{}")), &response).unwrap();
+
+        let replayed = replay(&dir, &request("post", "http://127.0.0.1:2529/jsonrpc?b=2&a=1", Some("{}"))).unwrap();
+        assert_eq!(replayed.status, 200);
+        assert_eq!(replayed.body, "{\"ok\":true}");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_replay_misses_on_different_body_or_path() {
+        let dir = temp_dir("miss");
+        let response = HttpResponse { status: 200, headers: Default::default(), body: String::new() };
+        record(&dir, &request("POST", "http://localhost:2528/jsonrpc", Some("{\"id\":1}")), &response).unwrap();
+
+        assert!(replay(&dir, &request("POST", "http://localhost:2528/jsonrpc", Some("{\"id\":2}"))).is_err());
+        assert!(replay(&dir, &request("POST", "http://localhost:2528/admin-api", Some("{\"id\":1}"))).is_err());
+        assert!(replay(&dir, &request("GET", "http://localhost:2528/jsonrpc", Some("{\"id\":1}"))).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_recorded_credentials_are_redacted() {
+        let dir = temp_dir("redact");
+        let response = HttpResponse {
+            status: 200,
+            headers: [
+                ("Set-Cookie".to_string(), "session=abc".to_string()),
+                ("content-type".to_string(), "application/json".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+            body: r#"{"data":{"access_token":"eyJ.a.b","refreshToken":"r1","expires_in":3600}}"#.to_string(),
+        };
+        let path = record(&dir, &request("POST", "http://localhost:2528/auth/token", Some("{}")), &response).unwrap();
+
+        let on_disk = std::fs::read_to_string(path).unwrap();
+        assert!(!on_disk.contains("session=abc") && !on_disk.contains("eyJ.a.b") && !on_disk.contains("r1"));
+        let replayed = replay(&dir, &request("POST", "http://localhost:2528/auth/token", Some("{}"))).unwrap();
+        assert_eq!(replayed.headers["content-type"], "application/json");
+        assert!(replayed.body.contains("3600"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!("Replay".parse::<ProxyMode>().unwrap(), ProxyMode::Replay);
+        assert_eq!("record".parse::<ProxyMode>().unwrap(), ProxyMode::Record);
+        assert!("mock".parse::<ProxyMode>().is_err());
+    }
+}
@@ -0,0 +1,551 @@
+//! Developer-mode traffic inspector for `proxy_http_request`.
+//!
+//! Keeps a bounded ring of proxied requests per window and can export it as a HAR 1.2
+//! file. Credentials (Authorization, cookies, and token or key query parameters) are always
+//! redacted before an entry is stored.
+//! Failed requests are also kept in a short list while the inspector is off, for support
+//! bundles.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of requests kept per window; the oldest entries are dropped first.
+pub const MAX_ENTRIES_PER_WINDOW: usize = 500;
+
+/// Failed requests kept for support bundles, whether or not the inspector is enabled.
+pub const MAX_RECENT_ERRORS: usize = 50;
+
+/// Bodies longer than this are truncated when body capture is enabled.
+const MAX_CAPTURED_BODY_BYTES: usize = 64 * 1024;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Headers that are never stored or logged in clear text.
+const REDACTED_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie", "set-cookie"];
+
+/// Query parameters whose values are never stored or logged in clear text.
+const REDACTED_QUERY_PARAMS: &[&str] = &[
+    "token",
+    "access_token",
+    "refresh_token",
+    "id_token",
+    "api_key",
+    "apikey",
+    "key",
+    "secret",
+    "client_secret",
+    "password",
+    "signature",
+    "sig",
+    "code",
+];
+
+pub fn is_sensitive_header(name: &str) -> bool {
+    REDACTED_HEADERS.contains(&name.to_lowercase().as_str())
+}
+
+pub fn is_sensitive_query_param(name: &str) -> bool {
+    let name = name.to_lowercase();
+    REDACTED_QUERY_PARAMS.contains(&name.as_str()) || name.ends_with("_token")
+}
+
+/// Returns `url` with the values of sensitive query parameters replaced. Everything else,
+/// including the encoding of other parameters, is kept as is.
+pub fn redact_url(url: &str) -> String {
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((base, query)) = rest.split_once('?') else { return url.to_string() };
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| {
+            let raw_name = pair.split('=').next().unwrap_or_default();
+            let name = url::form_urlencoded::parse(raw_name.as_bytes())
+                .next()
+                .map(|(name, _)| name.into_owned())
+                .unwrap_or_default();
+            if is_sensitive_query_param(&name) {
+                format!("{}={}", raw_name, REDACTED)
+            } else {
+                pair.to_string()
+            }
+        })
+        .collect();
+    let mut redacted = format!("{}?{}", base, query.join("&"));
+    if let Some(fragment) = fragment {
+        redacted.push('#');
+        redacted.push_str(fragment);
+    }
+    redacted
+}
+
+/// Returns the headers sorted by name with sensitive values replaced.
+pub fn redact_headers(headers: &HashMap<String, String>) -> Vec<(String, String)> {
+    let mut redacted: Vec<(String, String)> = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_sensitive_header(name) { REDACTED.to_string() } else { value.clone() };
+            (name.clone(), value)
+        })
+        .collect();
+    redacted.sort();
+    redacted
+}
+
+fn capture_body(body: &str) -> String {
+    if body.len() <= MAX_CAPTURED_BODY_BYTES {
+        return body.to_string();
+    }
+    let mut end = MAX_CAPTURED_BODY_BYTES;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...[truncated {} bytes]", &body[..end], body.len() - end)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyEntry {
+    pub id: u64,
+    pub window: String,
+    /// RFC 3339 timestamp of when the request was received from the webview.
+    pub started_at: String,
+    pub method: String,
+    pub url: String,
+    /// `None` when the request failed before a response was received.
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub request_size: usize,
+    pub response_size: usize,
+    pub request_headers: Vec<(String, String)>,
+    pub response_headers: Vec<(String, String)>,
+    pub request_body: Option<String>,
+    pub response_body: Option<String>,
+}
+
+/// Request half of an entry, captured before the request is sent.
+pub struct PendingEntry {
+    pub window: String,
+    pub started_at: String,
+    pub started: std::time::Instant,
+    pub method: String,
+    pub url: String,
+    pub request_headers: HashMap<String, String>,
+    pub request_body: Option<String>,
+}
+
+/// A proxied request that failed or got a 5xx response. The URL's query string is dropped.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyError {
+    pub at: String,
+    pub window: String,
+    pub method: String,
+    pub url: String,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct InspectorSettings {
+    pub enabled: bool,
+    pub capture_bodies: bool,
+}
+
+#[derive(Default)]
+struct InspectorState {
+    settings: InspectorSettings,
+    next_id: u64,
+    windows: HashMap<String, VecDeque<ProxyEntry>>,
+    recent_errors: VecDeque<ProxyError>,
+}
+
+#[derive(Default)]
+pub struct ProxyInspector(Mutex<InspectorState>);
+
+impl ProxyInspector {
+    pub fn settings(&self) -> InspectorSettings {
+        self.0.lock().map(|s| s.settings).unwrap_or_default()
+    }
+
+    pub fn configure(&self, settings: InspectorSettings) {
+        if let Ok(mut state) = self.0.lock() {
+            state.settings = settings;
+            if !settings.enabled {
+                state.windows.clear();
+            }
+        }
+    }
+
+    /// Stores the completed request. Does nothing unless the inspector is enabled.
+    pub fn record(&self, pending: PendingEntry, outcome: Result<(u16, &HashMap<String, String>, &str), &str>) {
+        let Ok(mut state) = self.0.lock() else { return };
+        let failure = match outcome {
+            Ok((status, _, _)) if status >= 500 => Some((Some(status), None)),
+            Ok(_) => None,
+            Err(error) => Some((None, Some(error.to_string()))),
+        };
+        if let Some((status, error)) = failure {
+            if state.recent_errors.len() == MAX_RECENT_ERRORS {
+                state.recent_errors.pop_front();
+            }
+            state.recent_errors.push_back(ProxyError {
+                at: pending.started_at.clone(),
+                window: pending.window.clone(),
+                method: pending.method.clone(),
+                url: pending.url.split('?').next().unwrap_or_default().to_string(),
+                status,
+                error,
+            });
+        }
+        if !state.settings.enabled {
+            return;
+        }
+        let capture_bodies = state.settings.capture_bodies;
+        state.next_id += 1;
+
+        let (status, error, response_headers, response_size, response_body) = match outcome {
+            Ok((status, headers, body)) => (
+                Some(status),
+                None,
+                redact_headers(headers),
+                body.len(),
+                capture_bodies.then(|| capture_body(body)),
+            ),
+            Err(error) => (None, Some(error.to_string()), Vec::new(), 0, None),
+        };
+
+        let entry = ProxyEntry {
+            id: state.next_id,
+            window: pending.window.clone(),
+            started_at: pending.started_at,
+            method: pending.method,
+            url: redact_url(&pending.url),
+            status,
+            error,
+            duration_ms: pending.started.elapsed().as_millis() as u64,
+            request_size: pending.request_body.as_ref().map_or(0, |b| b.len()),
+            response_size,
+            request_headers: redact_headers(&pending.request_headers),
+            response_headers,
+            request_body: if capture_bodies { pending.request_body.as_deref().map(capture_body) } else { None },
+            response_body,
+        };
+
+        let ring = state.windows.entry(pending.window).or_default();
+        if ring.len() == MAX_ENTRIES_PER_WINDOW {
+            ring.pop_front();
+        }
+        ring.push_back(entry);
+    }
+
+    /// Entries for one window (or all windows, ordered by id), newest last.
+    pub fn entries(&self, window: Option<&str>) -> Vec<ProxyEntry> {
+        let Ok(state) = self.0.lock() else { return Vec::new() };
+        let mut entries: Vec<ProxyEntry> = match window {
+            Some(label) => state.windows.get(label).map(|r| r.iter().cloned().collect()).unwrap_or_default(),
+            None => state.windows.values().flat_map(|r| r.iter().cloned()).collect(),
+        };
+        entries.sort_by_key(|e| e.id);
+        entries
+    }
+
+    /// Recent failed requests across all windows, oldest first.
+    pub fn recent_errors(&self) -> Vec<ProxyError> {
+        self.0.lock().map(|s| s.recent_errors.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    pub fn clear(&self, window: Option<&str>) {
+        if let Ok(mut state) = self.0.lock() {
+            match window {
+                Some(label) => {
+                    state.windows.remove(label);
+                }
+                None => state.windows.clear(),
+            }
+        }
+    }
+}
+
+fn har_headers(headers: &[(String, String)]) -> Vec<serde_json::Value> {
+    headers
+        .iter()
+        .map(|(name, value)| serde_json::json!({ "name": name, "value": value }))
+        .collect()
+}
+
+fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Builds a HAR 1.2 document from inspector entries.
+pub fn to_har(entries: &[ProxyEntry], creator_version: &str) -> serde_json::Value {
+    let har_entries: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|entry| {
+            let query_string: Vec<serde_json::Value> = url::Url::parse(&entry.url)
+                .map(|u| {
+                    u.query_pairs()
+                        .map(|(name, value)| {
+                            let value = if is_sensitive_query_param(&name) { REDACTED.into() } else { value };
+                            serde_json::json!({ "name": name, "value": value })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let mut request = serde_json::json!({
+                "method": entry.method,
+                "url": entry.url,
+                "httpVersion": "HTTP/1.1",
+                "cookies": [],
+                "headers": har_headers(&entry.request_headers),
+                "queryString": query_string,
+                "headersSize": -1,
+                "bodySize": entry.request_size,
+            });
+            if let Some(body) = &entry.request_body {
+                request["postData"] = serde_json::json!({
+                    "mimeType": header_value(&entry.request_headers, "content-type").unwrap_or("application/json"),
+                    "text": body,
+                });
+            }
+
+            let mut content = serde_json::json!({
+                "size": entry.response_size,
+                "mimeType": header_value(&entry.response_headers, "content-type").unwrap_or(""),
+            });
+            if let Some(body) = &entry.response_body {
+                content["text"] = serde_json::Value::String(body.clone());
+            }
+
+            let mut har_entry = serde_json::json!({
+                "startedDateTime": entry.started_at,
+                "time": entry.duration_ms,
+                "request": request,
+                "response": {
+                    // HAR uses status 0 for requests that never got a response
+                    "status": entry.status.unwrap_or(0),
+                    "statusText": "",
+                    "httpVersion": "HTTP/1.1",
+                    "cookies": [],
+                    "headers": har_headers(&entry.response_headers),
+                    "content": content,
+                    "redirectURL": header_value(&entry.response_headers, "location").unwrap_or(""),
+                    "headersSize": -1,
+                    "bodySize": entry.response_size,
+                },
+                "cache": {},
+                "timings": { "send": 0, "wait": entry.duration_ms, "receive": 0 },
+            });
+            if let Some(error) = &entry.error {
+                har_entry["comment"] = serde_json::Value::String(error.clone());
+            }
+            har_entry
+        })
+        .collect();
+
+    serde_json::json!({
+        "log": {
+            "version": "1.2",
+            "creator": { "name": "Calimero Desktop", "version": creator_version },
+            "entries": har_entries,
+        }
+    })
+}
+
+/// Enables or disables the inspector. Disabling it drops everything recorded so far.
+#[tauri::command]
+pub fn set_proxy_inspector(
+    enabled: bool,
+    capture_bodies: Option<bool>,
+    inspector: tauri::State<'_, ProxyInspector>,
+) -> InspectorSettings {
+    let settings = InspectorSettings {
+        enabled,
+        capture_bodies: capture_bodies.unwrap_or(false),
+    };
+    inspector.configure(settings);
+    info!(
+        "[Proxy Inspector] {} (bodies: {})",
+        if enabled { "Enabled" } else { "Disabled" },
+        settings.capture_bodies
+    );
+    settings
+}
+
+/// Returns recorded requests for a window (or all windows), newest last.
+#[tauri::command]
+pub fn get_proxy_traffic(
+    window_label: Option<String>,
+    limit: Option<usize>,
+    inspector: tauri::State<'_, ProxyInspector>,
+) -> Vec<ProxyEntry> {
+    let mut entries = inspector.entries(window_label.as_deref());
+    if let Some(limit) = limit {
+        let skip = entries.len().saturating_sub(limit);
+        entries.drain(..skip);
+    }
+    entries
+}
+
+#[tauri::command]
+pub fn clear_proxy_traffic(window_label: Option<String>, inspector: tauri::State<'_, ProxyInspector>) {
+    inspector.clear(window_label.as_deref());
+}
+
+/// Writes the recorded requests for a window (or all windows) to `dest` as a HAR 1.2 file.
+#[tauri::command]
+pub async fn export_proxy_har(
+    window_label: Option<String>,
+    dest: String,
+    app_handle: tauri::AppHandle,
+    inspector: tauri::State<'_, ProxyInspector>,
+) -> Result<String, String> {
+    let path = crate::expand_tilde(dest);
+
+    let entries = inspector.entries(window_label.as_deref());
+    let har = to_har(&entries, &app_handle.package_info().version.to_string());
+    let content = serde_json::to_string_pretty(&har)
+        .map_err(|e| format!("Failed to serialize HAR: {}", e))?;
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| format!("Failed to write HAR file to {:?}: {}", path, e))?;
+
+    info!("[Proxy Inspector] Exported {} request(s) to {:?}", entries.len(), path);
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(window: &str, headers: &[(&str, &str)]) -> PendingEntry {
+        PendingEntry {
+            window: window.to_string(),
+            started_at: "2026-01-01T00:00:00.000Z".to_string(),
+            started: std::time::Instant::now(),
+            method: "POST".to_string(),
+            url: "http://localhost:2528/jsonrpc?x=1".to_string(),
+            request_headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            request_body: Some("{\"id\":1}".to_string()),
+        }
+    }
+
+    fn enabled(capture_bodies: bool) -> ProxyInspector {
+        let inspector = ProxyInspector::default();
+        inspector.configure(InspectorSettings { enabled: true, capture_bodies });
+        inspector
+    }
+
+    #[test]
+    fn test_credentials_always_redacted() {
+        let inspector = enabled(true);
+        let response_headers: HashMap<String, String> =
+            [("Set-Cookie".to_string(), "session=abc".to_string())].into_iter().collect();
+        inspector.record(
+            pending("app-1", &[("Authorization", "Bearer secret"), ("Cookie", "a=b"), ("Accept", "*/*")]),
+            Ok((200, &response_headers, "{}")),
+        );
+
+        let entry = &inspector.entries(Some("app-1"))[0];
+        assert_eq!(header_value(&entry.request_headers, "authorization"), Some(REDACTED));
+        assert_eq!(header_value(&entry.request_headers, "cookie"), Some(REDACTED));
+        assert_eq!(header_value(&entry.request_headers, "accept"), Some("*/*"));
+        assert_eq!(header_value(&entry.response_headers, "set-cookie"), Some(REDACTED));
+        assert!(!serde_json::to_string(&to_har(std::slice::from_ref(entry), "0.0.0")).unwrap().contains("secret"));
+    }
+
+    #[test]
+    fn test_query_credentials_redacted() {
+        assert_eq!(
+            redact_url("http://localhost:2528/ws?access_token=abc&Api_Key=k%20y&page=2#top"),
+            "http://localhost:2528/ws?access_token=[REDACTED]&Api_Key=[REDACTED]&page=2#top"
+        );
+        assert_eq!(redact_url("http://localhost:2528/jsonrpc"), "http://localhost:2528/jsonrpc");
+        assert_eq!(redact_url("http://localhost/?x=1&refresh_token"), "http://localhost/?x=1&refresh_token=[REDACTED]");
+
+        let inspector = enabled(false);
+        let headers = HashMap::new();
+        let mut request = pending("app-1", &[]);
+        request.url = "http://localhost:2528/admin-api/contexts?token=secret&x=1".to_string();
+        inspector.record(request, Ok((200, &headers, "")));
+
+        let entries = inspector.entries(None);
+        assert_eq!(entries[0].url, "http://localhost:2528/admin-api/contexts?token=[REDACTED]&x=1");
+        let har = to_har(&entries, "0.0.0");
+        assert_eq!(har["log"]["entries"][0]["request"]["queryString"][0]["value"], REDACTED);
+        assert!(!serde_json::to_string(&har).unwrap().contains("secret"));
+    }
+
+    #[test]
+    fn test_ring_is_bounded_per_window() {
+        let inspector = enabled(false);
+        let headers = HashMap::new();
+        for _ in 0..MAX_ENTRIES_PER_WINDOW + 10 {
+            inspector.record(pending("app-1", &[]), Ok((200, &headers, "")));
+        }
+        inspector.record(pending("app-2", &[]), Err("connection refused"));
+
+        let app1 = inspector.entries(Some("app-1"));
+        assert_eq!(app1.len(), MAX_ENTRIES_PER_WINDOW);
+        assert_eq!(app1[0].id, 11);
+        assert!(app1[0].request_body.is_none());
+        assert_eq!(inspector.entries(Some("app-2"))[0].error.as_deref(), Some("connection refused"));
+        assert_eq!(inspector.entries(None).len(), MAX_ENTRIES_PER_WINDOW + 1);
+    }
+
+    #[test]
+    fn test_disabled_inspector_records_nothing() {
+        let inspector = ProxyInspector::default();
+        inspector.record(pending("app-1", &[]), Err("boom"));
+        assert!(inspector.entries(None).is_empty());
+    }
+
+    #[test]
+    fn test_recent_errors_kept_while_disabled() {
+        let inspector = ProxyInspector::default();
+        let headers = HashMap::new();
+        let mut failing = pending("app-1", &[]);
+        failing.url = "http://localhost:2528/admin-api/contexts?token=secret".to_string();
+        inspector.record(failing, Ok((502, &headers, "")));
+        inspector.record(pending("app-1", &[]), Ok((404, &headers, "")));
+
+        let errors = inspector.recent_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].status, Some(502));
+        assert_eq!(errors[0].url, "http://localhost:2528/admin-api/contexts");
+
+        for _ in 0..MAX_RECENT_ERRORS {
+            inspector.record(pending("app-1", &[]), Err("connection refused"));
+        }
+        let errors = inspector.recent_errors();
+        assert_eq!(errors.len(), MAX_RECENT_ERRORS);
+        assert!(errors.iter().all(|e| e.error.as_deref() == Some("connection refused")));
+    }
+
+    #[test]
+    fn test_har_structure() {
+        let inspector = enabled(true);
+        let headers: HashMap<String, String> =
+            [("content-type".to_string(), "application/json".to_string())].into_iter().collect();
+        inspector.record(pending("app-1", &[]), Ok((201, &headers, "{\"ok\":true}")));
+
+        let har = to_har(&inspector.entries(None), "1.2.3");
+        assert_eq!(har["log"]["version"], "1.2");
+        assert_eq!(har["log"]["creator"]["version"], "1.2.3");
+        let entry = &har["log"]["entries"][0];
+        assert_eq!(entry["request"]["method"], "POST");
+        assert_eq!(entry["request"]["queryString"][0]["name"], "x");
+        assert_eq!(entry["request"]["postData"]["text"], "{\"id\":1}");
+        assert_eq!(entry["response"]["status"], 201);
+        assert_eq!(entry["response"]["content"]["mimeType"], "application/json");
+        assert_eq!(entry["response"]["content"]["text"], "{\"ok\":true}");
+    }
+}
@@ -10,12 +10,24 @@
 
     console.log('[Tauri Proxy] Configured node URL for interception:', nodeUrl);
 
-    // Check if a URL is an HTTP localhost request that needs proxying
-    // Any http://localhost:* or http://127.0.0.1:* request from an HTTPS page
-    // will be blocked by mixed content rules, so we proxy all of them
-    function isHttpLocalhost(urlStr) {
+    var nodeOrigin = null;
+    try {
+        nodeOrigin = new URL(nodeUrl).origin;
+    } catch (e) {
+        console.warn('[Tauri Proxy] Invalid configured node URL:', nodeUrl);
+    }
+
+    // Check if a URL needs proxying:
+    // - Any http://localhost:* or http://127.0.0.1:* request from an HTTPS page
+    //   will be blocked by mixed content rules, so we proxy all of them
+    // - Requests to the configured node (including https:// nodes) go through the
+    //   backend so its node profile (custom CA, pinned certificate, mTLS) applies
+    function shouldProxyUrl(urlStr) {
         try {
             var u = new URL(urlStr);
+            if (nodeOrigin && u.origin === nodeOrigin) {
+                return true;
+            }
             return u.protocol === 'http:' && (u.hostname === 'localhost' || u.hostname === '127.0.0.1');
         } catch (e) {
             return false;
@@ -59,9 +71,10 @@
         // Debug: log all fetch calls to see what's happening
         console.log('[Tauri Proxy] Fetch called:', urlStr);
         
-        // Proxy any HTTP localhost request (any port) to avoid mixed content blocking.
+        // Proxy any HTTP localhost request (any port) to avoid mixed content blocking,
+        // plus requests to the configured node.
         // The Rust backend validates the URL before proxying.
-        const shouldProxy = isHttpLocalhost(urlStr);
+        const shouldProxy = shouldProxyUrl(urlStr);
         console.log('[Tauri Proxy] Should proxy?', shouldProxy, 'for URL:', urlStr);
         if (shouldProxy) {
             try {
@@ -139,7 +152,7 @@
         };
 
         xhr.send = function(body) {
-            const shouldProxy = isHttpLocalhost(xhrUrl);
+            const shouldProxy = shouldProxyUrl(xhrUrl);
 
             if (shouldProxy) {
                 console.log('[Tauri Proxy] XHR intercepted:', xhrMethod, xhrUrl);
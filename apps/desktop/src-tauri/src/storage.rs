@@ -0,0 +1,452 @@
+//! Disk usage of nodes and a guard against running them on a nearly full disk.
+//!
+//! RocksDB corrupts its data when writes fail with ENOSPC, so `start_merod` checks free space
+//! on the home's filesystem first, and a background task warns the UI (`storage-low`) when a
+//! running node's filesystem nears the configured threshold (below twice the threshold) and
+//! again when it drops below it. By default a low disk only warns; refusing to start is opt-in.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::data_dir::{dir_size, is_node_dir};
+
+/// Emitted to the main window with `LowSpaceWarning` when free space enters the warning band
+/// and again when it drops below the threshold.
+pub const STORAGE_LOW_EVENT: &str = "storage-low";
+const SETTINGS_FILE: &str = "storage-settings.json";
+const MONITOR_INTERVAL_SECS: u64 = 60;
+const DEFAULT_MIN_FREE_BYTES: u64 = 1024 * 1024 * 1024;
+/// The warning band starts at this multiple of the threshold.
+const WARNING_BAND_FACTOR: u64 = 2;
+
+/// How close a filesystem is to the threshold, ordered from fine to critical.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SpaceLevel {
+    Ok,
+    Warning,
+    Critical,
+}
+
+fn space_level(available: u64, min_free_bytes: u64) -> SpaceLevel {
+    if available < min_free_bytes {
+        SpaceLevel::Critical
+    } else if available < min_free_bytes.saturating_mul(WARNING_BAND_FACTOR) {
+        SpaceLevel::Warning
+    } else {
+        SpaceLevel::Ok
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StorageSettings {
+    /// Free space below which nodes are considered at risk.
+    pub min_free_bytes: u64,
+    /// Refuse to start a node below the threshold instead of only warning.
+    pub block_start: bool,
+}
+
+impl Default for StorageSettings {
+    fn default() -> Self {
+        Self { min_free_bytes: DEFAULT_MIN_FREE_BYTES, block_start: false }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NodeStorage {
+    pub name: String,
+    pub data: u64,
+    pub logs: u64,
+    pub auth: u64,
+    pub blobs: u64,
+    /// Config and anything else in the node directory.
+    pub other: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FilesystemSpace {
+    pub total: u64,
+    pub available: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageReport {
+    pub home: PathBuf,
+    pub nodes: Vec<NodeStorage>,
+    pub total: u64,
+    pub filesystem: Option<FilesystemSpace>,
+    pub min_free_bytes: u64,
+    pub low_space: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LowSpaceWarning {
+    pub home: PathBuf,
+    pub available: u64,
+    pub min_free_bytes: u64,
+    /// Below the threshold itself rather than in the warning band above it.
+    pub critical: bool,
+    pub nodes: Vec<String>,
+}
+
+/// Splits a node directory's bytes by what they hold.
+pub fn node_storage(node_dir: &Path, name: &str) -> NodeStorage {
+    let mut usage = NodeStorage { name: name.to_string(), ..Default::default() };
+    let Ok(entries) = std::fs::read_dir(node_dir) else { return usage };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let size = dir_size(&entry.path());
+        let entry_name = entry.file_name().to_string_lossy().to_lowercase();
+        match entry_name.as_str() {
+            "data" => usage.data += size,
+            "logs" => usage.logs += size,
+            "blobs" => usage.blobs += size,
+            n if n.starts_with("auth") => usage.auth += size,
+            _ => usage.other += size,
+        }
+        usage.total += size;
+    }
+    usage
+}
+
+/// Free and total bytes of the filesystem holding `path` (or its nearest existing ancestor).
+pub fn filesystem_space(path: &Path) -> Option<FilesystemSpace> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        let c_path = std::ffi::CString::new(existing.as_os_str().as_bytes()).ok()?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        // SAFETY: c_path is a valid NUL-terminated string and stat is a valid out pointer
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+        let block = stat.f_frsize as u64;
+        Some(FilesystemSpace {
+            total: stat.f_blocks as u64 * block,
+            available: stat.f_bavail as u64 * block,
+        })
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        let wide: Vec<u16> = existing.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        let (mut available, mut total, mut free) = (0u64, 0u64, 0u64);
+        // SAFETY: wide is NUL-terminated and the out pointers are valid u64s
+        let ok = unsafe {
+            windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW(
+                wide.as_ptr(),
+                &mut available,
+                &mut total,
+                &mut free,
+            )
+        };
+        (ok != 0).then_some(FilesystemSpace { total, available })
+    }
+}
+
+pub fn storage_report(home: &Path, settings: StorageSettings) -> Result<StorageReport, String> {
+    let mut nodes = Vec::new();
+    if home.is_dir() {
+        let mut entries: Vec<_> = std::fs::read_dir(home)
+            .map_err(|e| format!("Failed to read {:?}: {}", home, e))?
+            .filter_map(|e| e.ok())
+            .filter(|e| is_node_dir(&e.path()))
+            .collect();
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            nodes.push(node_storage(&entry.path(), &entry.file_name().to_string_lossy()));
+        }
+    }
+    let filesystem = filesystem_space(home);
+    Ok(StorageReport {
+        home: home.to_path_buf(),
+        total: nodes.iter().map(|n| n.total).sum(),
+        nodes,
+        filesystem,
+        min_free_bytes: settings.min_free_bytes,
+        low_space: filesystem.is_some_and(|fs| fs.available < settings.min_free_bytes),
+    })
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+pub struct StorageGuard {
+    path: Option<PathBuf>,
+    settings: Mutex<StorageSettings>,
+    /// Level each home was last reported at, so each step down is reported once.
+    warned: Mutex<HashMap<PathBuf, SpaceLevel>>,
+}
+
+impl Default for StorageGuard {
+    fn default() -> Self {
+        Self { path: None, settings: Mutex::new(StorageSettings::default()), warned: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl StorageGuard {
+    /// Loads settings from `<app data>/storage-settings.json`, falling back to defaults.
+    pub fn load(app_data_dir: &Path) -> Self {
+        let path = app_data_dir.join(SETTINGS_FILE);
+        let settings = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| match serde_json::from_str(&content) {
+                Ok(settings) => Some(settings),
+                Err(e) => {
+                    warn!("[Storage] Ignoring unreadable {:?}: {}", path, e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self { path: Some(path), settings: Mutex::new(settings), warned: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn settings(&self) -> StorageSettings {
+        self.settings.lock().map(|s| *s).unwrap_or_default()
+    }
+
+    pub fn set_settings(&self, settings: StorageSettings) -> Result<(), String> {
+        if let Ok(mut current) = self.settings.lock() {
+            *current = settings;
+        }
+        let Some(path) = &self.path else { return Ok(()) };
+        let content = serde_json::to_string_pretty(&settings)
+            .map_err(|e| format!("Failed to serialize storage settings: {}", e))?;
+        std::fs::write(path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    /// Checks free space before starting a node in `home`. Returns a warning to surface when
+    /// space is low but starting is still allowed, and an error when it is blocked.
+    pub fn check_before_start(&self, home: &Path) -> Result<Option<String>, String> {
+        let settings = self.settings();
+        let Some(space) = filesystem_space(home) else {
+            warn!("[Storage] Could not determine free space for {:?}", home);
+            return Ok(None);
+        };
+        match space_level(space.available, settings.min_free_bytes) {
+            SpaceLevel::Ok => return Ok(None),
+            SpaceLevel::Warning => {
+                let message = format!(
+                    "Only {} free on the disk holding {:?}, close to the {} minimum. A full disk can corrupt node data; consider freeing up space.",
+                    format_bytes(space.available),
+                    home,
+                    format_bytes(settings.min_free_bytes)
+                );
+                warn!("[Storage] {}", message);
+                return Ok(Some(message));
+            }
+            SpaceLevel::Critical => {}
+        }
+        let message = format!(
+            "Only {} free on the disk holding {:?} (minimum {}). A full disk can corrupt node data; free up space{}.",
+            format_bytes(space.available),
+            home,
+            format_bytes(settings.min_free_bytes),
+            if settings.block_start { " before starting the node" } else { "" }
+        );
+        if settings.block_start {
+            Err(message)
+        } else {
+            warn!("[Storage] {}", message);
+            Ok(Some(message))
+        }
+    }
+
+    /// Returns the homes (with their running nodes) that just entered the warning band or
+    /// dropped below the threshold.
+    fn newly_low(&self, homes: BTreeMap<PathBuf, Vec<String>>) -> Vec<LowSpaceWarning> {
+        let min_free_bytes = self.settings().min_free_bytes;
+        let Ok(mut warned) = self.warned.lock() else { return Vec::new() };
+        let mut warnings = Vec::new();
+        for (home, nodes) in homes {
+            let Some(space) = filesystem_space(&home) else { continue };
+            let level = space_level(space.available, min_free_bytes);
+            let reported = warned.get(&home).copied().unwrap_or(SpaceLevel::Ok);
+            if level > reported {
+                warned.insert(home.clone(), level);
+                warnings.push(LowSpaceWarning {
+                    home,
+                    available: space.available,
+                    min_free_bytes,
+                    critical: level == SpaceLevel::Critical,
+                    nodes,
+                });
+            } else {
+                // Re-arm with a little hysteresis so hovering at a boundary doesn't spam
+                let recovered = space_level(space.available.saturating_sub(min_free_bytes / 10), min_free_bytes);
+                if recovered < reported {
+                    warned.insert(home, recovered);
+                }
+            }
+        }
+        warnings
+    }
+}
+
+/// Periodically checks the filesystems of running nodes and emits `storage-low` to the main
+/// window when one nears or drops below the threshold.
+pub fn spawn_monitor(app_handle: tauri::AppHandle) {
+    use tauri::Manager;
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(MONITOR_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let homes: BTreeMap<PathBuf, Vec<String>> = {
+                let Some(state) = app_handle.try_state::<crate::MerodState>() else { continue };
+                let Ok(processes) = state.lock() else { continue };
+                let mut homes: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+                for process in processes.iter() {
+                    homes.entry(process.home_dir.clone()).or_default().push(process.node_name.clone());
+                }
+                homes
+            };
+            if homes.is_empty() {
+                continue;
+            }
+            let Some(guard) = app_handle.try_state::<StorageGuard>() else { continue };
+            for warning in guard.newly_low(homes) {
+                warn!(
+                    "[Storage] {} disk space for {:?}: {} free (nodes: {})",
+                    if warning.critical { "Critically low" } else { "Low" },
+                    warning.home,
+                    format_bytes(warning.available),
+                    warning.nodes.join(", ")
+                );
+                let _ = app_handle.emit_to(crate::capabilities::MAIN_WINDOW_LABEL, STORAGE_LOW_EVENT, warning);
+            }
+        }
+    });
+}
+
+/// Per-node disk usage of a Calimero home, split into data, logs, auth and blobs, plus free
+/// space on its filesystem.
+#[tauri::command]
+pub async fn get_storage_report(
+    home_dir: Option<String>,
+    guard: tauri::State<'_, StorageGuard>,
+) -> Result<StorageReport, String> {
+    let home = crate::resolve_calimero_home(home_dir)?;
+    let settings = guard.settings();
+    tokio::task::spawn_blocking(move || storage_report(&home, settings))
+        .await
+        .map_err(|e| format!("Storage report failed: {}", e))?
+}
+
+#[tauri::command]
+pub fn get_storage_settings(guard: tauri::State<'_, StorageGuard>) -> StorageSettings {
+    guard.settings()
+}
+
+#[tauri::command]
+pub fn set_storage_settings(
+    settings: StorageSettings,
+    guard: tauri::State<'_, StorageGuard>,
+) -> Result<StorageSettings, String> {
+    guard.set_settings(settings)?;
+    info!(
+        "[Storage] Free-space threshold set to {} ({})",
+        format_bytes(settings.min_free_bytes),
+        if settings.block_start { "blocks start" } else { "warns only" }
+    );
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_storage_splits_by_kind() {
+        let root = std::env::temp_dir().join(format!("calimero-storage-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let node = root.join("node1");
+        for dir in ["data", "logs", "blobs", "auth"] {
+            std::fs::create_dir_all(node.join(dir)).unwrap();
+        }
+        std::fs::write(node.join("config.toml"), "x = 1").unwrap();
+        std::fs::write(node.join("data/000001.sst"), vec![0u8; 100]).unwrap();
+        std::fs::write(node.join("logs/merod.log"), vec![0u8; 20]).unwrap();
+        std::fs::write(node.join("blobs/blob"), vec![0u8; 7]).unwrap();
+        std::fs::write(node.join("auth/LOG"), vec![0u8; 3]).unwrap();
+
+        let report = storage_report(&root, StorageSettings::default()).unwrap();
+        assert_eq!(report.nodes.len(), 1);
+        let usage = &report.nodes[0];
+        assert_eq!((usage.data, usage.logs, usage.blobs, usage.auth, usage.other), (100, 20, 7, 3, 5));
+        assert_eq!(report.total, 135);
+        assert!(report.filesystem.is_some());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_threshold_blocks_or_warns() {
+        let guard = StorageGuard::default();
+        let home = std::env::temp_dir();
+        guard.set_settings(StorageSettings { min_free_bytes: u64::MAX, block_start: true }).unwrap();
+        assert!(guard.check_before_start(&home).is_err());
+        guard.set_settings(StorageSettings { min_free_bytes: u64::MAX, block_start: false }).unwrap();
+        assert!(guard.check_before_start(&home).unwrap().is_some());
+        guard.set_settings(StorageSettings { min_free_bytes: 0, block_start: true }).unwrap();
+        assert!(guard.check_before_start(&home).unwrap().is_none());
+        assert!(!StorageSettings::default().block_start);
+    }
+
+    #[test]
+    fn test_space_levels() {
+        assert_eq!(space_level(3000, 1000), SpaceLevel::Ok);
+        assert_eq!(space_level(1999, 1000), SpaceLevel::Warning);
+        assert_eq!(space_level(999, 1000), SpaceLevel::Critical);
+    }
+
+    #[test]
+    fn test_warning_band_starts_before_threshold() {
+        let guard = StorageGuard::default();
+        let home = std::env::temp_dir();
+        let available = filesystem_space(&home).unwrap().available;
+        // Threshold just under the free space: inside the warning band, so start still succeeds
+        guard.set_settings(StorageSettings { min_free_bytes: available / 2 + available / 4, block_start: true }).unwrap();
+        assert!(guard.check_before_start(&home).unwrap().is_some());
+        let homes: BTreeMap<PathBuf, Vec<String>> = [(home, vec!["node1".to_string()])].into_iter().collect();
+        let warnings = guard.newly_low(homes);
+        assert_eq!(warnings.len(), 1);
+        assert!(!warnings[0].critical);
+    }
+
+    #[test]
+    fn test_low_space_is_reported_once() {
+        let guard = StorageGuard::default();
+        guard.set_settings(StorageSettings { min_free_bytes: u64::MAX, block_start: false }).unwrap();
+        let homes: BTreeMap<PathBuf, Vec<String>> =
+            [(std::env::temp_dir(), vec!["node1".to_string()])].into_iter().collect();
+        let warnings = guard.newly_low(homes.clone());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].critical);
+        assert!(guard.newly_low(homes).is_empty());
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(1024 * 1024 * 1024), "1.0 GB");
+    }
+}
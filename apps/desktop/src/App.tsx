@@ -2,7 +2,7 @@ import { useState, useEffect, useCallback } from "react";
 import { createClient, apiClient, LoginView, getAccessToken, clearAccessToken, clearRefreshToken } from "@calimero-network/mero-react";
 import { getSettings, getAuthUrl, saveSettings } from "./utils/settings";
 import { clearOnboardingProgress } from "./utils/onboardingProgress";
-import { startMerod, detectRunningMerodNodes, type RunningMerodNode } from "./utils/merod";
+import { startMerodUntilHealthy, detectRunningMerodNodes, syncSavedHome, type RunningMerodNode } from "./utils/merod";
 import { useToast } from "./contexts/ToastContext";
 import { checkOnboardingState, type OnboardingState } from "./utils/onboarding";
 import { decodeMetadata, openAppFrontend } from "./utils/appUtils";
@@ -120,6 +120,9 @@ function App() {
   useEffect(() => {
     async function initializeApp() {
       const hasCustomSettings = localStorage.getItem('calimero-desktop-settings') !== null;
+      if (hasCustomSettings) {
+        await syncSavedHome();
+      }
       const settings = getSettings();
       let onboardingCompleted = settings.onboardingCompleted ?? false;
 
@@ -152,7 +155,6 @@ function App() {
       }
 
       try {
-        const { startMerod } = await import('./utils/merod');
         let runningNodes = await detectRunningMerodNodes();
         setRunningNodes(runningNodes);
 
@@ -166,8 +168,7 @@ function App() {
           const serverPort = settings.embeddedNodePort ?? 2528;
           const swarmPort = 2428; // default swarm port
           try {
-            await startMerod(serverPort, swarmPort, dataDir, settings.embeddedNodeName, settings.debugLogs);
-            await new Promise((r) => setTimeout(r, 4000)); // give merod time to start (longer when app launches at login)
+            await startMerodUntilHealthy(serverPort, swarmPort, dataDir, settings.embeddedNodeName, settings.debugLogs);
             runningNodes = await detectRunningMerodNodes();
             setRunningNodes(runningNodes);
           } catch (startErr) {
@@ -321,9 +322,8 @@ function App() {
       try {
         const dataDir = settings.embeddedNodeDataDir || '~/.calimero';
         const serverPort = settings.embeddedNodePort ?? 2528;
-        await startMerod(serverPort, 2428, dataDir, settings.embeddedNodeName, settings.debugLogs);
-        toast.success("Starting node...");
-        await new Promise((r) => setTimeout(r, 3000));
+        toast.info("Starting node...");
+        await startMerodUntilHealthy(serverPort, 2428, dataDir, settings.embeddedNodeName, settings.debugLogs);
         await checkConnection();
       } catch (err) {
         toast.error(`Failed to start node: ${err instanceof Error ? err.message : String(err)}`);
@@ -4,12 +4,25 @@ import { clearAccessToken, clearRefreshToken } from "@calimero-network/mero-reac
 import {
   listMerodNodes,
   initMerodNode,
-  startMerod,
+  startMerodUntilHealthy,
   stopMerod,
   stopMerodByPid,
   detectRunningMerodNodes,
   getMerodLogs,
+  getNodeRunSettings,
+  setNodeRunSettings,
+  getSandboxSupport,
+  getNodeMerodVersion,
+  listMerodVersions,
+  listNodeServices,
+  installNodeService,
+  uninstallNodeService,
+  START_STAGE_LABELS,
+  type InstalledMerodVersion,
+  type MerodStartStage,
+  type NodeServiceStatus,
   type RunningMerodNode,
+  type SandboxSupport,
 } from "../utils/merod";
 import { invoke } from "@tauri-apps/api/tauri";
 import { useToast } from "../contexts/ToastContext";
@@ -24,6 +37,8 @@ export default function NodeManagement() {
   // Node management state
   const [availableNodes, setAvailableNodes] = useState<string[]>([]);
   const [runningNodes, setRunningNodes] = useState<RunningMerodNode[]>([]);
+  // Nodes installed as systemd user services (Linux)
+  const [nodeServices, setNodeServices] = useState<NodeServiceStatus[]>([]);
   const [homeDir, setHomeDir] = useState("~/.calimero");
   const [selectedNode, setSelectedNode] = useState<string>("");
   const [newNodeName, setNewNodeName] = useState("");
@@ -41,6 +56,22 @@ export default function NodeManagement() {
   const [logsLoading, setLogsLoading] = useState(false);
   const developerMode = getSettings().developerMode ?? false;
 
+  // Per-node run settings (developer mode): RUST_LOG filter, env vars (KEY=VALUE per line), extra args (one per line)
+  const [logFilter, setLogFilter] = useState("");
+  const [envText, setEnvText] = useState("");
+  const [extraArgsText, setExtraArgsText] = useState("");
+  const [sandbox, setSandbox] = useState(false);
+  const [autostart, setAutostart] = useState(false);
+  // Resource limits; empty means unlimited
+  const [maxMemoryMb, setMaxMemoryMb] = useState("");
+  const [cpuPercent, setCpuPercent] = useState("");
+  const [maxOpenFiles, setMaxOpenFiles] = useState("");
+  const [sandboxSupport, setSandboxSupport] = useState<SandboxSupport | null>(null);
+  // Stage of the node being started, from merod-start-progress events
+  const [startStage, setStartStage] = useState<MerodStartStage | null>(null);
+  // Installed merod the selected node is pinned to; null when it uses the bundled binary
+  const [pinnedMerod, setPinnedMerod] = useState<InstalledMerodVersion | null>(null);
+
   useEffect(() => {
     const settings = getSettings();
     setHomeDir(settings.embeddedNodeDataDir || "~/.calimero");
@@ -56,6 +87,34 @@ export default function NodeManagement() {
     return () => clearInterval(interval);
   }, [homeDir]);
 
+  useEffect(() => {
+    if (!developerMode || !selectedNode) return;
+    getNodeRunSettings(selectedNode, homeDir)
+      .then((runSettings) => {
+        setLogFilter(runSettings.logFilter ?? "");
+        setEnvText(Object.entries(runSettings.env).map(([key, value]) => `${key}=${value}`).join("\n"));
+        setExtraArgsText(runSettings.extraArgs.join("\n"));
+        setSandbox(runSettings.sandbox ?? false);
+        setAutostart(runSettings.autostart ?? false);
+        setMaxMemoryMb(runSettings.limits?.maxMemoryMb?.toString() ?? "");
+        setCpuPercent(runSettings.limits?.cpuPercent?.toString() ?? "");
+        setMaxOpenFiles(runSettings.limits?.maxOpenFiles?.toString() ?? "");
+      })
+      .catch((error) => console.error("Failed to load run settings:", error));
+    Promise.all([getNodeMerodVersion(selectedNode, homeDir), listMerodVersions()])
+      .then(([version, versions]) =>
+        setPinnedMerod(versions.installed.find((installed) => installed.version === version) ?? null)
+      )
+      .catch((error) => console.error("Failed to load merod version:", error));
+  }, [selectedNode, homeDir]);
+
+  useEffect(() => {
+    if (!developerMode) return;
+    getSandboxSupport()
+      .then(setSandboxSupport)
+      .catch((error) => console.error("Failed to check sandbox support:", error));
+  }, []);
+
   // When selected node is not running and current ports conflict with running nodes, auto-assign next free ports
   const getRunningNodeInfo = (nodeName: string): { running: boolean; port?: number } => {
     if (!nodeName) return { running: false };
@@ -97,8 +156,15 @@ export default function NodeManagement() {
     } catch (error) {
       console.error("Failed to detect running nodes:", error);
     }
+    try {
+      setNodeServices(await listNodeServices(homeDir));
+    } catch (error) {
+      console.error("Failed to list node services:", error);
+    }
   };
 
+  const getNodeService = (nodeName: string) => nodeServices.find((s) => s.node_name === nodeName);
+
   const handlePickHomeDir = async () => {
     try {
       const result = await invoke<string | null>('pick_directory', { 
@@ -164,8 +230,11 @@ export default function NodeManagement() {
     }
 
     setLoading(true);
+    setStartStage(null);
     try {
-      await startMerod(portToUse, swarmToUse, homeDir, selectedNode, getSettings().debugLogs);
+      await startMerodUntilHealthy(portToUse, swarmToUse, homeDir, selectedNode, getSettings().debugLogs, (progress) =>
+        setStartStage(progress.stage)
+      );
       toast.success(`Node "${selectedNode}" started successfully`);
       await detectRunning();
 
@@ -224,6 +293,33 @@ export default function NodeManagement() {
     }
   };
 
+  const handleToggleService = async () => {
+    if (!selectedNode) return;
+    const installed = !!getNodeService(selectedNode);
+    if (!installed && getRunningNodeInfo(selectedNode).running) {
+      toast.error(`Stop "${selectedNode}" before installing it as a service`);
+      return;
+    }
+    setLoading(true);
+    try {
+      if (installed) {
+        await uninstallNodeService(selectedNode, homeDir);
+        toast.success(`Service for "${selectedNode}" removed`);
+      } else {
+        const status = await installNodeService(selectedNode, homeDir);
+        toast.success(`"${selectedNode}" now runs as ${status.unit}`);
+        if (status.linger === false) {
+          toast.info("The service stops when you log out. Run `loginctl enable-linger` to keep it running.");
+        }
+      }
+      await detectRunning();
+    } catch (error: any) {
+      toast.error(`Failed to ${installed ? "remove" : "install"} the service: ${error.message || error}`);
+    } finally {
+      setLoading(false);
+    }
+  };
+
   const handleViewLogs = async () => {
     if (!selectedNode) return;
     setShowLogsModal(true);
@@ -258,6 +354,41 @@ export default function NodeManagement() {
     }
   };
 
+  const handleSaveRunSettings = async () => {
+    if (!selectedNode) return;
+    const env: Record<string, string> = {};
+    for (const line of envText.split("\n").map((l) => l.trim()).filter(Boolean)) {
+      const eq = line.indexOf("=");
+      if (eq <= 0) {
+        toast.error(`Invalid environment variable "${line}". Use KEY=VALUE.`);
+        return;
+      }
+      env[line.slice(0, eq).trim()] = line.slice(eq + 1);
+    }
+    const limit = (value: string) => (value.trim() ? Number(value) : null);
+    try {
+      await setNodeRunSettings(
+        selectedNode,
+        {
+          logFilter: logFilter.trim() || null,
+          env,
+          extraArgs: extraArgsText.split("\n").map((l) => l.trim()).filter(Boolean),
+          sandbox,
+          autostart,
+          limits: {
+            maxMemoryMb: limit(maxMemoryMb),
+            cpuPercent: limit(cpuPercent),
+            maxOpenFiles: limit(maxOpenFiles),
+          },
+        },
+        homeDir
+      );
+      toast.success(`Run settings for "${selectedNode}" saved. They apply on the next start.`);
+    } catch (error: any) {
+      toast.error(`Failed to save run settings: ${error.message || error}`);
+    }
+  };
+
   const handleSaveNodeConfig = () => {
     try {
       const settings = getSettings();
@@ -440,6 +571,11 @@ export default function NodeManagement() {
                         <span className="node-status-badge">
                           {nodeInfo.running ? `Port ${nodeInfo.port}` : 'Stopped'}
                         </span>
+                        {getNodeService(node) && (
+                          <span className="node-status-badge" title={getNodeService(node)!.unit}>
+                            Service • {getNodeService(node)!.active_state ?? 'unknown'}
+                          </span>
+                        )}
                       </div>
                     </div>
                   );
@@ -452,7 +588,7 @@ export default function NodeManagement() {
                   disabled={loading || !selectedNode || getRunningNodeInfo(selectedNode).running}
                 >
                   <Play size={16} />
-                  Start Node
+                  {loading && startStage ? `${START_STAGE_LABELS[startStage]}...` : "Start Node"}
                 </button>
                 <button
                   onClick={handleStopNode}
@@ -470,6 +606,16 @@ export default function NodeManagement() {
                   <RefreshCw size={16} />
                   Refresh
                 </button>
+                {developerMode && (
+                  <button
+                    onClick={handleToggleService}
+                    className="button button-secondary"
+                    disabled={loading || !selectedNode}
+                    title="Run the node as a systemd user service that keeps running without the app (Linux)"
+                  >
+                    {getNodeService(selectedNode) ? "Remove Service" : "Install as Service"}
+                  </button>
+                )}
                 {developerMode && (
                   <button
                     onClick={handleViewLogs}
@@ -485,6 +631,116 @@ export default function NodeManagement() {
             </div>
           )}
 
+          {developerMode && selectedNode && (
+            <div className="node-management-card">
+              <h3 className="node-card-title">Run Settings: {selectedNode}</h3>
+              <div className="form-field">
+                <label>merod version</label>
+                <p>{pinnedMerod ? pinnedMerod.version : "Bundled"}</p>
+                {pinnedMerod && !pinnedMerod.verified && (
+                  <p className="field-hint field-hint-warning">
+                    Unverified: this version was installed without a checksum.
+                  </p>
+                )}
+              </div>
+              <div className="form-field">
+                <label htmlFor="log-filter">Log filter (RUST_LOG)</label>
+                <input
+                  id="log-filter"
+                  type="text"
+                  value={logFilter}
+                  onChange={(e) => setLogFilter(e.target.value)}
+                  placeholder="calimero_node=trace,libp2p=warn"
+                />
+                <p className="field-hint">Overrides the debug logs setting for this node.</p>
+              </div>
+              <div className="form-field">
+                <label htmlFor="node-env">Environment variables</label>
+                <textarea
+                  id="node-env"
+                  rows={3}
+                  value={envText}
+                  onChange={(e) => setEnvText(e.target.value)}
+                  placeholder="KEY=VALUE, one per line"
+                />
+              </div>
+              <div className="form-field">
+                <label htmlFor="node-extra-args">Extra merod run arguments</label>
+                <textarea
+                  id="node-extra-args"
+                  rows={3}
+                  value={extraArgsText}
+                  onChange={(e) => setExtraArgsText(e.target.value)}
+                  placeholder="One argument per line (--home and --node are set by the app)"
+                />
+              </div>
+              <div className="form-field">
+                <label>Resource limits</label>
+                <div className="form-row">
+                  <input
+                    id="node-max-memory"
+                    type="number"
+                    min={128}
+                    value={maxMemoryMb}
+                    onChange={(e) => setMaxMemoryMb(e.target.value)}
+                    placeholder="Memory (MB)"
+                  />
+                  <input
+                    id="node-cpu-percent"
+                    type="number"
+                    min={1}
+                    value={cpuPercent}
+                    onChange={(e) => setCpuPercent(e.target.value)}
+                    placeholder="CPU (% of a core)"
+                  />
+                  <input
+                    id="node-max-open-files"
+                    type="number"
+                    min={256}
+                    value={maxOpenFiles}
+                    onChange={(e) => setMaxOpenFiles(e.target.value)}
+                    placeholder="Open files"
+                  />
+                </div>
+                <p className="field-hint">Leave empty for no limit. Exceeding the memory limit stops the node.</p>
+              </div>
+              <div className="form-field">
+                <label htmlFor="node-sandbox">
+                  <input
+                    id="node-sandbox"
+                    type="checkbox"
+                    checked={sandbox}
+                    onChange={(e) => setSandbox(e.target.checked)}
+                    disabled={!sandbox && !sandboxSupport?.supported}
+                  />{" "}
+                  Sandboxed launch
+                </label>
+                <p className="field-hint">
+                  Restricts merod to its node directory with resource limits. {sandboxSupport?.message}
+                </p>
+              </div>
+              <div className="form-field">
+                <label htmlFor="node-autostart">
+                  <input
+                    id="node-autostart"
+                    type="checkbox"
+                    checked={autostart}
+                    onChange={(e) => setAutostart(e.target.checked)}
+                  />{" "}
+                  Run in headless mode
+                </label>
+                <p className="field-hint">
+                  Started and restarted on crashes when the app is launched with <code>--headless</code>.
+                </p>
+              </div>
+              <div className="node-actions">
+                <button onClick={handleSaveRunSettings} className="button button-primary" disabled={loading}>
+                  Save Run Settings
+                </button>
+              </div>
+            </div>
+          )}
+
           {availableNodes.length === 0 && (
             <div className="empty-state">
               <p>No nodes found. Create your first node above.</p>
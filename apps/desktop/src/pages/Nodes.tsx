@@ -2,13 +2,15 @@ import { useState, useEffect } from "react";
 import { 
   listMerodNodes, 
   initMerodNode, 
-  startMerod, 
+  startMerodUntilHealthy,
   stopMerod, 
   stopMerodByPid,
   getMerodStatus,
   detectRunningMerodNodes,
+  START_STAGE_LABELS,
   type RunningMerodNode,
-  type MerodStatus
+  type MerodStatus,
+  type MerodStartStage
 } from "../utils/merod";
 import { invoke } from "@tauri-apps/api/tauri";
 import { getSettings, saveSettings } from "../utils/settings";
@@ -29,6 +31,7 @@ export default function Nodes({ onBack }: NodesProps) {
   const [creatingNode, setCreatingNode] = useState(false);
   const [newNodeName, setNewNodeName] = useState("");
   const [loading, setLoading] = useState(false);
+  const [startStage, setStartStage] = useState<MerodStartStage | null>(null);
   const [status, setStatus] = useState<MerodStatus>({ running: false });
   const [currentNodeUrl, setCurrentNodeUrl] = useState<string>("");
   const [serverPort, setServerPort] = useState<number>(2528);
@@ -167,8 +170,11 @@ export default function Nodes({ onBack }: NodesProps) {
     }
     
     setLoading(true);
+    setStartStage(null);
     try {
-      await startMerod(serverPort, swarmPort, homeDir, selectedNode, getSettings().debugLogs);
+      await startMerodUntilHealthy(serverPort, swarmPort, homeDir, selectedNode, getSettings().debugLogs, (progress) =>
+        setStartStage(progress.stage)
+      );
       await checkStatus();
       await detectRunning();
       toast.success(`Node '${selectedNode}' started successfully on server port ${serverPort} and swarm port ${swarmPort}`);
@@ -409,7 +415,7 @@ export default function Nodes({ onBack }: NodesProps) {
                   className="button button-primary"
                   disabled={!selectedNode || loading || getRunningNodeInfo(selectedNode || "").running}
                 >
-                  {loading ? 'Starting...' : 'Start Node'}
+                  {loading ? `${startStage ? START_STAGE_LABELS[startStage] : 'Starting'}...` : 'Start Node'}
                 </button>
                 {status.running && (
                   <p className="field-hint" style={{ marginTop: '8px' }}>
@@ -2,7 +2,15 @@ import { useState, useEffect, useRef } from "react";
 import { checkOnboardingState, getOnboardingMessage, type OnboardingState } from "../utils/onboarding";
 import { apiClient, setAccessToken, setRefreshToken } from "@calimero-network/mero-react";
 import { HTTPError } from "@calimero-network/mero-js";
-import { initMerodNode, startMerod, listMerodNodes, detectRunningMerodNodes, waitForNodeHealthy } from "../utils/merod";
+import {
+  initMerodNode,
+  startMerodUntilHealthy,
+  listMerodNodes,
+  detectRunningMerodNodes,
+  waitForNodeHealthy,
+  START_STAGE_LABELS,
+  type MerodStartStage,
+} from "../utils/merod";
 import { invoke } from "@tauri-apps/api/tauri";
 import { saveSettings, getSettings } from "../utils/settings";
 import { saveOnboardingProgress, loadOnboardingProgress } from "../utils/onboardingProgress";
@@ -261,6 +269,8 @@ export default function Onboarding({ onComplete, onSettings }: OnboardingProps)
       const [nodeError, setNodeError] = useState<string | null>(null);
       const [nodeCreated, setNodeCreated] = useState(false);
       const [nodeStarted, setNodeStarted] = useState(false);
+      // Stage of the node being started, from merod-start-progress events
+      const [startStage, setStartStage] = useState<MerodStartStage | null>(null);
       const [showAdvancedOptions, setShowAdvancedOptions] = useState(false);
       const [loginLoading, setLoginLoading] = useState(false);
   const [existingNodes, setExistingNodes] = useState<string[]>([]);
@@ -339,7 +349,9 @@ export default function Onboarding({ onComplete, onSettings }: OnboardingProps)
                 embeddedNodePort: alreadyRunning.port,
               });
             } else {
-              await startMerod(serverPort, swarmPort, dataDir, nodeToUse, getSettings().debugLogs);
+              await startMerodUntilHealthy(serverPort, swarmPort, dataDir, nodeToUse, getSettings().debugLogs, (progress) =>
+                setStartStage(progress.stage)
+              );
               saveSettings({
                 ...getSettings(),
                 nodeUrl: `http://localhost:${serverPort}`,
@@ -358,6 +370,7 @@ export default function Onboarding({ onComplete, onSettings }: OnboardingProps)
             setNodeSetupMode('choose');
           }
           setCreatingNode(false);
+          setStartStage(null);
           setLoadingExistingNodes(false);
         } else {
           setNodeSetupMode('create-new');
@@ -421,6 +434,7 @@ export default function Onboarding({ onComplete, onSettings }: OnboardingProps)
     }
 
     setCreatingNode(true);
+    setStartStage(null);
     setNodeError(null);
     
     try {
@@ -459,20 +473,20 @@ export default function Onboarding({ onComplete, onSettings }: OnboardingProps)
           return;
         }
         
-        // Start the existing node
-        await startMerod(serverPort, swarmPort, dataDir, useExistingNode, getSettings().debugLogs);
+        // Start the existing node; resolves once it is healthy
+        await startMerodUntilHealthy(serverPort, swarmPort, dataDir, useExistingNode, getSettings().debugLogs, (progress) =>
+          setStartStage(progress.stage)
+        );
         setNodeCreated(true);
         setNodeStarted(true);
-        const nodeUrl = `http://localhost:${serverPort}`;
         saveSettings({
           ...getSettings(),
-          nodeUrl,
+          nodeUrl: `http://localhost:${serverPort}`,
           useEmbeddedNode: true,
           embeddedNodeDataDir: dataDir,
           embeddedNodeName: useExistingNode,
           embeddedNodePort: serverPort,
         });
-        await waitForNodeHealthy(nodeUrl, 20000);
         advanceToLogin();
       } else {
         // Create new node
@@ -489,19 +503,19 @@ export default function Onboarding({ onComplete, onSettings }: OnboardingProps)
           return;
         }
         
-        await startMerod(serverPort, swarmPort, dataDir, targetNodeName, getSettings().debugLogs);
+        await startMerodUntilHealthy(serverPort, swarmPort, dataDir, targetNodeName, getSettings().debugLogs, (progress) =>
+          setStartStage(progress.stage)
+        );
         setNodeCreated(true);
         setNodeStarted(true);
-        const nodeUrl = `http://localhost:${serverPort}`;
         saveSettings({
           ...getSettings(),
-          nodeUrl,
+          nodeUrl: `http://localhost:${serverPort}`,
           useEmbeddedNode: true,
           embeddedNodeDataDir: dataDir,
           embeddedNodeName: targetNodeName,
           embeddedNodePort: serverPort,
         });
-        await waitForNodeHealthy(nodeUrl, 20000);
         advanceToLogin();
       }
     } catch (error: any) {
@@ -511,6 +525,7 @@ export default function Onboarding({ onComplete, onSettings }: OnboardingProps)
       setCreatingNode(false);
       setNodeCreated(false);
       setNodeStarted(false);
+      setStartStage(null);
       toast.error(`Failed to create node: ${errorMessage}`);
     }
   };
@@ -876,7 +891,7 @@ export default function Onboarding({ onComplete, onSettings }: OnboardingProps)
             {(loadingExistingNodes || (creatingNode && existingNodes.length > 0)) && (
               <p className="step-description">
                 {creatingNode && existingNodes.length > 0
-                  ? 'Using your existing node...'
+                  ? startStage ? `Using your existing node: ${START_STAGE_LABELS[startStage]}...` : 'Using your existing node...'
                   : 'Checking for existing nodes. If found, we\'ll continue automatically.'}
               </p>
             )}
@@ -961,7 +976,7 @@ export default function Onboarding({ onComplete, onSettings }: OnboardingProps)
                     className="step-button step-button-primary"
                     disabled={creatingNode || nodeCreated || !useExistingNode}
                   >
-                    {creatingNode ? 'Starting Node...' : 'Start Node & Continue'}
+                    {creatingNode ? `${startStage ? START_STAGE_LABELS[startStage] : 'Starting Node'}...` : 'Start Node & Continue'}
                     {!creatingNode && !nodeCreated && <ArrowRight size={18} />}
                   </button>
                 </div>
@@ -1075,7 +1090,7 @@ export default function Onboarding({ onComplete, onSettings }: OnboardingProps)
                   className="step-button step-button-primary"
                   disabled={creatingNode || nodeCreated || !nodeName.trim()}
                 >
-                  {creatingNode ? 'Creating Node...' : nodeCreated && nodeStarted ? 'Setting Up...' : 'Create Node & Continue'}
+                  {creatingNode ? `${startStage ? START_STAGE_LABELS[startStage] : 'Creating Node'}...` : nodeCreated && nodeStarted ? 'Setting Up...' : 'Create Node & Continue'}
                   {!creatingNode && !nodeCreated && <ArrowRight size={18} />}
                 </button>
               </div>
@@ -1,7 +1,7 @@
 import { useState, useEffect } from "react";
 import { getSettings, saveSettings, clearAllAppData } from "../utils/settings";
 import { invoke } from "@tauri-apps/api/tauri";
-import { killAllMerodProcesses, deleteCalimeroDataDir, stopMerod } from "../utils/merod";
+import { killAllMerodProcesses, deleteCalimeroDataDir, previewDeleteCalimeroDataDir, stopMerod } from "../utils/merod";
 import { useTheme } from "../contexts/ThemeContext";
 import { useToast } from "../contexts/ToastContext";
 import { ArrowLeft, RotateCcw, Trash2 } from "lucide-react";
@@ -350,8 +350,16 @@ export default function Settings({ onBack }: SettingsProps) {
                           const settingsDataDir = getSettings().embeddedNodeDataDir || "~/.calimero";
                           const defaultDataDir = "~/.calimero";
                           const dirsToDelete = [...new Set([settingsDataDir, defaultDataDir])];
+                          // Directories that don't look like a Calimero home are kept and
+                          // reported; the rest of the reset still goes ahead
+                          const keptDirs: string[] = [];
                           try {
                             for (const dir of dirsToDelete) {
+                              const preview = await previewDeleteCalimeroDataDir(dir);
+                              if ("deletable" in preview && !preview.deletable) {
+                                keptDirs.push(preview.reason ?? `${dir} was not deleted`);
+                                continue;
+                              }
                               await deleteCalimeroDataDir(dir);
                             }
                           } catch (err: unknown) {
@@ -360,7 +368,12 @@ export default function Settings({ onBack }: SettingsProps) {
                             return;
                           }
                           clearAllAppData();
-                          window.location.reload();
+                          if (keptDirs.length > 0) {
+                            toast.warning(`Reset done, but some data was kept: ${keptDirs.join("; ")}`);
+                            setTimeout(() => window.location.reload(), 5000);
+                          } else {
+                            window.location.reload();
+                          }
                         }}
                         className="button button-danger"
                         disabled={!nukeConfirmed || nuking}
@@ -1,8 +1,26 @@
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { getSettings, saveSettings } from './settings';
 
 export interface MerodStatus {
   running: boolean;
   exit_code?: number;
+  version?: string | null;
+  nodes?: { pid: number; port: number; version: string | null; limits: AppliedLimits }[];
+}
+
+export interface AppliedLimit {
+  value: number;
+  enforced_by: 'cgroup' | 'rlimit' | 'not_enforced';
+}
+
+/** Resource limits a running node was started with, and how each one is enforced */
+export interface AppliedLimits {
+  memory_mb: AppliedLimit | null;
+  cpu_percent: AppliedLimit | null;
+  open_files: AppliedLimit | null;
+  cgroup: string | null;
+  note: string | null; // why a limit isn't enforced as requested
 }
 
 export interface MerodHealth {
@@ -12,10 +30,80 @@ export interface MerodHealth {
 }
 
 /**
- * Download and extract the merod binary from GitHub release
+ * Download, verify and install a merod version into the app data directory.
+ * Without arguments, installs the latest GitHub release for this platform.
+ * `source` may be a local .tar.gz path or a URL; `sha256` is the archive checksum.
+ * Archives with no checksum available are refused unless `allowUnverified` is true.
+ */
+export async function downloadMerod(
+  version?: string,
+  source?: string,
+  sha256?: string,
+  allowUnverified?: boolean
+): Promise<string> {
+  return await invoke('download_merod', { version, source, sha256, allowUnverified });
+}
+
+export interface InstalledMerodVersion {
+  version: string;
+  binary_sha256: string;
+  archive_sha256: string;
+  verified: boolean;
+  source: string;
+  installed_at: string;
+  path: string;
+}
+
+export interface MerodInfo {
+  path: string;
+  binary_sha256: string;
+  raw: string;
+  version: string | null;
+  build: string | null;
+  supported: boolean;
+  supported_range: string;
+  warning: string | null;
+}
+
+/**
+ * Version and build info of the merod a node would run (bundled merod without nodeName),
+ * and whether this app supports it
+ */
+export async function getMerodInfo(nodeName?: string, homeDir?: string): Promise<MerodInfo> {
+  return await invoke('get_merod_info', { nodeName, homeDir });
+}
+
+export interface MerodVersions {
+  bundled: { version: 'bundled'; available: boolean; path: string | null };
+  installed: InstalledMerodVersion[];
+}
+
+/**
+ * List the bundled merod and every installed version
+ */
+export async function listMerodVersions(): Promise<MerodVersions> {
+  return await invoke('list_merod_versions');
+}
+
+/**
+ * Remove an installed merod version (must not be pinned by any node)
+ */
+export async function removeMerodVersion(version: string): Promise<string> {
+  return await invoke('remove_merod_version', { version });
+}
+
+/**
+ * The merod version a node is pinned to, or 'bundled'
  */
-export async function downloadMerod(): Promise<string> {
-  return await invoke('download_merod');
+export async function getNodeMerodVersion(nodeName: string, homeDir?: string): Promise<string> {
+  return await invoke('get_node_merod_version', { nodeName, homeDir });
+}
+
+/**
+ * Pin a node to an installed merod version. Omit version to use the bundled binary.
+ */
+export async function pinNodeMerodVersion(nodeName: string, homeDir?: string, version?: string): Promise<string> {
+  return await invoke('pin_node_merod_version', { nodeName, homeDir, version });
 }
 
 /**
@@ -25,11 +113,86 @@ export async function listMerodNodes(homeDir?: string): Promise<string[]> {
   return await invoke('list_merod_nodes', { homeDir });
 }
 
+export type MerodStartStage = 'spawned' | 'config_loaded' | 'listening' | 'healthy';
+
+/** Payload of the `merod-start-progress` event, emitted as a starting node reaches each stage */
+export interface MerodStartProgress {
+  node_name: string;
+  pid: number;
+  stage: MerodStartStage;
+  elapsed_ms: number;
+  detail: string | null;
+}
+
+/** What the UI shows while a starting node is at each stage */
+export const START_STAGE_LABELS: Record<MerodStartStage, string> = {
+  spawned: 'Starting merod',
+  config_loaded: 'Loading node config',
+  listening: 'Waiting for the node server',
+  healthy: 'Node ready',
+};
+
+/** How long the app's start flows wait for a node to answer its health check */
+export const START_HEALTH_TIMEOUT_SECS = 60;
+
+/**
+ * Call handler with every `merod-start-progress` event; resolves to a function that stops listening
+ */
+export async function onStartProgress(handler: (progress: MerodStartProgress) => void): Promise<UnlistenFn> {
+  return await listen<MerodStartProgress>('merod-start-progress', (event) => handler(event.payload));
+}
+
+export interface MerodStartResult {
+  pid: number;
+  node_name: string;
+  server_port: number;
+  swarm_port: number;
+  node_url: string;
+  admin_api_url: string;
+  version: string | null;
+  stage: MerodStartStage;
+  time_to_ready_ms: number | null; // set when waitUntilHealthy was given
+  warning: string | null;
+}
+
 /**
- * Start the embedded merod node
+ * Start the embedded merod node. With waitUntilHealthy (seconds), resolves only once the node
+ * answers its health check and rejects if it exits or the timeout passes.
  */
-export async function startMerod(serverPort?: number, swarmPort?: number, dataDir?: string, nodeName?: string, debugLogs?: boolean): Promise<string> {
-  return await invoke('start_merod', { serverPort, swarmPort, dataDir, nodeName, debugLogs });
+export async function startMerod(
+  serverPort?: number,
+  swarmPort?: number,
+  dataDir?: string,
+  nodeName?: string,
+  debugLogs?: boolean,
+  waitUntilHealthy?: number
+): Promise<MerodStartResult> {
+  return await invoke('start_merod', { serverPort, swarmPort, dataDir, nodeName, debugLogs, waitUntilHealthy });
+}
+
+/**
+ * Start a node and resolve once it is healthy, reporting each start stage of that node to
+ * onProgress. Rejects if merod exits or doesn't become healthy within timeoutSecs.
+ */
+export async function startMerodUntilHealthy(
+  serverPort: number | undefined,
+  swarmPort: number | undefined,
+  dataDir: string | undefined,
+  nodeName: string | undefined,
+  debugLogs: boolean | undefined,
+  onProgress?: (progress: MerodStartProgress) => void,
+  timeoutSecs: number = START_HEALTH_TIMEOUT_SECS
+): Promise<MerodStartResult> {
+  const unlisten = onProgress
+    ? await onStartProgress((progress) => {
+        if (!nodeName || progress.node_name === nodeName) onProgress(progress);
+      })
+    : undefined;
+  try {
+    return await startMerod(serverPort, swarmPort, dataDir, nodeName, debugLogs, timeoutSecs);
+  } finally {
+    unlisten?.();
+  }
 }
 
 /**
@@ -83,13 +246,366 @@ export interface RunningMerodNode {
   node_name: string;
   port: number; // Server port
   swarm_port?: number; // Swarm port
+  version?: string | null; // merod version, if it could be determined
+}
+
+export interface NodeInitOptions {
+  serverPort?: number;
+  serverHost?: string;
+  swarmPort?: number;
+  swarmHost?: string;
+  bootNodes?: string[]; // multiaddrs, e.g. /ip4/1.2.3.4/tcp/2428/p2p/<peer id>
+  bootNetwork?: 'calimero-dev' | 'ipfs';
+  mdns?: boolean;
+  relayRegistrationsLimit?: number;
+  authMode?: 'embedded' | 'proxy';
+  protocol?: string;
+  extraArgs?: string[];
+  timeoutSecs?: number;
+}
+
+/** Payload of the `merod-init-progress` event emitted while a node initializes */
+export interface NodeInitProgress {
+  nodeName: string;
+  stream: 'stdout' | 'stderr';
+  line: string;
+}
+
+/**
+ * Initialize/create a new merod node. Listen for `merod-init-progress` to show its output.
+ * A failed init removes the partially created node directory.
+ */
+export async function initMerodNode(
+  nodeName: string,
+  homeDir?: string,
+  merodVersion?: string,
+  options?: NodeInitOptions
+): Promise<string> {
+  return await invoke('init_merod_node', { nodeName, homeDir, merodVersion, options });
+}
+
+/** Per-node settings applied by startMerod. Saved in the node registry. */
+export interface NodeRunSettings {
+  logFilter?: string | null; // RUST_LOG directives, e.g. calimero_node=trace,libp2p=warn (overrides debugLogs)
+  env: Record<string, string>; // RUST_LOG is not allowed here, use logFilter
+  extraArgs: string[]; // appended after `merod ... run`; --home and --node are reserved
+  sandbox?: boolean; // hardened launch mode (Linux only, see getSandboxSupport)
+  limits?: NodeResourceLimits;
+  autostart?: boolean; // started and supervised when the app runs with --headless
+}
+
+/** Optional per-node limits. Memory and CPU use cgroups v2 on Linux when delegated. */
+export interface NodeResourceLimits {
+  maxMemoryMb?: number | null;
+  cpuPercent?: number | null; // percent of one core; 200 = two cores
+  maxOpenFiles?: number | null;
+}
+
+export interface SandboxSupport {
+  supported: boolean;
+  backend: 'landlock' | 'bubblewrap' | null;
+  landlock_abi: number | null;
+  bubblewrap: string | null;
+  message: string;
+}
+
+/**
+ * Whether this machine can start sandboxed nodes (Landlock or bubblewrap on Linux).
+ */
+export async function getSandboxSupport(): Promise<SandboxSupport> {
+  return await invoke('get_sandbox_support');
+}
+
+export async function getNodeRunSettings(nodeName: string, homeDir?: string): Promise<NodeRunSettings> {
+  return await invoke('get_node_run_settings', { nodeName, homeDir });
+}
+
+/**
+ * Validate and save a node's run settings. They apply the next time the node starts.
+ */
+export async function setNodeRunSettings(
+  nodeName: string,
+  settings: NodeRunSettings,
+  homeDir?: string
+): Promise<NodeRunSettings> {
+  return await invoke('set_node_run_settings', { nodeName, homeDir, settings });
+}
+
+export interface NodeServiceStatus {
+  node_name: string;
+  home_dir: string;
+  unit: string; // e.g. calimero-node-node1.service
+  unit_path: string;
+  installed: boolean;
+  enabled: boolean;
+  active_state: string | null; // active, inactive, failed, activating, ...
+  sub_state: string | null;
+  main_pid: number | null;
+  restarts: number | null;
+  exit_status: number | null;
+  active_since: string | null;
+  linger: boolean | null; // false: the service stops when the user logs out
+}
+
+/**
+ * Run a node as a `systemd --user` service (Linux) so it keeps running without the app.
+ * Uses the node's run settings at install time; reinstall after changing them.
+ */
+export async function installNodeService(nodeName: string, homeDir?: string, start?: boolean): Promise<NodeServiceStatus> {
+  return await invoke('install_node_service', { nodeName, homeDir, start });
+}
+
+export async function uninstallNodeService(nodeName: string, homeDir?: string): Promise<NodeServiceStatus> {
+  return await invoke('uninstall_node_service', { nodeName, homeDir });
+}
+
+export async function getNodeServiceStatus(nodeName: string, homeDir?: string): Promise<NodeServiceStatus> {
+  return await invoke('get_node_service_status', { nodeName, homeDir });
+}
+
+/** Nodes in homeDir installed as systemd user services (empty where systemd isn't available) */
+export async function listNodeServices(homeDir?: string): Promise<NodeServiceStatus[]> {
+  return await invoke('list_node_services', { homeDir });
+}
+
+export interface NodeLogLevelResult {
+  node_name: string;
+  filter: string;
+  method: 'restart' | 'saved'; // saved: the node isn't running
+  pid?: number;
+  previous_pid?: number; // set after a restart
+  time_to_ready_ms?: number | null;
+}
+
+/**
+ * Change a node's log filter. A running node is restarted gracefully on the same ports, as
+ * merod only reads its filter at startup. The filter is also saved as the node's logFilter
+ * run setting.
+ */
+export async function setNodeLogLevel(
+  nodeName: string,
+  filter: string,
+  homeDir?: string
+): Promise<NodeLogLevelResult> {
+  return await invoke('set_node_log_level', { nodeName, filter, homeDir });
+}
+
+/**
+ * Delete a single node directory (<home>/<node>). Fails while the node is running.
+ */
+export async function deleteNode(nodeName: string, homeDir?: string): Promise<string> {
+  return await invoke('delete_node', { nodeName, homeDir });
 }
 
 /**
- * Initialize/create a new merod node
+ * Rename a node, updating config paths and its merod version pin. Fails while the node is running.
  */
-export async function initMerodNode(nodeName: string, homeDir?: string): Promise<string> {
-  return await invoke('init_merod_node', { nodeName, homeDir });
+export async function renameNode(nodeName: string, newName: string, homeDir?: string): Promise<string> {
+  return await invoke('rename_node', { nodeName, newName, homeDir });
+}
+
+/**
+ * Copy a node (without logs) under a new name. The clone gets new identity keys unless
+ * regenerateIdentity is false.
+ */
+export async function cloneNode(
+  nodeName: string,
+  newName: string,
+  homeDir?: string,
+  regenerateIdentity?: boolean
+): Promise<string> {
+  return await invoke('clone_node', { nodeName, newName, homeDir, regenerateIdentity });
+}
+
+export interface NodeExportResult {
+  path: string;
+  size: number;
+  files: number;
+  merod_version: string | null;
+  exported_at: string;
+}
+
+export interface NodeImportResult {
+  node_name: string;
+  files: number;
+  merod_version: string | null;
+  exported_at: string;
+  warnings: string[];
+}
+
+/**
+ * Archive a stopped node (config, data, auth store) to dest, a file path or a directory.
+ * Logs are left out unless includeLogs is true.
+ */
+export async function exportNode(
+  nodeName: string,
+  dest: string,
+  homeDir?: string,
+  includeLogs?: boolean
+): Promise<NodeExportResult> {
+  return await invoke('export_node', { nodeName, dest, homeDir, includeLogs });
+}
+
+/**
+ * Verify and restore a node archive, optionally under a new name
+ */
+export async function importNode(archive: string, nodeName?: string, homeDir?: string): Promise<NodeImportResult> {
+  return await invoke('import_node', { archive, nodeName, homeDir });
+}
+
+export interface LocalClusterNode {
+  name: string;
+  server_port: number;
+  swarm_port: number;
+  peer_id: string;
+  url: string;
+  pid: number | null;
+  healthy: boolean;
+  error: string | null;
+}
+
+export interface LocalCluster {
+  name_prefix: string;
+  home: string;
+  healthy: boolean;
+  nodes: LocalClusterNode[];
+}
+
+/**
+ * Create `count` nodes (<namePrefix>-1 … <namePrefix>-<count>, default prefix "node") on free
+ * ports counting up from the base ports, bootstrap them from each other over localhost, start
+ * them and report their health
+ */
+export async function createLocalCluster(
+  count: number,
+  options?: { baseServerPort?: number; baseSwarmPort?: number; namePrefix?: string; homeDir?: string; merodVersion?: string }
+): Promise<LocalCluster> {
+  return await invoke('create_local_cluster', { count, ...options });
+}
+
+/**
+ * Stop a local cluster's nodes and delete them (unless keepNodes is true)
+ */
+export async function teardownLocalCluster(
+  namePrefix: string,
+  homeDir?: string,
+  keepNodes?: boolean
+): Promise<{ name_prefix: string; nodes: string[]; stopped_pids: number[]; removed: boolean }> {
+  return await invoke('teardown_local_cluster', { namePrefix, homeDir, keepNodes });
+}
+
+export interface NodeStorage {
+  name: string;
+  data: number;
+  logs: number;
+  auth: number;
+  blobs: number;
+  other: number;
+  total: number;
+}
+
+export interface StorageReport {
+  home: string;
+  nodes: NodeStorage[];
+  total: number;
+  filesystem: { total: number; available: number } | null;
+  min_free_bytes: number;
+  low_space: boolean;
+}
+
+export interface StorageSettings {
+  min_free_bytes: number;
+  block_start: boolean; // refuse to start nodes below the threshold (default: only warn)
+}
+
+/**
+ * Payload of the `storage-low` event, emitted when a running node's free disk space drops below
+ * twice the threshold, and again (with `critical`) when it drops below the threshold
+ */
+export interface LowSpaceWarning {
+  home: string;
+  available: number;
+  min_free_bytes: number;
+  critical: boolean;
+  nodes: string[];
+}
+
+/**
+ * Per-node disk usage (data, logs, auth, blobs) and free space on the home's filesystem
+ */
+export async function getStorageReport(homeDir?: string): Promise<StorageReport> {
+  return await invoke('get_storage_report', { homeDir });
+}
+
+export async function getStorageSettings(): Promise<StorageSettings> {
+  return await invoke('get_storage_settings');
+}
+
+export async function setStorageSettings(settings: StorageSettings): Promise<StorageSettings> {
+  return await invoke('set_storage_settings', { settings });
+}
+
+export type MerodFailureKind =
+  | 'address_in_use'
+  | 'database_locked'
+  | 'corrupt_config'
+  | 'missing_bootstrap'
+  | 'permission_denied'
+  | 'disk_full'
+  | 'panic'
+  | 'signal'
+  | 'unknown';
+
+/** Why a node exited abnormally. Also the payload of the `merod-crashed` event. */
+export interface CrashDiagnosis {
+  node_name: string;
+  pid: number;
+  exit_code: number | null;
+  signal: number | null;
+  signal_name: string | null;
+  kind: MerodFailureKind;
+  summary: string;
+  suggestion: string;
+  matched_line: string | null;
+  log_path: string;
+  log_tail: string[];
+  occurred_at: string;
+}
+
+/**
+ * Diagnosis of the node's last abnormal exit since the app started, or null
+ */
+export async function getCrashDiagnosis(nodeName: string, homeDir?: string): Promise<CrashDiagnosis | null> {
+  return await invoke('get_crash_diagnosis', { nodeName, homeDir });
+}
+
+export interface SupportBundleManifest {
+  created_at: string;
+  app_version: string;
+  system: { os: string; os_version: string | null; arch: string; family: string };
+  home: string;
+  entries: { path: string; size: number; description: string }[];
+  redactions: number;
+  notes: string[];
+}
+
+/**
+ * Collect a support bundle for review without writing anything. Pass preview_id to
+ * createSupportBundle to export exactly this bundle.
+ */
+export async function previewSupportBundle(homeDir?: string): Promise<{ preview_id: string; manifest: SupportBundleManifest }> {
+  return await invoke('preview_support_bundle', { homeDir });
+}
+
+/**
+ * Write the previewed support bundle (node logs, redacted configs, versions, OS info, backend
+ * log, proxy errors) to dest, a new file path or a directory. Each preview exports once.
+ */
+export async function createSupportBundle(
+  previewId: string,
+  dest: string
+): Promise<{ path: string; size: number; manifest: SupportBundleManifest }> {
+  return await invoke('create_support_bundle', { previewId, dest });
 }
 
 /**
@@ -117,11 +633,101 @@ export async function killAllMerodProcesses(): Promise<string> {
   return await invoke('kill_all_merod_processes');
 }
 
+/** Payload of the `home-migration-progress` event emitted while migrateHome copies files */
+export interface HomeMigrationProgress {
+  copied_bytes: number;
+  total_bytes: number;
+  files_done: number;
+  total_files: number;
+  current: string;
+}
+
+export interface HomeMigrationResult {
+  from: string;
+  to: string;
+  nodes: string[];
+  files: number;
+  bytes: number;
+  stopped_pids: number[];
+}
+
+/**
+ * Move the Calimero home to a new location. Running nodes in it are stopped, every file is
+ * verified after copying and node configs are rewritten. The backend saves `to` as the home
+ * before returning; the old home is kept until removeMigratedHome is confirmed.
+ */
+export async function migrateHome(from: string | undefined, to: string): Promise<HomeMigrationResult> {
+  const result: HomeMigrationResult = await invoke('migrate_home', { from, to });
+  saveSettings({ ...getSettings(), embeddedNodeDataDir: to });
+  return result;
+}
+
+/**
+ * Remove the old home after migrateHome. Refused unless `to` is the saved home and holds
+ * every node of `from`.
+ */
+export async function removeMigratedHome(from: string, to: string): Promise<void> {
+  await invoke('remove_migrated_home', { from, to });
+}
+
+/** The home saved by the last completed migration, if any */
+export async function getSavedHome(): Promise<string | null> {
+  return await invoke('get_saved_home');
+}
+
+/**
+ * Point the embedded node data directory at the saved home, in case the app closed after
+ * a migration but before the setting was stored.
+ */
+export async function syncSavedHome(): Promise<void> {
+  try {
+    const home = await getSavedHome();
+    if (home && getSettings().embeddedNodeDataDir !== home) {
+      saveSettings({ ...getSettings(), embeddedNodeDataDir: home });
+    }
+  } catch (error) {
+    console.warn('Failed to read the saved home:', error);
+  }
+}
+
+export interface NodeUsage {
+  name: string;
+  size: number;
+}
+
+/** Result of a dry run: what deleteCalimeroDataDir would remove, and whether it is allowed */
+export interface DataDirReport {
+  path: string;
+  has_marker: boolean;
+  nodes: NodeUsage[];
+  other_entries: string[];
+  total_size: number;
+  deletable: boolean;
+  reason: string | null;
+}
+
+export interface DataDirDeletion {
+  path: string;
+  existed: boolean;
+  trashed?: boolean;
+  nodes?: NodeUsage[];
+  total_size?: number;
+  message: string;
+}
+
 /**
  * Delete the Calimero data directory and all its contents (total nuke).
- * Path must be under the user's home directory.
- * Call killAllMerodProcesses() first.
+ * Path must be under the user's home directory and look like a Calimero home
+ * (marker file, or only node directories). Call killAllMerodProcesses() first.
+ * Pass useTrash to move it to the OS trash instead of deleting it permanently.
+ */
+export async function deleteCalimeroDataDir(dataDir: string, useTrash?: boolean): Promise<DataDirDeletion> {
+  return await invoke('delete_calimero_data_dir', { dataDir, useTrash });
+}
+
+/**
+ * Report the nodes and sizes deleteCalimeroDataDir would remove, without deleting anything
  */
-export async function deleteCalimeroDataDir(dataDir: string): Promise<string> {
-  return await invoke('delete_calimero_data_dir', { dataDir });
+export async function previewDeleteCalimeroDataDir(dataDir: string): Promise<DataDirReport | DataDirDeletion> {
+  return await invoke('delete_calimero_data_dir', { dataDir, dryRun: true });
 }